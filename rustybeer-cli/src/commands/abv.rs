@@ -1,4 +1,9 @@
-use rustybeer::calculators::abv::{calculate_abv, calculate_fg};
+use crate::output::{Output, Report};
+use crate::units::UnitSystem;
+use anyhow::Result;
+use rustybeer::calculators::abv::{abv, fg};
+use rustybeer::units::{Abv, Gravity};
+use serde::Serialize;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -7,23 +12,47 @@ use structopt::StructOpt;
 pub struct AbvOptions {
     #[structopt(short, long)]
     /// Original gravity
-    og: f32,
+    og: f64,
 
     #[structopt(short, long, required_unless("abv"))]
     /// Final gravity
-    fg: Option<f32>,
+    fg: Option<f64>,
 
     #[structopt(short, long, required_unless("fg"))]
     /// Alcohol by volume
-    abv: Option<f32>,
+    abv: Option<f64>,
 }
 
-pub fn calculate_and_print(abv_options: AbvOptions) {
-    if let Some(fg) = abv_options.fg {
-        println!("ABV: {:.3}%", calculate_abv(abv_options.og, fg));
-    }
+#[derive(Debug, Serialize)]
+pub struct AbvReport {
+    og: f64,
+    fg: f64,
+    abv_percent: f64,
+}
 
-    if let Some(abv) = abv_options.abv {
-        println!("ABV: {:.3}%", calculate_fg(abv_options.og, abv));
+impl Report for AbvReport {
+    fn print_table(&self, _units: UnitSystem) {
+        println!("ABV: {:.3}%", self.abv_percent);
+        println!("FG: {:.3}", self.fg);
     }
 }
+
+pub fn calculate_and_print(abv_options: AbvOptions, output: Output) -> Result<()> {
+    let og = abv_options.og;
+    let gravity = Gravity::from_specific_gravity;
+    let report = match (abv_options.fg, abv_options.abv) {
+        (Some(final_gravity), _) => AbvReport {
+            og,
+            fg: final_gravity,
+            abv_percent: abv(gravity(og), gravity(final_gravity))?.as_percent(),
+        },
+        (None, Some(abv_percent)) => AbvReport {
+            og,
+            fg: fg(gravity(og), Abv::from_percent(abv_percent))?.as_specific_gravity(),
+            abv_percent,
+        },
+        (None, None) => unreachable!(),
+    };
+
+    output.print(&report)
+}
@@ -1,8 +1,15 @@
+use crate::output::{Output, Report};
+use crate::units::UnitSystem;
+use anyhow::Result;
 pub use rustybeer::calculators::alcohol_volume_weight::{
     calculate_abv_abw, calculate_abv_abw_density, calculate_abw_abv, calculate_abw_abv_density,
     calculate_alc_vol, calculate_alc_weight,
 };
-use rustybeer_util::{conversions::VolumeParser, measurements::Volume};
+use rustybeer_util::{
+    conversions::VolumeParser,
+    measurements::{Mass, Volume},
+};
+use serde::Serialize;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -29,36 +36,63 @@ pub struct AbvAbwOptions {
     reverse: Option<bool>,
 }
 
-pub fn calculate_and_print(abv_abw: AbvAbwOptions) {
-    let end_percentage: f64;
+#[derive(Debug, Serialize)]
+pub struct AbvAbwReport {
+    abv_percent: f64,
+    abw_percent: f64,
+    volume_ml: Option<f64>,
+    alcohol_ml: Option<f64>,
+    alcohol_g: Option<f64>,
+    #[serde(skip)]
+    reverse: bool,
+}
+
+impl Report for AbvAbwReport {
+    fn print_table(&self, units: UnitSystem) {
+        if self.reverse {
+            println!("ABV: {:.3}%", self.abv_percent);
+            if let Some(alcohol) = self.alcohol_ml {
+                println!(
+                    "Alcohol: {}",
+                    units.volume(Volume::from_milliliters(alcohol))
+                );
+            }
+        } else {
+            println!("ABW: {:.3}%", self.abw_percent);
+            if let Some(alcohol) = self.alcohol_g {
+                println!("Alcohol: {}", units.mass(Mass::from_grams(alcohol)));
+            }
+        }
+    }
+}
+
+pub fn calculate_and_print(abv_abw: AbvAbwOptions, output: Output) -> Result<()> {
+    let reverse = Some(true) == abv_abw.reverse;
     // main ABV <-> ABW conversion
-    if Some(true) == abv_abw.reverse {
-        end_percentage = match abv_abw.density {
+    let (abv, abw) = if reverse {
+        let abv = match abv_abw.density {
             Some(density) => calculate_abw_abv_density(abv_abw.percent, density),
             None => calculate_abw_abv(abv_abw.percent),
         };
-
-        println!("ABV: {:.3}%", end_percentage);
+        (abv, abv_abw.percent)
     } else {
-        end_percentage = match abv_abw.density {
-            Some(density) => calculate_abv_abw_density(abv_abw.percent, density),
+        let abw = match abv_abw.density {
+            Some(density) => calculate_abv_abw_density(abv_abw.percent, density)?,
             None => calculate_abv_abw(abv_abw.percent),
         };
-        println!("ABW: {:.3}%", end_percentage);
-    }
+        (abv_abw.percent, abw)
+    };
 
     // Quantity of alcohol
-    if let Some(volume) = abv_abw.volume {
-        if Some(true) == abv_abw.reverse {
-            println!(
-                "Alcohol: {:.3} ml",
-                calculate_alc_vol(volume.as_millilitres(), end_percentage)
-            );
-        } else {
-            println!(
-                "Alcohol: {:.3} g",
-                calculate_alc_weight(volume.as_millilitres(), abv_abw.percent)
-            );
-        }
-    }
+    let volume = abv_abw.volume.map(|volume| volume.as_millilitres());
+    let report = AbvAbwReport {
+        abv_percent: abv,
+        abw_percent: abw,
+        volume_ml: volume,
+        alcohol_ml: volume.map(|volume| calculate_alc_vol(volume, abv)),
+        alcohol_g: volume.map(|volume| calculate_alc_weight(volume, abv)),
+        reverse,
+    };
+
+    output.print(&report)
 }
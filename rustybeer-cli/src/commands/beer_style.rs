@@ -1,62 +1,350 @@
-pub use rustybeer_util::beer_styles::{BeerStyle, Criteria, BEER_STYLES};
+use crate::output::{Output, Report};
+use crate::units::UnitSystem;
+use anyhow::{anyhow, Result};
+pub use rustybeer_util::beer_styles::{
+    find_style, styles_by_code, BeerStyle, Conformance, Criteria, Parameter, ParameterConformance,
+    StyleScore, BEER_STYLES,
+};
+use serde::Serialize;
+use std::fmt::Display;
+use std::io;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "beer_style", author = "Heikki Hellgren (heiccih@gmail.com)")]
-/// Finds matches of beer style based on parameters
+/// Ranks beer styles by how close they are to the given parameters, compares a beer against a style or looks up a style by its BJCP code
 pub struct BeerStyleOptions {
     #[structopt(short, long)]
     /// Original gravity
-    og: f32,
+    og: Option<f32>,
 
     #[structopt(short, long)]
     /// Final gravity
-    fg: f32,
+    fg: Option<f32>,
 
     #[structopt(short, long)]
     /// Alcohol by volume
-    abv: f32,
+    abv: Option<f32>,
 
     #[structopt(short, long)]
     /// International Bittering Units
-    ibu: u8,
+    ibu: Option<u8>,
 
     #[structopt(short, long)]
     /// Standard Reference Model Color
-    color: f32,
+    color: Option<f32>,
+
+    #[structopt(long, conflicts_with_all = &["og", "fg", "abv", "ibu", "color", "style"])]
+    /// BJCP style code (e.g. 21A) or category number (e.g. 21) to look up
+    code: Option<String>,
+
+    #[structopt(short = "n", long, default_value = "5")]
+    /// Number of closest styles to print
+    top: usize,
+
+    #[structopt(short, long)]
+    /// Target style name or code (e.g. 'American IPA', 21A) to compare the given parameters against
+    style: Option<String>,
 }
 
-pub fn calculate_and_print(beer_style_options: BeerStyleOptions) {
-    let criteria = Criteria {
-        og: Some(beer_style_options.og),
-        fg: Some(beer_style_options.fg),
-        abv: Some(beer_style_options.abv),
-        ibu: Some(beer_style_options.ibu),
-        srm: Some(beer_style_options.color),
-    };
-    let mut resp: Vec<&BeerStyle> = Vec::new();
+/// Beer style as a flat CSV record
+#[derive(Debug, Serialize)]
+struct BeerStyleRow<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    distance: Option<f32>,
+    code: &'a str,
+    name: &'a str,
+    category: &'a str,
+    parent: Option<&'a str>,
+    guide_year: u16,
+    original_gravity_min: Option<f32>,
+    original_gravity_max: Option<f32>,
+    final_gravity_min: Option<f32>,
+    final_gravity_max: Option<f32>,
+    abv_min: Option<f32>,
+    abv_max: Option<f32>,
+    ibu_min: Option<u8>,
+    ibu_max: Option<u8>,
+    color_srm_min: Option<f32>,
+    color_srm_max: Option<f32>,
+    description: &'a str,
+    aroma: &'a str,
+    appearance: &'a str,
+    flavor: &'a str,
+    mouthfeel: &'a str,
+    commercial_examples: String,
+    tags: String,
+}
 
-    for style in BEER_STYLES.iter() {
-        if criteria.matches(style) {
-            resp.push(style)
+impl<'a> From<&'a BeerStyle> for BeerStyleRow<'a> {
+    fn from(style: &'a BeerStyle) -> Self {
+        Self {
+            distance: None,
+            code: &style.code,
+            name: &style.name,
+            category: &style.category,
+            parent: style.parent.as_deref(),
+            guide_year: style.guide_year,
+            original_gravity_min: style.original_gravity_min,
+            original_gravity_max: style.original_gravity_max,
+            final_gravity_min: style.final_gravity_min,
+            final_gravity_max: style.final_gravity_max,
+            abv_min: style.abv_min,
+            abv_max: style.abv_max,
+            ibu_min: style.ibu_min,
+            ibu_max: style.ibu_max,
+            color_srm_min: style.color_srm_min,
+            color_srm_max: style.color_srm_max,
+            description: &style.description,
+            aroma: &style.aroma,
+            appearance: &style.appearance,
+            flavor: &style.flavor,
+            mouthfeel: &style.mouthfeel,
+            commercial_examples: style.commercial_examples.join("; "),
+            tags: style.tags.join(", "),
         }
     }
+}
 
-    if resp.is_empty() {
-        println!("Could not find any beer styles matching criteria");
-        return;
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub struct BeerStyleReport<'a> {
+    styles: Vec<&'a BeerStyle>,
+}
+
+/// Beer styles closest to the given parameters
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub struct RankingReport<'a> {
+    styles: Vec<StyleScore<'a>>,
+}
+
+// Formats a style statistic, which varies with the base style when missing
+fn range<T: Display>(min: Option<T>, max: Option<T>, unit: &str) -> String {
+    match (min, max) {
+        (Some(min), Some(max)) => format!("{}{}-{}{}", min, unit, max, unit),
+        _ => "varies with base style".to_owned(),
     }
+}
 
-    println!("Found the following beer styles with criteria:");
-    for x in &resp {
+// Prints a style with its statistics, and the full style guide entry if detailed
+fn print_style(x: &BeerStyle, detailed: bool) {
+    println!("{}\n", x.description);
+    if detailed {
+        println!("Category: {}", x.category);
+        println!("Aroma: {}", x.aroma);
+        println!("Appearance: {}", x.appearance);
+        println!("Flavor: {}", x.flavor);
+        println!("Mouthfeel: {}\n", x.mouthfeel);
+    }
+    println!(
+        "OG: {}",
+        range(x.original_gravity_min, x.original_gravity_max, "")
+    );
+    println!(
+        "FG: {}",
+        range(x.final_gravity_min, x.final_gravity_max, "")
+    );
+    println!("ABV: {}", range(x.abv_min, x.abv_max, "%"));
+    println!("IBU: {}", range(x.ibu_min, x.ibu_max, ""));
+    println!("SRM: {}", range(x.color_srm_min, x.color_srm_max, ""));
+    if detailed {
+        println!(
+            "\nCommercial examples: {}",
+            x.commercial_examples.join(", ")
+        );
+        println!("Tags: {}", x.tags.join(", "));
+    }
+}
+
+// Name of a style with its code and the style it belongs to
+fn style_title(x: &BeerStyle) -> String {
+    match &x.parent {
+        Some(parent) => format!("{} {} ({})", x.code, x.name, parent),
+        None => format!("{} {}", x.code, x.name),
+    }
+}
+
+impl Report for BeerStyleReport<'_> {
+    fn print_table(&self, _units: UnitSystem) {
+        for x in &self.styles {
+            println!("---------------------");
+            println!("{}\n", style_title(x));
+            print_style(x, true);
+        }
+        println!("---------------------");
+    }
+
+    fn write_csv<W: io::Write>(&self, writer: &mut csv::Writer<W>) -> csv::Result<()> {
+        for style in &self.styles {
+            writer.serialize(BeerStyleRow::from(*style))?;
+        }
+        Ok(())
+    }
+}
+
+impl Report for RankingReport<'_> {
+    fn print_table(&self, _units: UnitSystem) {
+        if self.styles.is_empty() {
+            println!("Could not find any beer styles matching criteria");
+            return;
+        }
+
+        println!("Closest beer styles to criteria:");
+        for score in &self.styles {
+            println!("---------------------");
+            if score.distance == 0.0 {
+                println!("{} - matches all criteria\n", style_title(score.style));
+            } else {
+                println!(
+                    "{} - distance {:.2}\n",
+                    style_title(score.style),
+                    score.distance
+                );
+            }
+            print_style(score.style, false);
+        }
         println!("---------------------");
-        println!("{}\n", x.name);
-        println!("{}\n", x.description);
-        println!("OG: {}-{}", x.original_gravity_min, x.original_gravity_max);
-        println!("FG: {}-{}", x.final_gravity_min, x.final_gravity_max);
-        println!("ABV: {}%-{}%", x.abv_min, x.abv_max);
-        println!("IBU: {}-{}", x.ibu_min, x.ibu_max);
-        println!("SRM: {}-{}", x.color_srm_min, x.color_srm_max);
-    }
-    println!("---------------------");
+    }
+
+    fn write_csv<W: io::Write>(&self, writer: &mut csv::Writer<W>) -> csv::Result<()> {
+        for score in &self.styles {
+            writer.serialize(BeerStyleRow {
+                distance: Some(score.distance),
+                ..BeerStyleRow::from(score.style)
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Comparison of a beer against a target style
+#[derive(Debug, Serialize)]
+pub struct ConformanceReport<'a> {
+    style: &'a str,
+    code: &'a str,
+    parameters: Vec<ParameterConformance>,
+}
+
+/// Parameter comparison as a flat CSV record
+#[derive(Debug, Serialize)]
+struct ConformanceRow<'a> {
+    style: &'a str,
+    code: &'a str,
+    parameter: Parameter,
+    value: f32,
+    min: Option<f32>,
+    max: Option<f32>,
+    conformance: Conformance,
+    distance: f32,
+}
+
+// Formats a parameter value with the precision it is usually given in
+fn parameter_value(parameter: Parameter, value: f32) -> String {
+    match parameter {
+        Parameter::Og | Parameter::Fg => format!("{:.3}", value),
+        Parameter::Abv => format!("{:.1}%", value),
+        Parameter::Ibu => format!("{:.0}", value),
+        Parameter::Srm => format!("{:.1}", value),
+    }
+}
+
+impl Report for ConformanceReport<'_> {
+    fn print_table(&self, _units: UnitSystem) {
+        println!("Style: {} {}", self.code, self.style);
+        println!("==================================================");
+        println!(
+            "| {:>5} | {:>7} | {:>15} | {:>12} |",
+            "", "Value", "Style range", "Result"
+        );
+        println!("==================================================");
+        for row in &self.parameters {
+            let range = match (row.min, row.max) {
+                (Some(min), Some(max)) => format!(
+                    "{}-{}",
+                    parameter_value(row.parameter, min),
+                    parameter_value(row.parameter, max)
+                ),
+                _ => "varies".to_owned(),
+            };
+            let distance = parameter_value(row.parameter, row.distance);
+            let result = match row.conformance {
+                Conformance::Low => format!("low by {}", distance),
+                Conformance::InRange => "in range".to_owned(),
+                Conformance::High => format!("high by {}", distance),
+                Conformance::Unknown => "unknown".to_owned(),
+            };
+            println!(
+                "| {:>5} | {:>7} | {:>15} | {:>12} |",
+                row.parameter.to_string(),
+                parameter_value(row.parameter, row.value),
+                range,
+                result
+            );
+        }
+        println!("==================================================");
+    }
+
+    fn write_csv<W: io::Write>(&self, writer: &mut csv::Writer<W>) -> csv::Result<()> {
+        self.parameters.iter().try_for_each(|row| {
+            writer.serialize(ConformanceRow {
+                style: self.style,
+                code: self.code,
+                parameter: row.parameter,
+                value: row.value,
+                min: row.min,
+                max: row.max,
+                conformance: row.conformance,
+                distance: row.distance,
+            })
+        })
+    }
+}
+
+pub fn calculate_and_print(beer_style_options: BeerStyleOptions, output: Output) -> Result<()> {
+    if let Some(code) = &beer_style_options.code {
+        let styles = styles_by_code(code);
+        if styles.is_empty() {
+            return Err(anyhow!("Could not find beer style with code '{}'", code));
+        }
+        return output.print(&BeerStyleReport { styles });
+    }
+
+    let criteria = Criteria {
+        og: beer_style_options.og,
+        fg: beer_style_options.fg,
+        abv: beer_style_options.abv,
+        ibu: beer_style_options.ibu,
+        srm: beer_style_options.color,
+    };
+
+    if let Some(name) = &beer_style_options.style {
+        let style = find_style(name).ok_or_else(|| {
+            anyhow!(
+                "Could not find a single beer style with name or code '{}'",
+                name
+            )
+        })?;
+        let parameters = criteria.conformance(style);
+        if parameters.is_empty() {
+            return Err(anyhow!(
+                "Give at least one of --og, --fg, --abv, --ibu or --color to compare"
+            ));
+        }
+        return output.print(&ConformanceReport {
+            style: &style.name,
+            code: &style.code,
+            parameters,
+        });
+    }
+    if criteria.values().is_empty() {
+        return Err(anyhow!(
+            "Give at least one of --og, --fg, --abv, --ibu or --color, or a style --code"
+        ));
+    }
+
+    let mut styles = criteria.rank(&BEER_STYLES);
+    styles.truncate(beer_style_options.top);
+
+    output.print(&RankingReport { styles })
 }
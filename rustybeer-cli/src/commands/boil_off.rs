@@ -1,51 +1,104 @@
-use rustybeer::calculators::diluting::{calculate_new_gravity, calculate_new_volume};
+use crate::output::{Output, Report};
+use crate::units::UnitSystem;
+use anyhow::Result;
+use rustybeer::calculators::diluting::{new_gravity, new_volume};
+use rustybeer::units::{Gravity, Volume};
+use rustybeer_util::conversions::VolumeParser;
+use serde::Serialize;
 use structopt::{clap::ArgGroup, StructOpt};
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "boil_off", group = ArgGroup::with_name("desired").required(true))]
 /// Calculates how much you need to dilute or boil down your wort volume to hit a certain gravity
 pub struct BoilOffOptions {
-    #[structopt(short, long)]
-    /// Wort Volume
-    wort_volume: f32,
+    #[structopt(short, long, parse(try_from_str = VolumeParser::parse))]
+    /// Wort Volume with unit (l, ml, gal, etc.). Defaults to liters.
+    wort_volume: Volume,
 
     #[structopt(short, long)]
     /// Current Gravity
-    current_gravity: f32,
+    current_gravity: f64,
 
     #[structopt(short, long, group = "desired")]
     /// Desired Gravity
-    desired_gravity: Option<f32>,
+    desired_gravity: Option<f64>,
 
-    #[structopt(short, long, group = "desired")]
-    /// Target Volume
-    target_volume: Option<f32>,
+    #[structopt(short, long, group = "desired", parse(try_from_str = VolumeParser::parse))]
+    /// Target Volume with unit (l, ml, gal, etc.). Defaults to liters.
+    target_volume: Option<Volume>,
 }
 
-pub fn calculate_and_print(boil_off_options: BoilOffOptions) {
-    println!("Wort Volume: {} l", boil_off_options.wort_volume);
-    println!("Current Gravity: {}", boil_off_options.current_gravity);
+#[derive(Debug, Serialize)]
+pub struct BoilOffReport {
+    wort_volume_l: f64,
+    current_gravity: Gravity,
+    new_volume_l: Option<f64>,
+    volume_difference_l: Option<f64>,
+    new_gravity: Option<Gravity>,
+    gravity_difference: Option<f64>,
+}
 
-    if let Some(desired_gravity) = boil_off_options.desired_gravity {
-        let new_volume = calculate_new_volume(
-            boil_off_options.wort_volume,
-            boil_off_options.current_gravity,
-            desired_gravity,
+impl Report for BoilOffReport {
+    fn print_table(&self, units: UnitSystem) {
+        println!(
+            "Wort Volume: {}",
+            units.volume(Volume::from_litres(self.wort_volume_l))
         );
-        println!("New Volume: {}", new_volume);
-        println!("Difference: {}", new_volume - boil_off_options.wort_volume);
+        println!("Current Gravity: {}", self.current_gravity);
+
+        if let (Some(new_volume), Some(difference)) = (self.new_volume_l, self.volume_difference_l)
+        {
+            println!(
+                "New Volume: {}",
+                units.volume(Volume::from_litres(new_volume))
+            );
+            println!(
+                "Difference: {}",
+                units.volume(Volume::from_litres(difference))
+            );
+        }
+
+        if let (Some(new_gravity), Some(difference)) = (self.new_gravity, self.gravity_difference) {
+            println!("New Gravity: {}", new_gravity);
+            println!("Difference: {:.3}", difference);
+        }
     }
+}
 
-    if let Some(target_volume) = boil_off_options.target_volume {
-        let new_gravity = calculate_new_gravity(
+pub fn calculate_and_print(boil_off_options: BoilOffOptions, output: Output) -> Result<()> {
+    let current_gravity = Gravity::from_specific_gravity(boil_off_options.current_gravity);
+    let wort_volume = boil_off_options.wort_volume.as_litres();
+
+    let new_volume = match boil_off_options.desired_gravity {
+        Some(desired_gravity) => Some(
+            new_volume(
+                current_gravity,
+                boil_off_options.wort_volume,
+                Gravity::from_specific_gravity(desired_gravity),
+            )?
+            .as_litres(),
+        ),
+        None => None,
+    };
+
+    let new_gravity = match boil_off_options.target_volume {
+        Some(target_volume) => Some(new_gravity(
+            current_gravity,
             boil_off_options.wort_volume,
-            boil_off_options.current_gravity,
             target_volume,
-        );
-        println!("New Gravity: {}", new_gravity);
-        println!(
-            "Difference: {}",
-            new_gravity - boil_off_options.current_gravity
-        );
-    }
+        )?),
+        None => None,
+    };
+
+    let report = BoilOffReport {
+        wort_volume_l: wort_volume,
+        current_gravity,
+        new_volume_l: new_volume,
+        volume_difference_l: new_volume.map(|volume| volume - wort_volume),
+        new_gravity,
+        gravity_difference: new_gravity
+            .map(|gravity| gravity.as_specific_gravity() - current_gravity.as_specific_gravity()),
+    };
+
+    output.print(&report)
 }
@@ -0,0 +1,394 @@
+use super::timeline::describe;
+use crate::output::Output;
+use crate::recipe::{read_recipe, RecipeFile};
+use anyhow::{anyhow, Context, Result};
+use chrono::Local;
+use rustybeer::calculators::timeline::{timeline, BrewStep, BrewStepKind};
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+use structopt::StructOpt;
+
+/// Terminal bell
+const BELL: char = '\x07';
+
+/// How often the countdown is updated
+const TICK: Duration = Duration::from_millis(250);
+
+/// Range of gravity readings accepted, from a dry beer to a strong wort
+const MIN_GRAVITY: f64 = 0.980;
+const MAX_GRAVITY: f64 = 1.200;
+
+const HELP: &str = "Commands: p = pause, r = resume, n = start the next step now, \
+g <gravity> [note] = record a gravity reading, q = quit, h = help";
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "brew")]
+/// Runs a brew-day countdown of a recipe file, ringing the bell at each step and hop addition
+/// and logging actual times and gravity readings
+pub struct BrewOptions {
+    #[structopt(parse(from_os_str))]
+    /// Recipe file in TOML, YAML or JSON with hop additions and mash steps
+    file: PathBuf,
+
+    #[structopt(short, long, parse(from_os_str))]
+    /// Brew log file events are appended to as JSON lines. Defaults to the recipe file
+    /// with the extension `brewlog.jsonl`.
+    log: Option<PathBuf>,
+}
+
+/// Something that happened on the brew day
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum BrewEvent<'a> {
+    Start {
+        recipe: &'a str,
+    },
+    Step {
+        kind: BrewStepKind,
+        name: &'a str,
+        /// planned start from the start of the brew day (min)
+        planned_mins: u32,
+        /// actual start from the start of the brew day, without pauses (min)
+        actual_mins: f64,
+    },
+    Pause,
+    Resume,
+    Gravity {
+        gravity: f64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        note: Option<String>,
+    },
+    Finish,
+    Quit,
+}
+
+/// An event with the wall-clock time it happened at, one line of the brew log
+#[derive(Debug, Serialize)]
+struct LogEntry<'a> {
+    time: String,
+    #[serde(flatten)]
+    event: BrewEvent<'a>,
+}
+
+/// A command typed in while the timer runs
+#[derive(Debug, PartialEq)]
+enum Command {
+    Pause,
+    Resume,
+    Next,
+    Gravity(f64, Option<String>),
+    Quit,
+    Help,
+    /// A known command with an invalid argument and why it is invalid
+    Invalid(String),
+    Unknown(String),
+}
+
+impl Command {
+    fn parse(line: &str) -> Self {
+        let line = line.trim();
+        let (word, rest) = line.split_once(' ').unwrap_or((line, ""));
+        match word.to_lowercase().as_str() {
+            "p" | "pause" => Command::Pause,
+            "r" | "resume" => Command::Resume,
+            "n" | "next" => Command::Next,
+            "q" | "quit" => Command::Quit,
+            "h" | "help" | "?" => Command::Help,
+            "g" | "gravity" => {
+                let (gravity, note) = rest.trim().split_once(' ').unwrap_or((rest.trim(), ""));
+                let gravity = match gravity.parse() {
+                    Ok(gravity) => gravity,
+                    Err(_) => return Command::Unknown(line.to_owned()),
+                };
+                if !(MIN_GRAVITY..=MAX_GRAVITY).contains(&gravity) {
+                    return Command::Invalid(format!(
+                        "gravity must be between {} and {}, got {}",
+                        MIN_GRAVITY, MAX_GRAVITY, gravity
+                    ));
+                }
+                let note = note.trim();
+                Command::Gravity(gravity, (!note.is_empty()).then(|| note.to_owned()))
+            }
+            _ => Command::Unknown(line.to_owned()),
+        }
+    }
+}
+
+/// Brew day clock that stops while paused
+struct Clock {
+    started: Instant,
+    /// time spent paused before the current pause
+    paused: Duration,
+    /// time skipped ahead to start steps early
+    skipped: Duration,
+    paused_at: Option<Instant>,
+}
+
+impl Clock {
+    fn start() -> Self {
+        Self {
+            started: Instant::now(),
+            paused: Duration::ZERO,
+            skipped: Duration::ZERO,
+            paused_at: None,
+        }
+    }
+
+    /// Brew day time passed, without pauses
+    fn elapsed(&self) -> Duration {
+        let now = self.paused_at.unwrap_or_else(Instant::now);
+        (now - self.started + self.skipped).saturating_sub(self.paused)
+    }
+
+    fn pause(&mut self) -> bool {
+        let paused = self.paused_at.is_none();
+        self.paused_at.get_or_insert_with(Instant::now);
+        paused
+    }
+
+    fn resume(&mut self) -> bool {
+        match self.paused_at.take() {
+            Some(paused_at) => {
+                self.paused += paused_at.elapsed();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves the clock ahead to the given brew day time
+    fn skip_to(&mut self, time: Duration) {
+        self.skipped += time.saturating_sub(self.elapsed());
+    }
+}
+
+/// Appends events to the brew log
+struct BrewLog(File);
+
+impl BrewLog {
+    fn write(&mut self, event: BrewEvent) -> Result<()> {
+        let entry = LogEntry {
+            time: Local::now().format("%Y-%m-%dT%H:%M:%S").to_string(),
+            event,
+        };
+        writeln!(self.0, "{}", serde_json::to_string(&entry)?)?;
+        Ok(self.0.flush()?)
+    }
+}
+
+fn clock_time(time: Duration) -> String {
+    let seconds = time.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Reads commands from the terminal in the background
+fn read_commands() -> Receiver<Command> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let line = match line {
+                Ok(line) if line.trim().is_empty() => continue,
+                Ok(line) => line,
+                Err(_) => break,
+            };
+            if sender.send(Command::parse(&line)).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+pub fn run(brew_options: BrewOptions, output: Output) -> Result<()> {
+    let file: RecipeFile = read_recipe(&brew_options.file)?;
+    let steps = timeline(&file.recipe, &file.brew_day)?;
+    let end = steps
+        .iter()
+        .map(BrewStep::end_mins)
+        .max()
+        .ok_or_else(|| anyhow!("Recipe has no brew day steps"))?;
+    let end = Duration::from_secs(u64::from(end) * 60);
+
+    let log_path = match brew_options.log {
+        Some(log_path) => log_path,
+        None => brew_options.file.with_extension("brewlog.jsonl"),
+    };
+    let mut log = BrewLog(
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_path)
+            .with_context(|| format!("Could not open brew log {}", log_path.display()))?,
+    );
+
+    let units = output.units;
+    let live = io::stdout().is_terminal();
+    println!(
+        "Brewing {}, logging to {}",
+        file.recipe.name,
+        log_path.display()
+    );
+    println!("{}", HELP);
+    log.write(BrewEvent::Start {
+        recipe: &file.recipe.name,
+    })?;
+
+    let commands = read_commands();
+    let mut clock = Clock::start();
+    let mut next = 0;
+    loop {
+        let elapsed = clock.elapsed();
+        while let Some(step) = steps
+            .get(next)
+            .filter(|step| elapsed.as_secs() >= u64::from(step.start_mins) * 60)
+        {
+            println!(
+                "\r{} {}{}",
+                clock_time(elapsed),
+                describe(step, units),
+                BELL
+            );
+            log.write(BrewEvent::Step {
+                kind: step.kind,
+                name: &step.name,
+                planned_mins: step.start_mins,
+                actual_mins: elapsed.as_secs() as f64 / 60.,
+            })?;
+            next += 1;
+        }
+        if elapsed >= end {
+            println!("\r{} Brew day done{}", clock_time(elapsed), BELL);
+            return log.write(BrewEvent::Finish);
+        }
+
+        if live {
+            let status = match (clock.paused_at, steps.get(next)) {
+                (Some(_), _) => "paused".to_owned(),
+                (None, Some(step)) => format!(
+                    "next in {}: {}",
+                    clock_time(Duration::from_secs(u64::from(step.start_mins) * 60) - elapsed),
+                    describe(step, units)
+                ),
+                (None, None) => format!("done in {}", clock_time(end - elapsed)),
+            };
+            print!("\r\x1b[K{} {}", clock_time(elapsed), status);
+            io::stdout().flush()?;
+        }
+
+        let command = match commands.recv_timeout(TICK) {
+            Ok(command) => command,
+            Err(RecvTimeoutError::Timeout) => continue,
+            // Without a terminal to read from the timer runs to the end
+            Err(RecvTimeoutError::Disconnected) => {
+                thread::sleep(TICK);
+                continue;
+            }
+        };
+        match command {
+            Command::Pause => {
+                if clock.pause() {
+                    println!("\r\x1b[KPaused, r to resume");
+                    log.write(BrewEvent::Pause)?;
+                }
+            }
+            Command::Resume => {
+                if clock.resume() {
+                    println!("\r\x1b[KResumed");
+                    log.write(BrewEvent::Resume)?;
+                }
+            }
+            Command::Next => match steps.get(next) {
+                Some(step) => clock.skip_to(Duration::from_secs(u64::from(step.start_mins) * 60)),
+                None => clock.skip_to(end),
+            },
+            Command::Gravity(gravity, note) => {
+                println!("\r\x1b[KGravity {:.3} recorded", gravity);
+                log.write(BrewEvent::Gravity { gravity, note })?;
+            }
+            Command::Quit => {
+                println!("\r\x1b[KStopped at {}", clock_time(clock.elapsed()));
+                return log.write(BrewEvent::Quit);
+            }
+            Command::Help => println!("\r\x1b[K{}", HELP),
+            Command::Invalid(e) => println!("\r\x1b[K{}", e),
+            Command::Unknown(line) => println!("\r\x1b[KUnknown command '{}'. {}", line, HELP),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Clock, Command};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn parse_commands() {
+        assert_eq!(Command::Pause, Command::parse(" p "));
+        assert_eq!(Command::Resume, Command::parse("Resume"));
+        assert_eq!(Command::Next, Command::parse("n"));
+        assert_eq!(Command::Quit, Command::parse("quit"));
+        assert_eq!(Command::Help, Command::parse("?"));
+        assert_eq!(Command::Gravity(1.052, None), Command::parse("g 1.052"));
+        assert_eq!(
+            Command::Gravity(1.012, Some("after dry hop".to_owned())),
+            Command::parse("gravity 1.012  after dry hop ")
+        );
+        assert_eq!(
+            Command::Unknown("g high".to_owned()),
+            Command::parse("g high")
+        );
+        assert_eq!(Command::Unknown("x".to_owned()), Command::parse("x"));
+    }
+
+    #[test]
+    fn parse_invalid_gravity() {
+        for line in ["g 52", "g 0.5", "g NaN", "g inf"] {
+            assert!(
+                matches!(Command::parse(line), Command::Invalid(_)),
+                "{}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn clock_stops_while_paused() {
+        let mut clock = Clock::start();
+        assert!(!clock.resume());
+        assert!(clock.pause());
+        assert!(!clock.pause());
+        let paused = clock.elapsed();
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(paused, clock.elapsed());
+
+        assert!(clock.resume());
+        assert!(clock.elapsed() < paused + Duration::from_millis(20));
+    }
+
+    #[test]
+    fn clock_skips_ahead_while_paused() {
+        let mut clock = Clock::start();
+        clock.pause();
+        clock.skip_to(Duration::from_secs(600));
+        assert_eq!(Duration::from_secs(600), clock.elapsed());
+
+        // Skipping back does nothing
+        clock.skip_to(Duration::from_secs(60));
+        assert_eq!(Duration::from_secs(600), clock.elapsed());
+
+        clock.resume();
+        assert!(clock.elapsed() >= Duration::from_secs(600));
+        assert!(clock.elapsed() < Duration::from_secs(601));
+    }
+}
@@ -1,12 +1,17 @@
+use crate::output::{Output, Report};
+use crate::units::UnitSystem;
+use anyhow::{anyhow, Result};
 use rustybeer::calculators::calorie_counter::{
     calculate_alcohol_calories, calculate_carbs_calories, calculate_total_calories,
 };
-use rustybeer::calculators::num_bottles::bottles;
 use rustybeer_util::{
     abv_calories::{Criteria, ABV_CALORIES},
+    containers::all_containers,
     conversions::{MassParser, VolumeParser},
     measurements::Volume,
 };
+use serde::Serialize;
+use std::io;
 
 use structopt::StructOpt;
 
@@ -31,98 +36,167 @@ pub struct CaloriesOptions {
     volume: Option<Volume>,
 }
 
-pub fn calculate_and_print(calories: CaloriesOptions) {
-    let conversion = MassParser::parse("12oz").unwrap().as_grams() as f32;
-    if calories.og.is_some() && calories.fg.is_some() {
-        let fg = calories.fg.unwrap();
-        let og = calories.og.unwrap();
-        if let Some(volume) = calories.volume {
-            let volume = volume.as_milliliters();
-            let ac = calculate_alcohol_calories(og, fg) / conversion * volume as f32;
-            let cc = calculate_carbs_calories(og, fg) / conversion * volume as f32;
-            let tc = calculate_total_calories(og, fg) / conversion * volume as f32;
-            println!("Estimated calories for: {} ml", volume);
-            println!("==============================");
-            println!("| {:<8} | {:<6} | {:<6} |", "", "kcal:", "kJ:");
-            println!("| {:<8} | {:>6.0} | {:>6.0} |", "Alcohol:", ac, ac * 4.184);
-            println!("| {:<8} | {:>6.0} | {:>6.0} |", "Carbs:", cc, cc * 4.184);
-            println!("| {:<8} | {:>6.0} | {:>6.0} |", "Total:", tc, tc * 4.184);
-            println!("==============================");
-        } else {
-            println!("Total estimated calories for:");
-            println!("==========================================================");
-            let bottles = calculate_calories_per_bottle(conversion, og, fg);
-            for bottle in bottles {
-                let output = format!(
-                    "| Type: {: <20} | kcal: {: >6} | kJ: {: >6.0} |",
-                    bottle.0,
-                    bottle.1,
-                    bottle.1 * 4.184
-                );
-                println!("{}", output);
-            }
-            println!("==========================================================");
-        }
-    } else if calories.abv.is_some() {
-        if let Some(volume) = calories.volume {
-            let volume = volume.as_milliliters() as f32;
-            let criteria = Criteria { abv: calories.abv };
-            for abv in ABV_CALORIES.iter() {
-                if criteria.matches(abv) {
-                    let lc = abv.calories_low / conversion * volume;
-                    let hc = abv.calories_high / conversion * volume;
-                    println!("Total estimated calories range for: {} ml", volume);
+/// Calories from original and final gravity for a volume or container
+#[derive(Debug, Serialize)]
+pub struct GravityCalories {
+    container: Option<String>,
+    volume_ml: f32,
+    alcohol_kcal: f32,
+    carbs_kcal: f32,
+    total_kcal: f32,
+}
+
+/// Calorie range from alcohol by volume for a volume or container
+#[derive(Debug, Serialize)]
+pub struct AbvCalories {
+    container: Option<String>,
+    volume_ml: f32,
+    kcal_low: f32,
+    kcal_high: f32,
+}
+
+/// Calories for the given volume, or for each container
+/// when no volume is given
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum CaloriesReport {
+    Gravity(Vec<GravityCalories>),
+    Abv(Vec<AbvCalories>),
+}
+
+impl Report for CaloriesReport {
+    fn print_table(&self, units: UnitSystem) {
+        match self {
+            CaloriesReport::Gravity(rows) => match rows.as_slice() {
+                [row] if row.container.is_none() => {
+                    println!(
+                        "Estimated calories for: {}",
+                        units.volume(Volume::from_milliliters(row.volume_ml as f64))
+                    );
+                    println!("==============================");
+                    println!("| {:<8} | {:<6} | {:<6} |", "", "kcal:", "kJ:");
+                    for (name, kcal) in [
+                        ("Alcohol:", row.alcohol_kcal),
+                        ("Carbs:", row.carbs_kcal),
+                        ("Total:", row.total_kcal),
+                    ] {
+                        println!("| {:<8} | {:>6.0} | {:>6.0} |", name, kcal, kcal * 4.184);
+                    }
+                    println!("==============================");
+                }
+                _ => {
+                    println!("Total estimated calories for:");
+                    println!("==========================================================");
+                    for row in rows {
+                        let kcal = row.total_kcal.ceil();
+                        println!(
+                            "| Type: {: <20} | kcal: {: >6} | kJ: {: >6.0} |",
+                            row.container.as_deref().unwrap_or_default(),
+                            kcal,
+                            kcal * 4.184
+                        );
+                    }
+                    println!("==========================================================");
+                }
+            },
+            CaloriesReport::Abv(rows) => match rows.as_slice() {
+                [row] if row.container.is_none() => {
+                    println!(
+                        "Total estimated calories range for: {}",
+                        units.volume(Volume::from_milliliters(row.volume_ml as f64))
+                    );
                     println!("=========================");
-                    println!("| {:>6.0} to {:<6.0} kcal |", lc, hc);
-                    println!("| {:>6.0} to {:<6.0} kJ   |", lc * 4.184, hc * 4.184);
+                    println!("| {:>6.0} to {:<6.0} kcal |", row.kcal_low, row.kcal_high);
+                    println!(
+                        "| {:>6.0} to {:<6.0} kJ   |",
+                        row.kcal_low * 4.184,
+                        row.kcal_high * 4.184
+                    );
                     println!("=========================");
-                    return;
                 }
-            }
-        } else {
-            let criteria = Criteria { abv: calories.abv };
-            for abv in ABV_CALORIES.iter() {
-                if criteria.matches(abv) {
+                _ => {
                     println!("Total estimated calories range for:");
                     println!("============================================================================");
-                    let bottles = get_list_of_volumes_from_bottles();
-                    for bottle in bottles {
-                        let lc = abv.calories_low / conversion * bottle.1;
-                        let hc = abv.calories_high / conversion * bottle.1;
-                        let output =
-                            format!(
+                    for row in rows {
+                        println!(
                             "| Type: {: <20} | kcal: {:>5.0} to {:<5.0} | kJ: {:>6.0} to {:<6.0} |",
-                            bottle.0, lc, hc, lc * 4.184, hc * 4.184
+                            row.container.as_deref().unwrap_or_default(),
+                            row.kcal_low,
+                            row.kcal_high,
+                            row.kcal_low * 4.184,
+                            row.kcal_high * 4.184
                         );
-                        println!("{}", output);
                     }
                     println!("============================================================================");
-                    return;
                 }
-            }
+            },
+        }
+    }
+
+    fn write_csv<W: io::Write>(&self, writer: &mut csv::Writer<W>) -> csv::Result<()> {
+        match self {
+            CaloriesReport::Gravity(rows) => rows.iter().try_for_each(|row| writer.serialize(row)),
+            CaloriesReport::Abv(rows) => rows.iter().try_for_each(|row| writer.serialize(row)),
         }
-        println!("Could not find any ABV to calories matching criteria (range: 0 to 22)");
     }
 }
 
-pub fn calculate_calories_per_bottle(conversion: f32, og: f32, fg: f32) -> Vec<(String, f32)> {
-    let bottle_types = bottles();
-    let mut bottle_counter: Vec<(String, f32)> = Vec::with_capacity(bottle_types.len());
+pub fn calculate_and_print(calories: CaloriesOptions, output: Output) -> Result<()> {
+    let conversion = MassParser::parse("12oz").unwrap().as_grams() as f32;
+    let volumes = match calories.volume {
+        Some(volume) => vec![(None, volume.as_milliliters() as f32)],
+        None => get_list_of_volumes_from_bottles()?
+            .into_iter()
+            .map(|(name, volume)| (Some(name), volume))
+            .collect(),
+    };
+
+    let report = if let (Some(og), Some(fg)) = (calories.og, calories.fg) {
+        let alcohol = calculate_alcohol_calories(og, fg)? / conversion;
+        let carbs = calculate_carbs_calories(og, fg)? / conversion;
+        let total = calculate_total_calories(og, fg)? / conversion;
+        CaloriesReport::Gravity(
+            volumes
+                .into_iter()
+                .map(|(container, volume)| GravityCalories {
+                    container,
+                    volume_ml: volume,
+                    alcohol_kcal: alcohol * volume,
+                    carbs_kcal: carbs * volume,
+                    total_kcal: total * volume,
+                })
+                .collect(),
+        )
+    } else {
+        let criteria = Criteria { abv: calories.abv };
+        let abv = ABV_CALORIES
+            .iter()
+            .find(|abv| criteria.matches(abv))
+            .ok_or_else(|| {
+                anyhow!("Could not find any ABV to calories matching criteria (range: 0 to 22)")
+            })?;
+        CaloriesReport::Abv(
+            volumes
+                .into_iter()
+                .map(|(container, volume)| AbvCalories {
+                    container,
+                    volume_ml: volume,
+                    kcal_low: abv.calories_low / conversion * volume,
+                    kcal_high: abv.calories_high / conversion * volume,
+                })
+                .collect(),
+        )
+    };
 
-    for bottle in bottle_types {
-        let total_calories: f32 =
-            (calculate_total_calories(og, fg) / conversion * bottle.1 as f32).ceil();
-        bottle_counter.push((bottle.0, total_calories));
-    }
-    bottle_counter
+    output.print(&report)
 }
 
-pub fn get_list_of_volumes_from_bottles() -> Vec<(String, f32)> {
-    let bottle_types = bottles();
-    let mut bottle_counter: Vec<(String, f32)> = Vec::with_capacity(bottle_types.len());
+pub fn get_list_of_volumes_from_bottles() -> Result<Vec<(String, f32)>> {
+    let containers = all_containers()?;
+    let mut bottle_counter: Vec<(String, f32)> = Vec::with_capacity(containers.len());
 
-    for bottle in bottle_types {
-        bottle_counter.push((bottle.0, bottle.1 as f32));
+    for container in containers {
+        bottle_counter.push((container.name, container.volume as f32));
     }
-    bottle_counter
+    Ok(bottle_counter)
 }
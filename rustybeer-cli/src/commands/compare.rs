@@ -0,0 +1,183 @@
+use crate::output::{Output, Report};
+use crate::recipe::{read_recipe, RecipeFile};
+use crate::units::UnitSystem;
+use anyhow::Result;
+use rustybeer::calculators::comparison::{
+    compare, Change, Delta, IngredientChange, IngredientKind, RecipeComparison,
+};
+use rustybeer_util::measurements::Mass;
+use serde::Serialize;
+use std::io;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "compare")]
+/// Compares two recipe files: ingredient changes and how OG, FG, ABV, IBU, color and BU:GU moved
+pub struct CompareOptions {
+    #[structopt(parse(from_os_str))]
+    /// Earlier recipe file in TOML, YAML or JSON
+    before: PathBuf,
+
+    #[structopt(parse(from_os_str))]
+    /// Later recipe file in TOML, YAML or JSON
+    after: PathBuf,
+}
+
+/// One value or ingredient of the comparison as a flat CSV record
+#[derive(Debug, Serialize)]
+struct ComparisonRow<'a> {
+    section: &'static str,
+    name: &'a str,
+    time_mins: Option<u32>,
+    change: Option<Change>,
+    before: f64,
+    after: f64,
+    delta: f64,
+    /// attributes other than the amount that changed
+    attributes: String,
+}
+
+/// Comparison of two named recipes
+#[derive(Debug, Serialize)]
+struct ComparisonReport {
+    before: String,
+    after: String,
+    #[serde(flatten)]
+    comparison: RecipeComparison,
+}
+
+impl ComparisonReport {
+    fn values(&self) -> [(&'static str, &'static str, &Delta); 6] {
+        let comparison = &self.comparison;
+        [
+            (
+                "original_gravity",
+                "Original gravity",
+                &comparison.original_gravity,
+            ),
+            ("final_gravity", "Final gravity", &comparison.final_gravity),
+            ("abv", "ABV (%)", &comparison.abv),
+            ("ibu", "IBU", &comparison.ibu),
+            ("color_srm", "Color (SRM)", &comparison.color_srm),
+            ("bu_gu", "BU:GU", &comparison.bu_gu),
+        ]
+    }
+}
+
+fn section(kind: IngredientKind) -> &'static str {
+    match kind {
+        IngredientKind::Fermentable => "fermentable",
+        IngredientKind::Hop => "hop",
+        IngredientKind::Salt => "salt",
+        IngredientKind::Yeast => "yeast",
+    }
+}
+
+/// Changed attributes, e.g. `time_mins 10 -> 5; hop_type Pellet -> Whole`
+fn attributes(ingredient: &IngredientChange) -> String {
+    ingredient
+        .attributes
+        .iter()
+        .map(|a| format!("{} {} -> {}", a.attribute, a.before, a.after))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+fn amount(ingredient: &IngredientChange, value: f64, units: UnitSystem) -> String {
+    match ingredient.kind {
+        IngredientKind::Yeast => format!("{:.0} billion cells", value),
+        _ => units.mass(Mass::from_grams(value)),
+    }
+}
+
+impl Report for ComparisonReport {
+    fn print_table(&self, units: UnitSystem) {
+        println!("Before: {}", self.before);
+        println!("After: {}", self.after);
+        for (_, label, delta) in self.values() {
+            let decimals = if label.ends_with("gravity") { 3 } else { 2 };
+            println!(
+                "{:>23}: {:.*} -> {:.*} ({:+.*})",
+                label, decimals, delta.before, decimals, delta.after, decimals, delta.delta
+            );
+        }
+
+        if self.comparison.ingredients.is_empty() {
+            println!("Ingredients: unchanged");
+            return;
+        }
+        println!("Ingredients:");
+        for ingredient in &self.comparison.ingredients {
+            let name = match ingredient.time_mins {
+                Some(time) => format!("{} at {} min", ingredient.name, time),
+                None => ingredient.name.clone(),
+            };
+            let change = match ingredient.change {
+                Change::Added => format!("added {}", amount(ingredient, ingredient.after, units)),
+                Change::Removed => {
+                    format!("removed {}", amount(ingredient, ingredient.before, units))
+                }
+                Change::Changed => {
+                    let mut changes = Vec::new();
+                    if (ingredient.after - ingredient.before).abs() > 1e-6 {
+                        changes.push(format!(
+                            "{} -> {}",
+                            amount(ingredient, ingredient.before, units),
+                            amount(ingredient, ingredient.after, units)
+                        ));
+                    }
+                    if !ingredient.attributes.is_empty() {
+                        changes.push(attributes(ingredient));
+                    }
+                    changes.join("; ")
+                }
+            };
+            println!("{:>23}: {}", name, change);
+        }
+    }
+
+    fn write_csv<W: io::Write>(&self, writer: &mut csv::Writer<W>) -> csv::Result<()> {
+        let values = self.values();
+        let values = values.iter().map(|&(name, _, delta)| ComparisonRow {
+            section: "value",
+            name,
+            time_mins: None,
+            change: None,
+            before: delta.before,
+            after: delta.after,
+            delta: delta.delta,
+            attributes: String::new(),
+        });
+        let ingredients = self.comparison.ingredients.iter().map(|i| ComparisonRow {
+            section: section(i.kind),
+            name: &i.name,
+            time_mins: i.time_mins,
+            change: Some(i.change),
+            before: i.before,
+            after: i.after,
+            delta: i.after - i.before,
+            attributes: attributes(i),
+        });
+        values
+            .chain(ingredients)
+            .try_for_each(|row| writer.serialize(row))
+    }
+}
+
+pub fn calculate_and_print(compare_options: CompareOptions, output: Output) -> Result<()> {
+    let before: RecipeFile = read_recipe(&compare_options.before)?;
+    let after: RecipeFile = read_recipe(&compare_options.after)?;
+
+    let comparison = compare(
+        &before.recipe,
+        &after.recipe,
+        before.final_gravity()?,
+        after.final_gravity()?,
+    )?;
+    output.print(&ComparisonReport {
+        before: before.recipe.name,
+        after: after.recipe.name,
+        comparison,
+    })
+}
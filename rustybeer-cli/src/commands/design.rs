@@ -0,0 +1,42 @@
+use crate::output::Output;
+use crate::recipe::{read_recipe, RecipeReport};
+use anyhow::Result;
+use rustybeer::calculators::design::RecipeDesign;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "design")]
+/// Solves grain and hop weights of a recipe for a target OG, color and IBU
+pub struct DesignOptions {
+    #[structopt(short, long, parse(from_os_str))]
+    /// Recipe design file in JSON, TOML or YAML with targets, grain shares and hop shares
+    recipe: PathBuf,
+
+    #[structopt(short, long)]
+    /// Target original gravity, overrides the one in the design
+    og: Option<f64>,
+
+    #[structopt(short, long)]
+    /// Target color in SRM, overrides the one in the design
+    color: Option<f64>,
+
+    #[structopt(short, long)]
+    /// Target IBU, overrides the one in the design
+    ibu: Option<f64>,
+}
+
+pub fn calculate_and_print(design_options: DesignOptions, output: Output) -> Result<()> {
+    let mut design: RecipeDesign = read_recipe(&design_options.recipe)?;
+    if let Some(og) = design_options.og {
+        design.original_gravity = og;
+    }
+    if let Some(color) = design_options.color {
+        design.color_srm = Some(color);
+    }
+    if let Some(ibu) = design_options.ibu {
+        design.ibu = ibu;
+    }
+
+    output.print(&RecipeReport::new(design.solve()?)?)
+}
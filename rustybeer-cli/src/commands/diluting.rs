@@ -1,4 +1,10 @@
-use rustybeer::calculators::diluting::calculate_new_gravity;
+use crate::output::{Output, Report};
+use crate::units::UnitSystem;
+use anyhow::Result;
+use rustybeer::calculators::diluting::new_gravity;
+use rustybeer::units::{Gravity, Volume};
+use rustybeer_util::conversions::VolumeParser;
+use serde::Serialize;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -10,24 +16,42 @@ use structopt::StructOpt;
 pub struct DilutingOptions {
     #[structopt(short, long)]
     /// Current specific gravity
-    sg: f32,
+    sg: f64,
 
-    #[structopt(short, long)]
-    /// Current Volume
-    cv: f32,
+    #[structopt(short, long, parse(try_from_str = VolumeParser::parse))]
+    /// Current Volume with unit (l, ml, gal, etc.). Defaults to liters.
+    cv: Volume,
 
-    #[structopt(short, long)]
-    /// Target Volume
-    tv: f32,
+    #[structopt(short, long, parse(try_from_str = VolumeParser::parse))]
+    /// Target Volume with unit (l, ml, gal, etc.). Defaults to liters.
+    tv: Volume,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DilutingReport {
+    gravity: f64,
+    current_volume_l: f64,
+    target_volume_l: f64,
+    new_gravity: Gravity,
 }
 
-pub fn calculate_and_print(diluting_options: DilutingOptions) {
-    println!(
-        "New SG: {:.3}",
-        calculate_new_gravity(
-            diluting_options.sg,
+impl Report for DilutingReport {
+    fn print_table(&self, _units: UnitSystem) {
+        println!("New SG: {}", self.new_gravity);
+    }
+}
+
+pub fn calculate_and_print(diluting_options: DilutingOptions, output: Output) -> Result<()> {
+    let report = DilutingReport {
+        gravity: diluting_options.sg,
+        current_volume_l: diluting_options.cv.as_litres(),
+        target_volume_l: diluting_options.tv.as_litres(),
+        new_gravity: new_gravity(
+            Gravity::from_specific_gravity(diluting_options.sg),
             diluting_options.cv,
-            diluting_options.tv
-        )
-    );
+            diluting_options.tv,
+        )?,
+    };
+
+    output.print(&report)
 }
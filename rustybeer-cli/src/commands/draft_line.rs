@@ -0,0 +1,139 @@
+use crate::output::{Output, Report};
+use crate::units::UnitSystem;
+use anyhow::{anyhow, Result};
+use rustybeer::calculators::draft_line::{
+    beer_lines, calculate_line_length, calculate_pour_time, STANDARD_FLOW_RATE,
+};
+use rustybeer_util::{
+    conversions::{LengthParser, PressureParser},
+    measurements::{Length, Pressure, Volume},
+};
+use serde::Serialize;
+use structopt::{clap::ArgGroup, StructOpt};
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "draft_line", group = ArgGroup::with_name("line").required(true))]
+/// Calculates the beer line length needed to balance a draught system and the pour time per pint
+pub struct DraftLineOptions {
+    #[structopt(short, long, parse(try_from_str = PressureParser::parse))]
+    /// Serving pressure with unit (psi, bar, kPa, etc.). Defaults to psi.
+    pressure: Pressure,
+
+    #[structopt(short, long, group = "line")]
+    /// Beer line tubing (e.g. '3/16 in vinyl', '3/8 in stainless')
+    tubing: Option<String>,
+
+    #[structopt(short, long, group = "line")]
+    /// Beer line resistance in psi/ft at 1 gal/min
+    resistance: Option<f64>,
+
+    #[structopt(long, default_value = "0", parse(try_from_str = LengthParser::parse))]
+    /// Vertical rise from the keg to the faucet with unit (m, cm, ft, in, etc.). Defaults to meters.
+    rise: Length,
+
+    #[structopt(short, long = "flow_rate")]
+    /// Wanted flow rate in ml/s. Defaults to the standard 1 gal/min (about 63 ml/s).
+    flow_rate: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DraftLineReport {
+    pressure_psi: f64,
+    pressure_bar: f64,
+    line: String,
+    resistance_psi_per_ft: f64,
+    rise_m: f64,
+    flow_rate_ml_s: f64,
+    line_length_m: f64,
+    line_length_ft: f64,
+    pour_time_pint_s: f64,
+    pour_time_us_pint_s: f64,
+}
+
+impl Report for DraftLineReport {
+    fn print_table(&self, units: UnitSystem) {
+        println!(
+            "Serving pressure: {}",
+            units.pressure(Pressure::from_psi(self.pressure_psi))
+        );
+        println!(
+            "Beer line: {} ({} psi/ft)",
+            self.line, self.resistance_psi_per_ft
+        );
+        println!(
+            "Vertical rise: {}",
+            units.length(Length::from_meters(self.rise_m))
+        );
+        println!(
+            "Flow rate: {} ({:.2} of standard)",
+            units.flow_rate(Volume::from_milliliters(self.flow_rate_ml_s)),
+            self.flow_rate_ml_s / STANDARD_FLOW_RATE
+        );
+        println!(
+            "Line length: {}",
+            units.length(Length::from_meters(self.line_length_m))
+        );
+        match units {
+            UnitSystem::Us => println!("Pour time per pint: {:.1} s", self.pour_time_us_pint_s),
+            _ => println!(
+                "Pour time per pint: {:.1} s (US pint: {:.1} s)",
+                self.pour_time_pint_s, self.pour_time_us_pint_s
+            ),
+        }
+    }
+}
+
+pub fn calculate_and_print(draft_line: DraftLineOptions, output: Output) -> Result<()> {
+    let (name, resistance) = match (draft_line.tubing, draft_line.resistance) {
+        (Some(tubing), _) => {
+            let line = beer_lines()
+                .into_iter()
+                .find(|line| line.name.eq_ignore_ascii_case(&tubing))
+                .ok_or_else(|| {
+                    let available: Vec<String> = beer_lines()
+                        .into_iter()
+                        .map(|line| format!("{:>20}: {} psi/ft", line.name, line.resistance))
+                        .collect();
+                    anyhow!(
+                        "Could not find beer line '{}'. Available lines:\n{}",
+                        tubing,
+                        available.join("\n")
+                    )
+                })?;
+            (line.name, line.resistance)
+        }
+        (None, Some(resistance)) => ("Custom".to_string(), resistance),
+        // The required "line" group gives tubing or resistance
+        (None, None) => unreachable!(),
+    };
+
+    let flow_rate = draft_line.flow_rate.unwrap_or(STANDARD_FLOW_RATE);
+    let rise = draft_line.rise;
+    let length = Length::from_feet(calculate_line_length(
+        draft_line.pressure.as_psi(),
+        resistance,
+        rise.as_feet(),
+        flow_rate,
+    )?);
+
+    let report = DraftLineReport {
+        pressure_psi: draft_line.pressure.as_psi(),
+        pressure_bar: draft_line.pressure.as_bars(),
+        line: name,
+        resistance_psi_per_ft: resistance,
+        rise_m: rise.as_meters(),
+        flow_rate_ml_s: flow_rate,
+        line_length_m: length.as_meters(),
+        line_length_ft: length.as_feet(),
+        pour_time_pint_s: calculate_pour_time(
+            Volume::from_pints_uk(1.0).as_milliliters(),
+            flow_rate,
+        )?,
+        pour_time_us_pint_s: calculate_pour_time(
+            Volume::from_pints(1.0).as_milliliters(),
+            flow_rate,
+        )?,
+    };
+
+    output.print(&report)
+}
@@ -0,0 +1,126 @@
+use crate::output::{Output, Report};
+use crate::recipe::{read_recipe, RecipeFile};
+use crate::units::UnitSystem;
+use anyhow::Result;
+use rustybeer::calculators::efficiency::{
+    brewhouse_efficiency, conversion_efficiency, lauter_efficiency, mash_efficiency,
+};
+use rustybeer::units::{Gravity, Volume};
+use rustybeer_util::conversions::VolumeParser;
+use serde::Serialize;
+use std::path::PathBuf;
+use structopt::{clap::ArgGroup, StructOpt};
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "efficiency",
+    group = ArgGroup::with_name("measured").required(true).multiple(true)
+)]
+/// Calculates conversion, lauter, mash and brewhouse efficiency of a recipe file's grain bill
+/// from measured gravities and volumes
+pub struct EfficiencyOptions {
+    #[structopt(parse(from_os_str))]
+    /// Recipe file in TOML, YAML or JSON with the grain bill brewed
+    file: PathBuf,
+
+    #[structopt(long, group = "measured", requires("mash-water"))]
+    /// Gravity of the first wort run off the mash
+    first_wort_gravity: Option<f64>,
+
+    #[structopt(long, parse(try_from_str = VolumeParser::parse))]
+    /// Volume of mash water with unit (l, gal, etc.). Defaults to liters.
+    mash_water: Option<Volume>,
+
+    #[structopt(long, group = "measured", requires("pre-boil-volume"))]
+    /// Gravity of the wort in the kettle before sugars and extracts are added
+    pre_boil_gravity: Option<f64>,
+
+    #[structopt(long, parse(try_from_str = VolumeParser::parse))]
+    /// Volume of the wort in the kettle with unit (l, gal, etc.). Defaults to liters.
+    pre_boil_volume: Option<Volume>,
+
+    #[structopt(long, group = "measured", requires("post-boil-volume"))]
+    /// Original gravity
+    og: Option<f64>,
+
+    #[structopt(long, parse(try_from_str = VolumeParser::parse))]
+    /// Volume of the wort at the end of the boil with unit (l, gal, etc.). Defaults to liters.
+    post_boil_volume: Option<Volume>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EfficiencyReport {
+    recipe: String,
+    recipe_efficiency: f64,
+    conversion_efficiency: Option<f64>,
+    lauter_efficiency: Option<f64>,
+    mash_efficiency: Option<f64>,
+    brewhouse_efficiency: Option<f64>,
+}
+
+impl Report for EfficiencyReport {
+    fn print_table(&self, _units: UnitSystem) {
+        println!("Recipe: {}", self.recipe);
+        let efficiencies = [
+            ("Conversion efficiency", self.conversion_efficiency),
+            ("Lauter efficiency", self.lauter_efficiency),
+            ("Mash efficiency", self.mash_efficiency),
+        ];
+        for (name, efficiency) in efficiencies {
+            if let Some(efficiency) = efficiency {
+                println!("{}: {:.1}%", name, efficiency * 100.);
+            }
+        }
+        if let Some(efficiency) = self.brewhouse_efficiency {
+            println!(
+                "Brewhouse efficiency: {:.1}% (recipe {:.1}%)",
+                efficiency * 100.,
+                self.recipe_efficiency * 100.
+            );
+        }
+    }
+}
+
+pub fn calculate_and_print(efficiency_options: EfficiencyOptions, output: Output) -> Result<()> {
+    let file: RecipeFile = read_recipe(&efficiency_options.file)?;
+    let fermentables = &file.recipe.fermentables;
+    let gravity = Gravity::from_specific_gravity;
+
+    let conversion = match (
+        efficiency_options.first_wort_gravity,
+        efficiency_options.mash_water,
+    ) {
+        (Some(first_wort), Some(water)) => Some(conversion_efficiency(
+            fermentables,
+            gravity(first_wort),
+            water,
+        )?),
+        _ => None,
+    };
+    let mash = match (
+        efficiency_options.pre_boil_gravity,
+        efficiency_options.pre_boil_volume,
+    ) {
+        (Some(pre_boil), Some(volume)) => {
+            Some(mash_efficiency(fermentables, gravity(pre_boil), volume)?)
+        }
+        _ => None,
+    };
+    let lauter = match (mash, conversion) {
+        (Some(mash), Some(conversion)) => Some(lauter_efficiency(mash, conversion)?),
+        _ => None,
+    };
+    let brewhouse = match (efficiency_options.og, efficiency_options.post_boil_volume) {
+        (Some(og), Some(volume)) => Some(brewhouse_efficiency(fermentables, gravity(og), volume)?),
+        _ => None,
+    };
+
+    output.print(&EfficiencyReport {
+        recipe_efficiency: file.recipe.efficiency,
+        recipe: file.recipe.name,
+        conversion_efficiency: conversion,
+        lauter_efficiency: lauter,
+        mash_efficiency: mash,
+        brewhouse_efficiency: brewhouse,
+    })
+}
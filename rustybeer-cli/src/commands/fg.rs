@@ -1,4 +1,9 @@
-use rustybeer::calculators::fg::calculate_fg;
+use crate::output::{Output, Report};
+use crate::units::UnitSystem;
+use anyhow::Result;
+use rustybeer::calculators::fg::fg;
+use rustybeer::units::Gravity;
+use serde::Serialize;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -7,13 +12,36 @@ use structopt::StructOpt;
 pub struct FgOptions {
     #[structopt(short, long)]
     /// Original gravity
-    og: f32,
+    og: f64,
 
     #[structopt(short, long)]
     /// Yeast attenuation
     att: u8,
 }
 
-pub fn calculate_and_print(fg_options: FgOptions) {
-    println!("FG: {:.3}", calculate_fg(fg_options.og, fg_options.att));
+#[derive(Debug, Serialize)]
+pub struct FgReport {
+    og: f64,
+    attenuation_percent: u8,
+    fg: f64,
+}
+
+impl Report for FgReport {
+    fn print_table(&self, _units: UnitSystem) {
+        println!("FG: {:.3}", self.fg);
+    }
+}
+
+pub fn calculate_and_print(fg_options: FgOptions, output: Output) -> Result<()> {
+    let report = FgReport {
+        og: fg_options.og,
+        attenuation_percent: fg_options.att,
+        fg: fg(
+            Gravity::from_specific_gravity(fg_options.og),
+            fg_options.att,
+        )?
+        .as_specific_gravity(),
+    };
+
+    output.print(&report)
 }
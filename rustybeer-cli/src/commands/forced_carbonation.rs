@@ -0,0 +1,167 @@
+use crate::output::{Output, Report};
+use crate::units::UnitSystem;
+use anyhow::Result;
+use rustybeer::calculators::forced_carbonation::{
+    calculate_altitude_correction, calculate_co2_volumes, calculate_pressure,
+};
+use rustybeer_util::{
+    conversions::{PressureParser, TemperatureParser},
+    measurements::{Length, Pressure, Temperature},
+};
+use serde::Serialize;
+use std::io;
+use structopt::{clap::ArgGroup, StructOpt};
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "forced_carbonation", group = ArgGroup::with_name("desired").required(true))]
+/// Calculates the regulator pressure needed to force carbonate a keg or the CO2 volumes reached with a given pressure
+pub struct ForcedCarbonationOptions {
+    #[structopt(short, long, parse(try_from_str = TemperatureParser::parse))]
+    /// Temperature of beer with unit (C, F, K). Defaults to Celsius.
+    temp: Temperature,
+
+    #[structopt(short, long = "co2_volumes", group = "desired")]
+    /// Volumes of wanted CO2, depends on beer style (e.g. British Style Ales 1.5 to 2.0)
+    co2_volumes: Option<f64>,
+
+    #[structopt(short, long, group = "desired", parse(try_from_str = PressureParser::parse))]
+    /// Regulator gauge pressure with unit (psi, bar, kPa, etc.). Defaults to psi.
+    pressure: Option<Pressure>,
+
+    #[structopt(short, long, default_value = "0")]
+    /// Altitude in meters, used to correct the gauge pressure
+    altitude: f64,
+
+    #[structopt(long, requires("co2-volumes"))]
+    /// Prints a temperature/pressure table for the wanted CO2 volumes
+    table: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Carbonation {
+    temperature_c: f64,
+    temperature_f: f64,
+    altitude_m: f64,
+    co2_volumes: f64,
+    pressure_psi: f64,
+    pressure_bar: f64,
+}
+
+impl Carbonation {
+    fn new(temp: Temperature, altitude: f64, co2_volumes: f64, pressure: Pressure) -> Self {
+        Self {
+            temperature_c: temp.as_celsius(),
+            temperature_f: temp.as_fahrenheit(),
+            altitude_m: altitude,
+            co2_volumes,
+            pressure_psi: pressure.as_psi(),
+            pressure_bar: pressure.as_bars(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ForcedCarbonationReport {
+    #[serde(flatten)]
+    carbonation: Carbonation,
+    /// regulator pressures over a range of temperatures
+    table: Vec<Carbonation>,
+    #[serde(skip)]
+    from_pressure: bool,
+}
+
+impl Report for ForcedCarbonationReport {
+    fn print_table(&self, units: UnitSystem) {
+        let carbonation = &self.carbonation;
+        let pressure = units.pressure(Pressure::from_psi(carbonation.pressure_psi));
+        println!(
+            "Temperature: {}",
+            units.temperature(Temperature::from_celsius(carbonation.temperature_c))
+        );
+        println!(
+            "Altitude: {}",
+            units.length(Length::from_meters(carbonation.altitude_m))
+        );
+
+        if self.from_pressure {
+            println!("Regulator pressure: {}", pressure);
+            println!("Volumes of CO2: {:.2}", carbonation.co2_volumes);
+            return;
+        }
+
+        println!("Volumes of CO2: {}", carbonation.co2_volumes);
+        println!("Regulator pressure: {}", pressure);
+
+        if !self.table.is_empty() {
+            println!("=========================");
+            println!("| {:>9} | {:>9} |", "Temp", "Pressure");
+            println!("=========================");
+            for row in &self.table {
+                println!(
+                    "| {:>9} | {:>9} |",
+                    units.temperature(Temperature::from_celsius(row.temperature_c)),
+                    units.pressure(Pressure::from_psi(row.pressure_psi))
+                );
+            }
+            println!("=========================");
+        }
+    }
+
+    fn write_csv<W: io::Write>(&self, writer: &mut csv::Writer<W>) -> csv::Result<()> {
+        if self.table.is_empty() {
+            return writer.serialize(&self.carbonation);
+        }
+        self.table.iter().try_for_each(|row| writer.serialize(row))
+    }
+}
+
+pub fn calculate_and_print(
+    forced_carbonation: ForcedCarbonationOptions,
+    output: Output,
+) -> Result<()> {
+    let temp = forced_carbonation.temp;
+    let altitude = forced_carbonation.altitude;
+    let correction = calculate_altitude_correction(altitude)?;
+
+    let report = match (forced_carbonation.co2_volumes, forced_carbonation.pressure) {
+        (Some(co2_volumes), _) => {
+            let pressure_at = |temp: Temperature| -> Result<Pressure> {
+                Ok(Pressure::from_psi(
+                    calculate_pressure(temp.as_fahrenheit(), co2_volumes)? + correction,
+                ))
+            };
+
+            let mut table = Vec::new();
+            if forced_carbonation.table {
+                for celsius in (0..=24).step_by(2) {
+                    let temp = Temperature::from_celsius(celsius as f64);
+                    table.push(Carbonation::new(
+                        temp,
+                        altitude,
+                        co2_volumes,
+                        pressure_at(temp)?,
+                    ));
+                }
+            }
+
+            ForcedCarbonationReport {
+                carbonation: Carbonation::new(temp, altitude, co2_volumes, pressure_at(temp)?),
+                table,
+                from_pressure: false,
+            }
+        }
+        (None, Some(pressure)) => ForcedCarbonationReport {
+            carbonation: Carbonation::new(
+                temp,
+                altitude,
+                calculate_co2_volumes(temp.as_fahrenheit(), pressure.as_psi() - correction)?,
+                pressure,
+            ),
+            table: Vec::new(),
+            from_pressure: true,
+        },
+        (None, None) => unreachable!(),
+    };
+
+    output.print(&report)
+}
@@ -0,0 +1,76 @@
+use crate::output::{Output, Report};
+use crate::recipe::RecipeReport;
+use crate::units::UnitSystem;
+use anyhow::{anyhow, Result};
+use rustybeer::calculators::generator::generate;
+use rustybeer_util::beer_styles::find_style;
+use rustybeer_util::{conversions::VolumeParser, measurements::Volume};
+use serde::Serialize;
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "generate")]
+/// Generates a starting recipe landing in the middle of the OG, FG, IBU and color ranges of a style
+pub struct GenerateOptions {
+    #[structopt(short, long)]
+    /// Style name or code (e.g. 'American IPA', 21A) to generate a recipe for
+    style: String,
+
+    #[structopt(short, long, parse(try_from_str = VolumeParser::parse), default_value = "20")]
+    /// Batch volume with unit (l, gal, etc.). Defaults to liters.
+    volume: Volume,
+
+    #[structopt(short, long, default_value = "72")]
+    /// Brewhouse efficiency in percent
+    efficiency: f64,
+
+    #[structopt(long)]
+    /// Seed for picking the ingredients. The same seed gives the same recipe, defaults to a new one on every run.
+    seed: Option<u64>,
+}
+
+/// A generated recipe with the style and seed it was generated from
+#[derive(Debug, Serialize)]
+struct GeneratedReport {
+    style: &'static str,
+    code: &'static str,
+    seed: u64,
+    #[serde(flatten)]
+    recipe: RecipeReport,
+}
+
+impl Report for GeneratedReport {
+    fn print_table(&self, units: UnitSystem) {
+        println!("Style: {} ({})", self.style, self.code);
+        println!("Seed: {}", self.seed);
+        self.recipe.print_table(units);
+    }
+
+    fn write_csv<W: io::Write>(&self, writer: &mut csv::Writer<W>) -> csv::Result<()> {
+        self.recipe.write_csv(writer)
+    }
+}
+
+pub fn calculate_and_print(generate_options: GenerateOptions, output: Output) -> Result<()> {
+    let style = find_style(&generate_options.style)
+        .ok_or_else(|| anyhow!("Could not find beer style '{}'", generate_options.style))?;
+    let seed = match generate_options.seed {
+        Some(seed) => seed,
+        None => SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+    };
+
+    let generated = generate(
+        style,
+        generate_options.volume,
+        generate_options.efficiency / 100.,
+        seed,
+    )?;
+    output.print(&GeneratedReport {
+        style: &style.name,
+        code: &style.code,
+        seed,
+        recipe: RecipeReport::new(generated.recipe)?.with_final_gravity(generated.final_gravity)?,
+    })
+}
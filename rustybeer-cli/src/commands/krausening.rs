@@ -0,0 +1,116 @@
+use crate::output::{Output, Report};
+use crate::units::UnitSystem;
+use anyhow::Result;
+use rustybeer::calculators::priming::{calculate_co2, calculate_krausen, calculate_speise};
+use rustybeer_util::{
+    conversions::{TemperatureParser, VolumeParser},
+    measurements::{Temperature, Volume},
+};
+use serde::Serialize;
+use structopt::{clap::ArgGroup, StructOpt};
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "krausening", group = ArgGroup::with_name("addition").required(true))]
+/// Calculates the volume of speise (saved wort) or krausen (fermenting beer) needed for priming
+pub struct KrauseningOptions {
+    #[structopt(short, long, parse(try_from_str = TemperatureParser::parse))]
+    /// Temperature of beer with unit (C, F, K). Defaults to Celsius.
+    temp: Temperature,
+
+    #[structopt(short, long = "amount", parse(try_from_str = VolumeParser::parse))]
+    /// Amount being packaged with unit (l, ml, gal, etc.). Defaults to liters.
+    amount: Volume,
+
+    #[structopt(short, long = "co2_volumes", default_value = "2.0")]
+    /// Volumes of wanted CO2, depends on beer style (e.g. British Style Ales 1.5 to 2.0)
+    co2_volumes: f64,
+
+    #[structopt(short, long)]
+    /// Current gravity of the beer, which the addition ferments down to
+    fg: f64,
+
+    #[structopt(short, long, group = "addition")]
+    /// Gravity of the speise (unfermented wort)
+    speise: Option<f64>,
+
+    #[structopt(short, long, group = "addition")]
+    /// Current gravity of the krausen (actively fermenting beer)
+    krausen: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KrauseningReport {
+    amount_l: f64,
+    co2_volumes: f64,
+    residual_co2_volumes: f64,
+    beer_gravity: f64,
+    /// "speise" or "krausen"
+    addition: &'static str,
+    addition_gravity: f64,
+    addition_l: f64,
+}
+
+impl Report for KrauseningReport {
+    fn print_table(&self, units: UnitSystem) {
+        let name = match self.addition {
+            "speise" => "Speise",
+            _ => "Krausen",
+        };
+        println!(
+            "Amount: {}",
+            units.volume(Volume::from_litres(self.amount_l))
+        );
+        println!("Volumes of CO2: {}", self.co2_volumes);
+        println!("CO2 in Beer: {:.2} volumes", self.residual_co2_volumes);
+        println!("Beer gravity: {:.3}", self.beer_gravity);
+        println!("{} gravity: {:.3}", name, self.addition_gravity);
+        println!(
+            "{} to add: {}",
+            name,
+            units.volume(Volume::from_litres(self.addition_l))
+        );
+    }
+}
+
+pub fn calculate_and_print(krausening: KrauseningOptions, output: Output) -> Result<()> {
+    let fahrenheit = krausening.temp.as_fahrenheit();
+    let amount = krausening.amount.as_litres();
+
+    let (addition, gravity, volume) = match (krausening.speise, krausening.krausen) {
+        (Some(gravity), _) => (
+            "speise",
+            gravity,
+            calculate_speise(
+                fahrenheit,
+                amount,
+                krausening.co2_volumes,
+                gravity,
+                krausening.fg,
+            )?,
+        ),
+        (None, Some(gravity)) => (
+            "krausen",
+            gravity,
+            calculate_krausen(
+                fahrenheit,
+                amount,
+                krausening.co2_volumes,
+                gravity,
+                krausening.fg,
+            )?,
+        ),
+        (None, None) => unreachable!(),
+    };
+
+    let report = KrauseningReport {
+        amount_l: amount,
+        co2_volumes: krausening.co2_volumes,
+        residual_co2_volumes: calculate_co2(fahrenheit),
+        beer_gravity: krausening.fg,
+        addition,
+        addition_gravity: gravity,
+        addition_l: volume,
+    };
+
+    output.print(&report)
+}
@@ -2,11 +2,23 @@ pub mod abv;
 pub mod alcohol_volume_weight;
 pub mod beer_style;
 pub mod boil_off;
+pub mod brew;
 pub mod calories;
+pub mod compare;
+pub mod design;
 pub mod diluting;
+pub mod draft_line;
+pub mod efficiency;
 pub mod fg;
+pub mod forced_carbonation;
+pub mod generate;
+pub mod krausening;
 pub mod num_bottles;
 pub mod priming;
+pub mod recipe;
+pub mod scale;
+pub mod sessions;
 pub mod sg_correction;
+pub mod timeline;
 pub mod yeast;
 pub mod yeast_viability;
@@ -1,5 +1,14 @@
-use rustybeer::calculators::num_bottles::calculate_num_bottles;
-use rustybeer_util::{conversions::VolumeParser, measurements::Volume};
+use crate::output::{Output, Report};
+use crate::units::UnitSystem;
+use anyhow::{anyhow, Result};
+use rustybeer::calculators::num_bottles::{calculate_num_bottles, calculate_packing, BottleStock};
+use rustybeer_util::{
+    containers::{all_containers, Container},
+    conversions::VolumeParser,
+    measurements::Volume,
+};
+use serde::Serialize;
+use std::io;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -12,19 +21,180 @@ pub struct NumBottlesOptions {
     #[structopt(short, long, parse(try_from_str = VolumeParser::parse))]
     /// Volume as a string ('e.g 10mL, 4gal')
     volume: Volume,
+
+    #[structopt(short, long, parse(try_from_str = parse_stock))]
+    /// Bottles at hand as count and volume or container name (e.g. '24x330ml', '2x5 liter mini keg'), can be given multiple times
+    stock: Vec<BottleStock>,
+
+    #[structopt(short, long, parse(try_from_str = VolumeParser::parse), default_value = "0")]
+    /// Volume lost to trub and not bottled
+    trub: Volume,
+
+    #[structopt(long, default_value = "0")]
+    /// Share of each bottle left as headspace in percent
+    headspace: f64,
 }
 
-pub fn calculate_and_print(num_bottles_options: NumBottlesOptions) {
-    let volume = num_bottles_options.volume.as_milliliters();
-    println!("Volume to contain: {} ml", volume);
-    println!("=========================================================");
-    let bottles = calculate_num_bottles(volume);
-    for bottle in bottles {
-        let output = format!(
-            "| Type: {0: <20} | Quantity required: {1: <5} |",
-            bottle.0, bottle.1
+/// Parses bottles at hand from a string like '24x330ml' or '2x5 liter mini keg'
+fn parse_stock(val: &str) -> Result<BottleStock> {
+    let (quantity, container) = val.split_once('x').ok_or_else(|| {
+        anyhow!(
+            "expected bottles as <count>x<volume or container name>, got '{}'",
+            val
+        )
+    })?;
+    let quantity = quantity.trim().parse::<u32>()?;
+    let container = container.trim();
+    let containers = all_containers()?;
+    let container = match VolumeParser::parse(container) {
+        Ok(volume) => {
+            let volume = volume.as_milliliters();
+            containers
+                .iter()
+                .find(|c| (c.volume - volume).abs() < 1.0)
+                .cloned()
+                .unwrap_or_else(|| Container::new(format!("{} ml bottle", volume), volume))
+        }
+        Err(_) => containers
+            .iter()
+            .find(|c| c.name.eq_ignore_ascii_case(container))
+            .cloned()
+            .ok_or_else(|| anyhow!("unknown container '{}'", container))?,
+    };
+
+    Ok(BottleStock::new(container, quantity))
+}
+
+/// Number of bottles of one container
+#[derive(Debug, Serialize)]
+pub struct BottleRow {
+    container: String,
+    volume_ml: f64,
+    quantity: u32,
+}
+
+/// One container of the report with the volumes of the whole report
+/// as a flat CSV record
+#[derive(Debug, Serialize)]
+struct BottleCsvRow<'a> {
+    volume_to_contain_ml: f64,
+    packaged_ml: Option<f64>,
+    leftover_ml: Option<f64>,
+    container: Option<&'a str>,
+    volume_ml: Option<f64>,
+    quantity: Option<u32>,
+}
+
+/// Bottles to fill from the bottles at hand, or the number of each
+/// container needed when no bottles at hand are given
+#[derive(Debug, Serialize)]
+pub struct NumBottlesReport {
+    volume_ml: f64,
+    bottles: Vec<BottleRow>,
+    packaged_ml: Option<f64>,
+    leftover_ml: Option<f64>,
+}
+
+impl NumBottlesReport {
+    fn csv_row<'a>(&'a self, bottle: Option<&'a BottleRow>) -> BottleCsvRow<'a> {
+        BottleCsvRow {
+            volume_to_contain_ml: self.volume_ml,
+            packaged_ml: self.packaged_ml,
+            leftover_ml: self.leftover_ml,
+            container: bottle.map(|bottle| bottle.container.as_str()),
+            volume_ml: bottle.map(|bottle| bottle.volume_ml),
+            quantity: bottle.map(|bottle| bottle.quantity),
+        }
+    }
+}
+
+impl Report for NumBottlesReport {
+    fn print_table(&self, units: UnitSystem) {
+        println!(
+            "Volume to contain: {}",
+            units.volume(Volume::from_milliliters(self.volume_ml))
         );
-        println!("{}", output);
+        println!("=========================================================");
+        for bottle in &self.bottles {
+            let output = match self.packaged_ml {
+                Some(_) => format!(
+                    "| Type: {0: <20} | Quantity to fill: {1: <6} |",
+                    bottle.container, bottle.quantity
+                ),
+                None => format!(
+                    "| Type: {0: <20} | Quantity required: {1: <5} |",
+                    bottle.container, bottle.quantity
+                ),
+            };
+            println!("{}", output);
+        }
+        println!("=========================================================");
+        if let (Some(packaged), Some(leftover)) = (self.packaged_ml, self.leftover_ml) {
+            println!(
+                "Packaged: {}",
+                units.volume(Volume::from_milliliters(packaged))
+            );
+            println!(
+                "Left over: {}",
+                units.volume(Volume::from_milliliters(leftover))
+            );
+        }
+    }
+
+    fn write_csv<W: io::Write>(&self, writer: &mut csv::Writer<W>) -> csv::Result<()> {
+        // The volumes are written even when there are no bottles to fill
+        if self.bottles.is_empty() {
+            return writer.serialize(self.csv_row(None));
+        }
+        self.bottles
+            .iter()
+            .try_for_each(|bottle| writer.serialize(self.csv_row(Some(bottle))))
     }
-    println!("=========================================================");
+}
+
+pub fn calculate_and_print(num_bottles_options: NumBottlesOptions, output: Output) -> Result<()> {
+    let volume = num_bottles_options.volume.as_milliliters();
+
+    let report = if !num_bottles_options.stock.is_empty() {
+        let plan = calculate_packing(
+            volume,
+            num_bottles_options.trub.as_milliliters(),
+            num_bottles_options.headspace / 100.0,
+            &num_bottles_options.stock,
+        )?;
+        NumBottlesReport {
+            volume_ml: volume,
+            bottles: plan
+                .bottles
+                .into_iter()
+                .map(|bottle| BottleRow {
+                    container: bottle.container.name,
+                    volume_ml: bottle.container.volume,
+                    quantity: bottle.quantity,
+                })
+                .collect(),
+            packaged_ml: Some(plan.packaged),
+            leftover_ml: Some(plan.leftover),
+        }
+    } else {
+        let containers = all_containers()?;
+        NumBottlesReport {
+            volume_ml: volume,
+            bottles: calculate_num_bottles(volume)?
+                .into_iter()
+                .map(|(name, quantity)| BottleRow {
+                    volume_ml: containers
+                        .iter()
+                        .find(|container| container.name == name)
+                        .map_or(0.0, |container| container.volume),
+                    container: name,
+                    quantity: quantity as u32,
+                })
+                .collect(),
+            packaged_ml: None,
+            leftover_ml: None,
+        }
+    };
+
+    output.print(&report)
 }
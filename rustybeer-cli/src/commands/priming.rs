@@ -1,8 +1,14 @@
-use rustybeer::calculators::priming::{calculate_co2, calculate_sugars};
+use crate::output::{Output, Report};
+use crate::units::UnitSystem;
+use anyhow::{anyhow, Result};
+use rustybeer::calculators::priming::{calculate_co2, calculate_sugar, calculate_syrup};
 use rustybeer_util::{
     conversions::{TemperatureParser, VolumeParser},
-    measurements::{Temperature, Volume},
+    measurements::{Mass, Temperature, Volume},
+    sugars::{SugarForm, SUGARS},
 };
+use serde::Serialize;
+use std::io;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -20,20 +26,177 @@ pub struct PrimingOptions {
     #[structopt(short, long = "co2_volumes", default_value = "2.0")]
     /// Volumes of wanted CO2, depends on beer style (e.g. British Style Ales 1.5 to 2.0)
     co2_volumes: f64,
+
+    #[structopt(short, long)]
+    /// Priming sugar to use (e.g. 'honey', 'corn sugar'). Defaults to listing all sugars.
+    sugar: Option<String>,
+
+    #[structopt(long, requires("sugar"))]
+    /// Concentration of the sugar dissolved in a priming syrup in g/l
+    syrup: Option<f64>,
+}
+
+/// Amount of a priming sugar to add
+#[derive(Debug, Serialize)]
+pub struct SugarRow {
+    name: String,
+    form: SugarForm,
+    grams: f64,
+    milliliters: f64,
+    /// volume of priming syrup when a syrup concentration is given
+    syrup_ml: Option<f64>,
+}
+
+/// Amount of one priming sugar with the inputs of the report
+/// as a flat CSV record
+#[derive(Debug, Serialize)]
+struct SugarCsvRow<'a> {
+    amount_l: f64,
+    temperature_f: f64,
+    co2_volumes: f64,
+    residual_co2_volumes: f64,
+    syrup_concentration_g_l: Option<f64>,
+    name: &'a str,
+    form: SugarForm,
+    grams: f64,
+    milliliters: f64,
+    syrup_ml: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PrimingReport {
+    amount_l: f64,
+    temperature_f: f64,
+    co2_volumes: f64,
+    residual_co2_volumes: f64,
+    syrup_concentration_g_l: Option<f64>,
+    sugars: Vec<SugarRow>,
+    #[serde(skip)]
+    single: bool,
 }
 
-pub fn calculate_and_print(priming: PrimingOptions) {
+impl Report for PrimingReport {
+    fn print_table(&self, units: UnitSystem) {
+        println!(
+            "Amount: {}",
+            units.volume(Volume::from_litres(self.amount_l))
+        );
+        println!("Volumes of CO2: {}", self.co2_volumes);
+        println!(
+            "Temperature: {}",
+            units.temperature(Temperature::from_fahrenheit(self.temperature_f))
+        );
+        println!("CO2 in Beer: {:.2} volumes", self.residual_co2_volumes);
+
+        if !self.single {
+            println!("Priming Sugar Options:");
+            for sugar in &self.sugars {
+                println!(
+                    "{:>23}: {}",
+                    sugar.name,
+                    units.mass(Mass::from_grams(sugar.grams))
+                );
+            }
+            return;
+        }
+
+        for sugar in &self.sugars {
+            let volume = Volume::from_milliliters(sugar.milliliters);
+            match sugar.form {
+                SugarForm::Solid => println!(
+                    "{}: {} ({:.1} tsp / {:.1} tbsp)",
+                    sugar.name,
+                    units.mass(Mass::from_grams(sugar.grams)),
+                    volume.as_teaspoons(),
+                    volume.as_tablespoons()
+                ),
+                SugarForm::Syrup => println!(
+                    "{}: {} ({})",
+                    sugar.name,
+                    units.mass(Mass::from_grams(sugar.grams)),
+                    units.volume(volume)
+                ),
+            }
+
+            if let (Some(concentration), Some(syrup)) =
+                (self.syrup_concentration_g_l, sugar.syrup_ml)
+            {
+                println!(
+                    "Priming syrup ({} g/l): {}",
+                    concentration,
+                    units.volume(Volume::from_milliliters(syrup))
+                );
+            }
+        }
+    }
+
+    fn write_csv<W: io::Write>(&self, writer: &mut csv::Writer<W>) -> csv::Result<()> {
+        self.sugars.iter().try_for_each(|sugar| {
+            writer.serialize(SugarCsvRow {
+                amount_l: self.amount_l,
+                temperature_f: self.temperature_f,
+                co2_volumes: self.co2_volumes,
+                residual_co2_volumes: self.residual_co2_volumes,
+                syrup_concentration_g_l: self.syrup_concentration_g_l,
+                name: &sugar.name,
+                form: sugar.form,
+                grams: sugar.grams,
+                milliliters: sugar.milliliters,
+                syrup_ml: sugar.syrup_ml,
+            })
+        })
+    }
+}
+
+pub fn calculate_and_print(priming: PrimingOptions, output: Output) -> Result<()> {
     let fahrenheit = priming.temp.as_fahrenheit();
     let amount = priming.amount.as_litres();
-    let co2_beer = calculate_co2(fahrenheit);
-    let sugars = calculate_sugars(fahrenheit, amount, priming.co2_volumes);
-
-    println!("Amount: {}l", amount);
-    println!("Volumes of CO2: {}", priming.co2_volumes);
-    println!("Temperature: {}C", fahrenheit);
-    println!("CO2 in Beer: {:.2} volumes", co2_beer);
-    println!("Priming Sugar Options:");
-    for sugar in sugars.iter() {
-        println!("{:>23}: {:.2} g", sugar.name, sugar.ratio);
-    }
+
+    let sugars = match &priming.sugar {
+        Some(name) => {
+            let criteria = name.to_lowercase();
+            let sugar = SUGARS
+                .iter()
+                .find(|sugar| sugar.name.to_lowercase().contains(&criteria))
+                .ok_or_else(|| anyhow!("Could not find priming sugar matching '{}'", name))?;
+            vec![sugar]
+        }
+        None => SUGARS.iter().collect(),
+    };
+
+    let sugars = sugars
+        .into_iter()
+        .map(|sugar| {
+            let sugar_amount = calculate_sugar(fahrenheit, amount, priming.co2_volumes, sugar)?;
+            let syrup_ml = match priming.syrup {
+                Some(concentration) => Some(calculate_syrup(
+                    fahrenheit,
+                    amount,
+                    priming.co2_volumes,
+                    sugar,
+                    concentration,
+                )?),
+                None => None,
+            };
+            Ok(SugarRow {
+                name: sugar_amount.name,
+                form: sugar_amount.form,
+                grams: sugar_amount.grams,
+                milliliters: sugar_amount.milliliters,
+                syrup_ml,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let report = PrimingReport {
+        amount_l: amount,
+        temperature_f: fahrenheit,
+        co2_volumes: priming.co2_volumes,
+        residual_co2_volumes: calculate_co2(fahrenheit),
+        syrup_concentration_g_l: priming.syrup,
+        sugars,
+        single: priming.sugar.is_some(),
+    };
+
+    output.print(&report)
 }
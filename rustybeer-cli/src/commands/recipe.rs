@@ -0,0 +1,187 @@
+use crate::output::{Output, Report};
+use crate::recipe::{read_recipe, RecipeFile, RecipeReport};
+use crate::units::UnitSystem;
+use anyhow::{anyhow, Result};
+use rustybeer::calculators::calorie_counter::total_calories;
+use rustybeer::calculators::num_bottles::calculate_num_bottles;
+use rustybeer::calculators::priming::{calculate_sugar, residual_co2, SugarAmount};
+use rustybeer_util::{
+    measurements::{Mass, Temperature, Volume},
+    sugars::SUGARS,
+};
+use serde::Serialize;
+use std::io;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "recipe")]
+/// Prints the brew sheet of a recipe file: gravities, ABV, IBU, color, calories, priming and bottles
+pub struct RecipeOptions {
+    #[structopt(parse(from_os_str))]
+    /// Recipe file in TOML, YAML or JSON
+    file: PathBuf,
+}
+
+/// Bottles of one container needed for the packaged beer
+#[derive(Debug, Serialize)]
+struct BottleCount {
+    container: String,
+    count: i32,
+}
+
+/// One value of the brew sheet as a flat CSV record
+#[derive(Debug, Serialize)]
+struct BrewSheetRow {
+    section: &'static str,
+    name: String,
+    value: f64,
+    unit: &'static str,
+}
+
+/// A recipe with the values calculated for brewing and packaging it
+#[derive(Debug, Serialize)]
+struct BrewSheet {
+    #[serde(flatten)]
+    recipe: RecipeReport,
+    serving_ml: f64,
+    calories_per_serving_kcal: f64,
+    packaged_volume_l: f64,
+    co2_volumes: f64,
+    temperature_c: f64,
+    residual_co2_volumes: f64,
+    priming: Vec<SugarAmount>,
+    bottles: Vec<BottleCount>,
+}
+
+impl Report for BrewSheet {
+    fn print_table(&self, units: UnitSystem) {
+        self.recipe.print_table(units);
+        println!(
+            "Calories: {:.0} kcal per {}",
+            self.calories_per_serving_kcal,
+            units.volume(Volume::from_milliliters(self.serving_ml))
+        );
+
+        println!(
+            "Packaging: {} at {} volumes of CO2, {}",
+            units.volume(Volume::from_litres(self.packaged_volume_l)),
+            self.co2_volumes,
+            units.temperature(Temperature::from_celsius(self.temperature_c))
+        );
+        println!("CO2 in Beer: {:.2} volumes", self.residual_co2_volumes);
+        println!("Priming Sugar Options:");
+        for sugar in &self.priming {
+            println!(
+                "{:>23}: {}",
+                sugar.name,
+                units.mass(Mass::from_grams(sugar.grams))
+            );
+        }
+        println!("Bottles:");
+        for bottle in &self.bottles {
+            println!("{:>23}: {}", bottle.container, bottle.count);
+        }
+    }
+
+    fn write_csv<W: io::Write>(&self, writer: &mut csv::Writer<W>) -> csv::Result<()> {
+        let row = |section, name: &str, value, unit| BrewSheetRow {
+            section,
+            name: name.to_owned(),
+            value,
+            unit,
+        };
+        let recipe = &self.recipe;
+        let mut rows = vec![
+            row("recipe", "original_gravity", recipe.original_gravity, "sg"),
+            row(
+                "recipe",
+                "final_gravity",
+                recipe.final_gravity.unwrap_or_default(),
+                "sg",
+            ),
+            row("recipe", "abv", recipe.abv.unwrap_or_default(), "%"),
+            row("recipe", "ibu", recipe.ibu, "IBU"),
+            row("recipe", "color", recipe.color_srm, "SRM"),
+            row("serving", "volume", self.serving_ml, "ml"),
+            row(
+                "serving",
+                "calories",
+                self.calories_per_serving_kcal,
+                "kcal",
+            ),
+            row("packaging", "volume", self.packaged_volume_l, "l"),
+            row("packaging", "co2_volumes", self.co2_volumes, "volumes"),
+            row("packaging", "temperature", self.temperature_c, "C"),
+        ];
+        rows.extend(
+            self.priming
+                .iter()
+                .map(|sugar| row("priming", &sugar.name, sugar.grams, "g")),
+        );
+        rows.extend(
+            self.bottles
+                .iter()
+                .map(|bottle| row("bottles", &bottle.container, bottle.count.into(), "bottles")),
+        );
+        rows.into_iter().try_for_each(|row| writer.serialize(row))
+    }
+}
+
+pub fn calculate_and_print(recipe_options: RecipeOptions, output: Output) -> Result<()> {
+    let file: RecipeFile = read_recipe(&recipe_options.file)?;
+    let packaging = &file.packaging;
+    let original_gravity = file.recipe.original_gravity()?;
+    let final_gravity = file.final_gravity()?;
+
+    // Calories are calculated for a 12 oz. serving
+    let calories = total_calories(original_gravity, final_gravity)?.as_kcalories()
+        / Volume::from_fluid_ounces(12.).as_milliliters()
+        * packaging.serving_ml;
+
+    let volume = file.packaged_volume_liters();
+    let temperature = Temperature::from_celsius(packaging.temperature_celsius);
+    let sugars = if packaging.sugars.is_empty() {
+        SUGARS.iter().collect()
+    } else {
+        packaging
+            .sugars
+            .iter()
+            .map(|name| {
+                let criteria = name.to_lowercase();
+                SUGARS
+                    .iter()
+                    .find(|sugar| sugar.name.to_lowercase().contains(&criteria))
+                    .ok_or_else(|| anyhow!("Could not find priming sugar matching '{}'", name))
+            })
+            .collect::<Result<Vec<_>>>()?
+    };
+    let priming = sugars
+        .into_iter()
+        .map(|sugar| {
+            calculate_sugar(
+                temperature.as_fahrenheit(),
+                volume,
+                packaging.co2_volumes,
+                sugar,
+            )
+        })
+        .collect::<rustybeer::Result<Vec<_>>>()?;
+    let bottles = calculate_num_bottles(Volume::from_litres(volume).as_milliliters())?
+        .into_iter()
+        .map(|(container, count)| BottleCount { container, count })
+        .collect();
+
+    let sheet = BrewSheet {
+        serving_ml: packaging.serving_ml,
+        calories_per_serving_kcal: calories,
+        packaged_volume_l: volume,
+        co2_volumes: packaging.co2_volumes,
+        temperature_c: packaging.temperature_celsius,
+        residual_co2_volumes: residual_co2(temperature),
+        priming,
+        bottles,
+        recipe: RecipeReport::new(file.recipe)?.with_final_gravity(final_gravity)?,
+    };
+    output.print(&sheet)
+}
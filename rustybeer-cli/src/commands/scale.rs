@@ -0,0 +1,36 @@
+use crate::output::Output;
+use crate::recipe::{read_recipe, RecipeReport};
+use anyhow::Result;
+use rustybeer::calculators::scaling::scale;
+use rustybeer::recipe::Recipe;
+use rustybeer_util::{conversions::VolumeParser, measurements::Volume};
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "scale")]
+/// Scales a recipe to a new batch volume and brewhouse efficiency keeping its OG and IBU
+pub struct ScaleOptions {
+    #[structopt(short, long, parse(from_os_str))]
+    /// Recipe file in JSON, TOML or YAML
+    recipe: PathBuf,
+
+    #[structopt(short, long, parse(try_from_str = VolumeParser::parse))]
+    /// New batch volume with unit (l, gal, etc.). Defaults to liters.
+    volume: Volume,
+
+    #[structopt(short, long)]
+    /// Brewhouse efficiency of the new equipment in percent. Defaults to the efficiency of the recipe.
+    efficiency: Option<f64>,
+}
+
+pub fn calculate_and_print(scale_options: ScaleOptions, output: Output) -> Result<()> {
+    let recipe: Recipe = read_recipe(&scale_options.recipe)?;
+    let efficiency = match scale_options.efficiency {
+        Some(percent) => percent / 100.,
+        None => recipe.efficiency,
+    };
+
+    let recipe = scale(&recipe, scale_options.volume, efficiency)?;
+    output.print(&RecipeReport::new(recipe)?)
+}
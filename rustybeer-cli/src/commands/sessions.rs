@@ -0,0 +1,241 @@
+use crate::output::{Output, Report};
+use crate::recipe::{read_recipe, RecipeFile};
+use crate::units::UnitSystem;
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use rustybeer::session::{BrewSession, Measured, SessionResults};
+use rustybeer_util::measurements::Volume;
+use serde::Serialize;
+use std::io;
+use std::path::{Path, PathBuf};
+use structopt::StructOpt;
+
+/// Format of the brew and packaging dates
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "sessions")]
+/// Lists brew session files by brew date with the measured and predicted OG, FG, ABV,
+/// attenuation, volume and efficiencies, and how the efficiency changed over time
+pub struct SessionsOptions {
+    #[structopt(parse(from_os_str), required = true)]
+    /// Brew session files in TOML, YAML or JSON. The recipe file of a session
+    /// is found relative to the session file.
+    files: Vec<PathBuf>,
+}
+
+/// A brew session with the values it reached
+#[derive(Debug, Serialize)]
+struct SessionReport {
+    recipe_name: String,
+    #[serde(flatten)]
+    session: BrewSession,
+    results: SessionResults,
+    days_to_packaging: Option<i64>,
+    #[serde(skip)]
+    brew_date: NaiveDate,
+}
+
+/// One session as a flat CSV record
+#[derive(Debug, Serialize)]
+struct SessionRow<'a> {
+    brew_date: &'a str,
+    recipe: &'a str,
+    original_gravity: Option<f64>,
+    predicted_original_gravity: f64,
+    final_gravity: Option<f64>,
+    predicted_final_gravity: f64,
+    abv: Option<f64>,
+    predicted_abv: f64,
+    apparent_attenuation: Option<f64>,
+    predicted_apparent_attenuation: f64,
+    post_boil_volume_l: Option<f64>,
+    predicted_post_boil_volume_l: f64,
+    conversion_efficiency: Option<f64>,
+    lauter_efficiency: Option<f64>,
+    mash_efficiency: Option<f64>,
+    efficiency: Option<f64>,
+    predicted_efficiency: f64,
+    packaging_date: Option<&'a str>,
+    days_to_packaging: Option<i64>,
+}
+
+/// Brew sessions in the order they were brewed
+#[derive(Debug, Serialize)]
+struct SessionsReport {
+    sessions: Vec<SessionReport>,
+    average_efficiency: f64,
+}
+
+fn measured(value: &Measured, format: impl Fn(f64) -> String, missing: &str) -> String {
+    match value.measured {
+        Some(measured) => format!(
+            "{} (predicted {})",
+            format(measured),
+            format(value.predicted)
+        ),
+        None => format!("{} (predicted {})", missing, format(value.predicted)),
+    }
+}
+
+impl Report for SessionsReport {
+    fn print_table(&self, units: UnitSystem) {
+        let gravity = |value: f64| format!("{:.3}", value);
+        let percent = |value: f64| format!("{:.1}%", value);
+        let fraction = |value: f64| format!("{:.1}%", value * 100.);
+        let volume = |value: f64| units.volume(Volume::from_litres(value));
+
+        for report in &self.sessions {
+            let results = &report.results;
+            println!("---------------------");
+            println!(
+                "Session: {} {}",
+                report.session.brew_date, report.recipe_name
+            );
+            println!(
+                "{:>23}: {}",
+                "Original gravity",
+                measured(&results.original_gravity, gravity, "-")
+            );
+            println!(
+                "{:>23}: {}",
+                "Final gravity",
+                measured(&results.final_gravity, gravity, "fermenting")
+            );
+            println!("{:>23}: {}", "ABV", measured(&results.abv, percent, "-"));
+            println!(
+                "{:>23}: {}",
+                "Apparent attenuation",
+                measured(&results.apparent_attenuation, percent, "-")
+            );
+            println!(
+                "{:>23}: {}",
+                "Post-boil volume",
+                measured(&results.post_boil_volume_liters, volume, "-")
+            );
+            let efficiencies = [
+                ("Conversion efficiency", results.conversion_efficiency),
+                ("Lauter efficiency", results.lauter_efficiency),
+                ("Mash efficiency", results.mash_efficiency),
+            ];
+            for (name, efficiency) in efficiencies {
+                if let Some(efficiency) = efficiency {
+                    println!("{:>23}: {}", name, fraction(efficiency));
+                }
+            }
+            println!(
+                "{:>23}: {}",
+                "Brewhouse efficiency",
+                measured(&results.efficiency, fraction, "-")
+            );
+            if let (Some(date), Some(days)) =
+                (&report.session.packaging_date, report.days_to_packaging)
+            {
+                println!("{:>23}: {}, {} days after brewing", "Packaged", date, days);
+            }
+            if let Some(notes) = &report.session.notes {
+                println!("{:>23}: {}", "Notes", notes);
+            }
+        }
+        println!("---------------------");
+
+        println!("Brewhouse efficiency over time:");
+        let mut previous: Option<f64> = None;
+        for report in &self.sessions {
+            let efficiency = report.results.efficiency.measured.unwrap_or_default() * 100.;
+            let change = previous
+                .map(|previous| format!(" ({:+.1}%)", efficiency - previous))
+                .unwrap_or_default();
+            println!(
+                "{:>23}: {:.1}%{} {}",
+                report.session.brew_date, efficiency, change, report.recipe_name
+            );
+            previous = Some(efficiency);
+        }
+        println!(
+            "Average brewhouse efficiency: {:.1}% over {} sessions",
+            self.average_efficiency * 100.,
+            self.sessions.len()
+        );
+    }
+
+    fn write_csv<W: io::Write>(&self, writer: &mut csv::Writer<W>) -> csv::Result<()> {
+        self.sessions.iter().try_for_each(|report| {
+            let session = &report.session;
+            let results = &report.results;
+            writer.serialize(SessionRow {
+                brew_date: &session.brew_date,
+                recipe: &report.recipe_name,
+                original_gravity: results.original_gravity.measured,
+                predicted_original_gravity: results.original_gravity.predicted,
+                final_gravity: results.final_gravity.measured,
+                predicted_final_gravity: results.final_gravity.predicted,
+                abv: results.abv.measured,
+                predicted_abv: results.abv.predicted,
+                apparent_attenuation: results.apparent_attenuation.measured,
+                predicted_apparent_attenuation: results.apparent_attenuation.predicted,
+                post_boil_volume_l: results.post_boil_volume_liters.measured,
+                predicted_post_boil_volume_l: results.post_boil_volume_liters.predicted,
+                conversion_efficiency: results.conversion_efficiency,
+                lauter_efficiency: results.lauter_efficiency,
+                mash_efficiency: results.mash_efficiency,
+                efficiency: results.efficiency.measured,
+                predicted_efficiency: results.efficiency.predicted,
+                packaging_date: session.packaging_date.as_deref(),
+                days_to_packaging: report.days_to_packaging,
+            })
+        })
+    }
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(date, DATE_FORMAT)
+        .with_context(|| format!("Date '{}' is not in the format 'YYYY-MM-DD'", date))
+}
+
+fn read_session(path: &Path) -> Result<SessionReport> {
+    let session: BrewSession = read_recipe(path)?;
+    let recipe_path = path
+        .parent()
+        .map(|dir| dir.join(&session.recipe))
+        .unwrap_or_else(|| PathBuf::from(&session.recipe));
+    let recipe: RecipeFile = read_recipe(&recipe_path)?;
+
+    let brew_date = parse_date(&session.brew_date)?;
+    let days_to_packaging = session
+        .packaging_date
+        .as_deref()
+        .map(|date| parse_date(date).map(|date| (date - brew_date).num_days()))
+        .transpose()?;
+    let results = session
+        .results(&recipe.recipe, recipe.final_gravity()?)
+        .with_context(|| format!("Invalid brew session {}", path.display()))?;
+    Ok(SessionReport {
+        recipe_name: recipe.recipe.name,
+        session,
+        results,
+        days_to_packaging,
+        brew_date,
+    })
+}
+
+pub fn calculate_and_print(sessions_options: SessionsOptions, output: Output) -> Result<()> {
+    let mut sessions = sessions_options
+        .files
+        .iter()
+        .map(|path| read_session(path))
+        .collect::<Result<Vec<_>>>()?;
+    sessions.sort_by_key(|report| report.brew_date);
+
+    // Files are required, so there is at least one session
+    let average_efficiency = sessions
+        .iter()
+        .map(|report| report.results.efficiency.measured.unwrap_or_default())
+        .sum::<f64>()
+        / sessions.len() as f64;
+
+    output.print(&SessionsReport {
+        sessions,
+        average_efficiency,
+    })
+}
@@ -1,5 +1,10 @@
-use rustybeer::calculators::sg_correction::correct_sg;
+use crate::output::{Output, Report};
+use crate::units::UnitSystem;
+use anyhow::Result;
+use rustybeer::calculators::sg_correction::correct_gravity;
+use rustybeer::units::Gravity;
 use rustybeer_util::{conversions::TemperatureParser, measurements::Temperature};
+use serde::Serialize;
 
 use structopt::StructOpt;
 
@@ -23,14 +28,43 @@ pub struct SgCorrectionOptions {
     mt: Temperature,
 }
 
-pub fn calculate_and_print(sg_correction_options: SgCorrectionOptions) {
-    let ct = sg_correction_options.ct.as_fahrenheit();
-    let mt = sg_correction_options.mt.as_fahrenheit();
-    println!("Measured gravity: {}", sg_correction_options.sg);
-    println!("Calibration temperature: {} F", ct);
-    println!("Measurement temperature: {} F", mt);
-    println!(
-        "Corrected gravity: {:.3}",
-        correct_sg(sg_correction_options.sg, ct, mt)
-    );
+#[derive(Debug, Serialize)]
+pub struct SgCorrectionReport {
+    measured_gravity: f64,
+    calibration_temperature_f: f64,
+    measurement_temperature_f: f64,
+    corrected_gravity: Gravity,
+}
+
+impl Report for SgCorrectionReport {
+    fn print_table(&self, units: UnitSystem) {
+        println!("Measured gravity: {}", self.measured_gravity);
+        println!(
+            "Calibration temperature: {}",
+            units.temperature(Temperature::from_fahrenheit(self.calibration_temperature_f))
+        );
+        println!(
+            "Measurement temperature: {}",
+            units.temperature(Temperature::from_fahrenheit(self.measurement_temperature_f))
+        );
+        println!("Corrected gravity: {}", self.corrected_gravity);
+    }
+}
+
+pub fn calculate_and_print(
+    sg_correction_options: SgCorrectionOptions,
+    output: Output,
+) -> Result<()> {
+    let report = SgCorrectionReport {
+        measured_gravity: sg_correction_options.sg,
+        calibration_temperature_f: sg_correction_options.ct.as_fahrenheit(),
+        measurement_temperature_f: sg_correction_options.mt.as_fahrenheit(),
+        corrected_gravity: correct_gravity(
+            Gravity::from_specific_gravity(sg_correction_options.sg),
+            sg_correction_options.ct,
+            sg_correction_options.mt,
+        )?,
+    };
+
+    output.print(&report)
 }
@@ -0,0 +1,309 @@
+use crate::output::{Output, Report};
+use crate::recipe::{read_recipe, RecipeFile};
+use crate::units::UnitSystem;
+use anyhow::{Context, Result};
+use chrono::{Duration, Local, NaiveDateTime, Timelike, Utc};
+use rustybeer::calculators::timeline::{timeline, BrewStep, BrewStepKind};
+use rustybeer_util::measurements::{Mass, Temperature};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::{fs, io};
+use structopt::StructOpt;
+
+/// Format of the brew day start time
+const START_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Local date-time format of iCalendar
+const ICAL_FORMAT: &str = "%Y%m%dT%H%M%S";
+
+/// Longest iCalendar content line in octets, without the line break
+const ICAL_LINE_OCTETS: usize = 75;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "timeline")]
+/// Prints the brew-day timeline of a recipe file: strike water, mash rests, sparge, boil, hop additions, whirlpool and chill
+pub struct TimelineOptions {
+    #[structopt(parse(from_os_str))]
+    /// Recipe file in TOML, YAML or JSON
+    file: PathBuf,
+
+    #[structopt(short, long, parse(try_from_str = parse_start))]
+    /// Start of the brew day as 'YYYY-MM-DD HH:MM'. Defaults to now.
+    start: Option<NaiveDateTime>,
+
+    #[structopt(long, parse(from_os_str))]
+    /// Also writes the timeline as an iCalendar file with an alarm at each step
+    ical: Option<PathBuf>,
+}
+
+fn parse_start(start: &str) -> Result<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(start, START_FORMAT)
+        .with_context(|| format!("Start '{}' is not in the format 'YYYY-MM-DD HH:MM'", start))
+}
+
+/// A brew day step at the time of day it is done
+#[derive(Debug, Serialize)]
+struct TimedStep {
+    start: String,
+    end: String,
+    #[serde(flatten)]
+    step: BrewStep,
+    #[serde(skip)]
+    start_time: NaiveDateTime,
+    #[serde(skip)]
+    end_time: NaiveDateTime,
+}
+
+/// One step of the timeline as a flat CSV record
+#[derive(Debug, Serialize)]
+struct TimelineRow<'a> {
+    start: &'a str,
+    end: &'a str,
+    kind: BrewStepKind,
+    name: &'a str,
+    duration_mins: u32,
+    temperature_celsius: Option<f64>,
+    weight_grams: Option<f64>,
+    boil_mins_left: Option<u32>,
+}
+
+/// The brew-day timeline of a recipe
+#[derive(Debug, Serialize)]
+struct TimelineReport {
+    recipe: String,
+    steps: Vec<TimedStep>,
+}
+
+/// What to do in a step, in the given units
+pub fn describe(step: &BrewStep, units: UnitSystem) -> String {
+    let temperature = || {
+        step.temperature_celsius
+            .map(|t| units.temperature(Temperature::from_celsius(t)))
+            .unwrap_or_default()
+    };
+    match step.kind {
+        BrewStepKind::HeatStrikeWater => {
+            format!("Heat strike water to mash in at {}", temperature())
+        }
+        BrewStepKind::MashRest => format!("{} at {}", step.name, temperature()),
+        BrewStepKind::BoilStart => format!("Start the boil ({} min)", step.duration_mins),
+        BrewStepKind::HopAddition => {
+            let weight = units.mass(Mass::from_grams(step.weight_grams.unwrap_or_default()));
+            match step.boil_mins_left.unwrap_or_default() {
+                0 => format!("Add {} {} at flameout", weight, step.name),
+                left => format!("Add {} {} ({} min left)", weight, step.name, left),
+            }
+        }
+        BrewStepKind::Sparge
+        | BrewStepKind::Flameout
+        | BrewStepKind::Whirlpool
+        | BrewStepKind::Chill => step.name.clone(),
+    }
+}
+
+/// Escapes text for an iCalendar property value
+fn ical_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Folds an iCalendar content line into lines of at most 75 octets,
+/// each continuation line starting with a space
+fn ical_fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > ICAL_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded
+}
+
+impl TimelineReport {
+    /// The timeline as an iCalendar document with an event for each step
+    fn to_ical(&self, units: UnitSystem) -> String {
+        let stamp = Utc::now().format("%Y%m%dT%H%M%SZ");
+        let mut ical = String::new();
+        let mut line = |text: String| {
+            ical.push_str(&ical_fold(&text));
+            ical.push_str("\r\n");
+        };
+        line("BEGIN:VCALENDAR".to_owned());
+        line("VERSION:2.0".to_owned());
+        line("PRODID:-//rustybeer//brew day timeline//EN".to_owned());
+        for (index, timed) in self.steps.iter().enumerate() {
+            let summary = ical_text(&describe(&timed.step, units));
+            line("BEGIN:VEVENT".to_owned());
+            line(format!(
+                "UID:{}-{}@rustybeer",
+                timed.start_time.format(ICAL_FORMAT),
+                index
+            ));
+            line(format!("DTSTAMP:{}", stamp));
+            line(format!("DTSTART:{}", timed.start_time.format(ICAL_FORMAT)));
+            // Events without an end last no time
+            if timed.end_time > timed.start_time {
+                line(format!("DTEND:{}", timed.end_time.format(ICAL_FORMAT)));
+            }
+            line(format!("SUMMARY:{}", summary));
+            line(format!("DESCRIPTION:{}", ical_text(&self.recipe)));
+            line("BEGIN:VALARM".to_owned());
+            line("ACTION:DISPLAY".to_owned());
+            line("TRIGGER:PT0S".to_owned());
+            line(format!("DESCRIPTION:{}", summary));
+            line("END:VALARM".to_owned());
+            line("END:VEVENT".to_owned());
+        }
+        line("END:VCALENDAR".to_owned());
+        ical
+    }
+}
+
+impl Report for TimelineReport {
+    fn print_table(&self, units: UnitSystem) {
+        println!("Recipe: {}", self.recipe);
+        for timed in &self.steps {
+            let time = if timed.end_time > timed.start_time {
+                format!(
+                    "{} - {}",
+                    timed.start_time.format("%H:%M"),
+                    timed.end_time.format("%H:%M")
+                )
+            } else {
+                timed.start_time.format("%H:%M").to_string()
+            };
+            println!("{:>13}  {}", time, describe(&timed.step, units));
+        }
+    }
+
+    fn write_csv<W: io::Write>(&self, writer: &mut csv::Writer<W>) -> csv::Result<()> {
+        self.steps.iter().try_for_each(|timed| {
+            let step = &timed.step;
+            writer.serialize(TimelineRow {
+                start: &timed.start,
+                end: &timed.end,
+                kind: step.kind,
+                name: &step.name,
+                duration_mins: step.duration_mins,
+                temperature_celsius: step.temperature_celsius,
+                weight_grams: step.weight_grams,
+                boil_mins_left: step.boil_mins_left,
+            })
+        })
+    }
+}
+
+pub fn calculate_and_print(timeline_options: TimelineOptions, output: Output) -> Result<()> {
+    let file: RecipeFile = read_recipe(&timeline_options.file)?;
+    let start = match timeline_options.start {
+        Some(start) => start,
+        None => Local::now()
+            .naive_local()
+            .with_second(0)
+            .and_then(|now| now.with_nanosecond(0))
+            .context("Could not read the current time")?,
+    };
+
+    let at = |mins: u32| start + Duration::minutes(mins.into());
+    let steps = timeline(&file.recipe, &file.brew_day)?
+        .into_iter()
+        .map(|step| {
+            let start_time = at(step.start_mins);
+            let end_time = at(step.end_mins());
+            TimedStep {
+                start: start_time.format("%Y-%m-%dT%H:%M").to_string(),
+                end: end_time.format("%Y-%m-%dT%H:%M").to_string(),
+                step,
+                start_time,
+                end_time,
+            }
+        })
+        .collect();
+    let report = TimelineReport {
+        recipe: file.recipe.name,
+        steps,
+    };
+
+    if let Some(path) = &timeline_options.ical {
+        fs::write(path, report.to_ical(output.units))
+            .with_context(|| format!("Could not write iCalendar {}", path.display()))?;
+    }
+    output.print(&report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TimedStep, TimelineReport, ICAL_FORMAT};
+    use crate::units::UnitSystem;
+    use chrono::NaiveDateTime;
+    use rustybeer::calculators::timeline::{BrewStep, BrewStepKind};
+
+    fn timed(kind: BrewStepKind, name: &str, start: &str, duration_mins: u32) -> TimedStep {
+        let start_time = NaiveDateTime::parse_from_str(start, ICAL_FORMAT).unwrap();
+        let end_time = start_time + chrono::Duration::minutes(duration_mins.into());
+        TimedStep {
+            start: String::new(),
+            end: String::new(),
+            step: BrewStep {
+                kind,
+                name: name.to_owned(),
+                start_mins: 0,
+                duration_mins,
+                temperature_celsius: None,
+                weight_grams: None,
+                boil_mins_left: None,
+            },
+            start_time,
+            end_time,
+        }
+    }
+
+    #[test]
+    fn ical() {
+        let report = TimelineReport {
+            recipe:
+                "Hazy IPA; double dry hopped, with Citra\\Mosaic and a name long enough to fold"
+                    .to_owned(),
+            steps: vec![
+                timed(BrewStepKind::Sparge, "Sparge", "20240601T100000", 30),
+                timed(BrewStepKind::Flameout, "Flameout", "20240601T120000", 0),
+            ],
+        };
+        let ical = report.to_ical(UnitSystem::Metric);
+
+        // Every line ends with CRLF and is at most 75 octets
+        assert!(ical.ends_with("\r\n"));
+        assert!(!ical.replace("\r\n", "").contains('\n'));
+        assert!(ical.split("\r\n").all(|line| line.len() <= 75));
+
+        // Long lines are folded with a space starting the continuation
+        assert!(ical.contains("\r\n "));
+        let unfolded = ical.replace("\r\n ", "");
+        assert!(unfolded.contains(
+            "DESCRIPTION:Hazy IPA\\; double dry hopped\\, with Citra\\\\Mosaic \
+             and a name long enough to fold\r\n"
+        ));
+
+        // Zero-length events have no end
+        let events: Vec<&str> = unfolded.split("BEGIN:VEVENT").skip(1).collect();
+        assert_eq!(2, events.len());
+        assert!(events[0].contains("DTSTART:20240601T100000\r\nDTEND:20240601T103000\r\n"));
+        assert!(events[1].contains("DTSTART:20240601T120000\r\n"));
+        assert!(!events[1].contains("DTEND"));
+        assert!(events[1].contains("SUMMARY:Flameout\r\n"));
+    }
+
+    #[test]
+    fn ical_fold_keeps_characters_whole() {
+        let line = format!("SUMMARY:{}", "ä".repeat(80));
+        let folded = super::ical_fold(&line);
+        assert!(folded.split("\r\n").all(|line| line.len() <= 75));
+        assert_eq!(line, folded.replace("\r\n ", ""));
+    }
+}
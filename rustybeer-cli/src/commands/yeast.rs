@@ -1,3 +1,9 @@
+use crate::output::{Output, Report};
+use crate::units::UnitSystem;
+use anyhow::Result;
+use rustybeer_util::yeasts::{Yeast, YEASTS};
+use serde::Serialize;
+use std::io;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -9,17 +15,58 @@ pub struct YeastOptions {
     name: Option<String>,
 }
 
-pub fn search_and_print(opt: YeastOptions) {
-    if let Some(name) = opt.name {
-        let criteria = name.to_lowercase();
-        for yeast in rustybeer_util::yeasts::YEASTS.iter() {
-            if yeast.name.to_lowercase().contains(&criteria) {
-                println!("{:?}", yeast);
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub struct YeastReport<'a> {
+    yeasts: Vec<&'a Yeast>,
+}
+
+impl Report for YeastReport<'_> {
+    fn print_table(&self, units: UnitSystem) {
+        for yeast in &self.yeasts {
+            println!("---------------------");
+            match &yeast.id {
+                Some(id) => println!("{} {} ({})", yeast.company, yeast.name, id),
+                None => println!("{} {}", yeast.company, yeast.name),
+            }
+            if let (Some(min), Some(max)) = (yeast.min_attenuation, yeast.max_attenuation) {
+                println!("Attenuation: {}%-{}%", min, max);
+            }
+            if let Some(flocculation) = yeast.flocculation {
+                println!("Flocculation: {:?}", flocculation);
+            }
+            if let (Some(min), Some(max)) = (yeast.min_temp, yeast.max_temp) {
+                println!(
+                    "Temperature: {} - {}",
+                    units.temperature(min),
+                    units.temperature(max)
+                );
+            }
+            if let Some(tolerance) = yeast.alc_tolerance {
+                println!("Alcohol tolerance: {}%", tolerance);
             }
         }
-    } else {
-        for yeast in rustybeer_util::yeasts::YEASTS.iter() {
-            println!("{:?}", yeast);
+    }
+
+    fn write_csv<W: io::Write>(&self, writer: &mut csv::Writer<W>) -> csv::Result<()> {
+        for yeast in &self.yeasts {
+            writer.serialize(yeast)?;
         }
+        Ok(())
     }
 }
+
+pub fn search_and_print(opt: YeastOptions, output: Output) -> Result<()> {
+    let yeasts = match opt.name {
+        Some(name) => {
+            let criteria = name.to_lowercase();
+            YEASTS
+                .iter()
+                .filter(|yeast| yeast.name.to_lowercase().contains(&criteria))
+                .collect()
+        }
+        None => YEASTS.iter().collect(),
+    };
+
+    output.print(&YeastReport { yeasts })
+}
@@ -1,6 +1,10 @@
+use crate::output::{Output, Report};
+use crate::units::UnitSystem;
+use anyhow::{Context, Result};
 use chrono::format::ParseError;
 use chrono::{Duration, Local, NaiveDate, NaiveDateTime, NaiveTime};
 use rustybeer::calculators::yeast_viability::{calculate_cc, calculate_yv};
+use serde::Serialize;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -21,23 +25,44 @@ pub struct YeastViabilityOptions {
 }
 
 fn parse_date(date: String, format: String) -> Result<NaiveDateTime, ParseError> {
-    let t = NaiveTime::from_hms(0, 0, 0);
+    let t = NaiveTime::from_hms_opt(0, 0, 0).unwrap();
     let date_only = NaiveDate::parse_from_str(&date, &format)?.and_time(t);
     Ok(date_only)
 }
 
-pub fn calculate_and_print(yv_options: YeastViabilityOptions) {
-    let mut format = String::from("%d/%m/%Y");
-    if let Some(f) = yv_options.f {
-        format = f;
-    }
-    if let Ok(date) = parse_date(yv_options.pd, format) {
-        let days = (Local::now().timestamp() - date.timestamp()) / Duration::days(1).num_seconds();
-        println!("Yeast viability: {:.3}%", calculate_yv(days as f32));
-        if let Some(cc) = yv_options.cc {
-            println!("Cell count: {:.3}", calculate_cc(cc, days as f32))
+#[derive(Debug, Serialize)]
+pub struct YeastViabilityReport {
+    days: i64,
+    viability_percent: f32,
+    cell_count: Option<f32>,
+}
+
+impl Report for YeastViabilityReport {
+    fn print_table(&self, _units: UnitSystem) {
+        println!("Yeast viability: {:.3}%", self.viability_percent);
+        if let Some(cc) = self.cell_count {
+            println!("Cell count: {:.3}", cc)
         }
-    } else {
-        println!("Date is invalid.");
     }
 }
+
+pub fn calculate_and_print(yv_options: YeastViabilityOptions, output: Output) -> Result<()> {
+    let mut format_string = String::from("%d/%m/%Y");
+    if let Some(f) = yv_options.f {
+        format_string = f;
+    }
+    let date = parse_date(yv_options.pd, format_string).context("Date is invalid.")?;
+    let days =
+        (Local::now().timestamp() - date.and_utc().timestamp()) / Duration::days(1).num_seconds();
+
+    let report = YeastViabilityReport {
+        days,
+        viability_percent: calculate_yv(days as f32)?,
+        cell_count: match yv_options.cc {
+            Some(cc) => Some(calculate_cc(cc, days as f32)?),
+            None => None,
+        },
+    };
+
+    output.print(&report)
+}
@@ -1,41 +1,80 @@
 use anyhow::{Context, Result};
 use structopt::StructOpt;
 mod commands;
+mod output;
+mod recipe;
+mod units;
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "RustyBeer", version = "0.1")]
 /// RustyBeer Calculators CLI
-pub enum RustyBeer {
+pub struct RustyBeer {
+    #[structopt(flatten)]
+    output: output::Output,
+
+    #[structopt(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, StructOpt)]
+pub enum Command {
     Abv(commands::abv::AbvOptions),
     AbvAbw(commands::alcohol_volume_weight::AbvAbwOptions),
     BeerStyle(commands::beer_style::BeerStyleOptions),
     BoilOff(commands::boil_off::BoilOffOptions),
+    Brew(commands::brew::BrewOptions),
     Calories(commands::calories::CaloriesOptions),
+    Compare(commands::compare::CompareOptions),
+    Design(commands::design::DesignOptions),
     Diluting(commands::diluting::DilutingOptions),
+    DraftLine(commands::draft_line::DraftLineOptions),
+    Efficiency(commands::efficiency::EfficiencyOptions),
     Fg(commands::fg::FgOptions),
+    ForcedCarbonation(commands::forced_carbonation::ForcedCarbonationOptions),
+    Generate(commands::generate::GenerateOptions),
+    Krausening(commands::krausening::KrauseningOptions),
     NumBottles(commands::num_bottles::NumBottlesOptions),
     Priming(commands::priming::PrimingOptions),
+    Recipe(commands::recipe::RecipeOptions),
+    Scale(commands::scale::ScaleOptions),
+    Sessions(commands::sessions::SessionsOptions),
     SgCorrection(commands::sg_correction::SgCorrectionOptions),
+    Timeline(commands::timeline::TimelineOptions),
     Yeast(commands::yeast::YeastOptions),
     YeastViability(commands::yeast_viability::YeastViabilityOptions),
 }
 
 fn main() -> Result<()> {
     let opt = RustyBeer::from_args_safe().with_context(|| "wrong arguments")?;
-    match opt {
-        RustyBeer::Abv(opts) => commands::abv::calculate_and_print(opts),
-        RustyBeer::AbvAbw(opts) => commands::alcohol_volume_weight::calculate_and_print(opts),
-        RustyBeer::BeerStyle(opts) => commands::beer_style::calculate_and_print(opts),
-        RustyBeer::BoilOff(opts) => commands::boil_off::calculate_and_print(opts),
-        RustyBeer::Calories(opts) => commands::calories::calculate_and_print(opts),
-        RustyBeer::Diluting(opts) => commands::diluting::calculate_and_print(opts),
-        RustyBeer::Fg(opts) => commands::fg::calculate_and_print(opts),
-        RustyBeer::NumBottles(opts) => commands::num_bottles::calculate_and_print(opts),
-        RustyBeer::Priming(opts) => commands::priming::calculate_and_print(opts),
-        RustyBeer::SgCorrection(opts) => commands::sg_correction::calculate_and_print(opts),
-        RustyBeer::Yeast(opts) => commands::yeast::search_and_print(opts),
-        RustyBeer::YeastViability(opts) => commands::yeast_viability::calculate_and_print(opts),
+    let output = opt.output;
+    match opt.command {
+        Command::Abv(opts) => commands::abv::calculate_and_print(opts, output),
+        Command::AbvAbw(opts) => commands::alcohol_volume_weight::calculate_and_print(opts, output),
+        Command::BeerStyle(opts) => commands::beer_style::calculate_and_print(opts, output),
+        Command::BoilOff(opts) => commands::boil_off::calculate_and_print(opts, output),
+        Command::Brew(opts) => commands::brew::run(opts, output),
+        Command::Calories(opts) => commands::calories::calculate_and_print(opts, output),
+        Command::Compare(opts) => commands::compare::calculate_and_print(opts, output),
+        Command::Design(opts) => commands::design::calculate_and_print(opts, output),
+        Command::Diluting(opts) => commands::diluting::calculate_and_print(opts, output),
+        Command::DraftLine(opts) => commands::draft_line::calculate_and_print(opts, output),
+        Command::Efficiency(opts) => commands::efficiency::calculate_and_print(opts, output),
+        Command::Fg(opts) => commands::fg::calculate_and_print(opts, output),
+        Command::ForcedCarbonation(opts) => {
+            commands::forced_carbonation::calculate_and_print(opts, output)
+        }
+        Command::Generate(opts) => commands::generate::calculate_and_print(opts, output),
+        Command::Krausening(opts) => commands::krausening::calculate_and_print(opts, output),
+        Command::NumBottles(opts) => commands::num_bottles::calculate_and_print(opts, output),
+        Command::Priming(opts) => commands::priming::calculate_and_print(opts, output),
+        Command::Recipe(opts) => commands::recipe::calculate_and_print(opts, output),
+        Command::Scale(opts) => commands::scale::calculate_and_print(opts, output),
+        Command::Sessions(opts) => commands::sessions::calculate_and_print(opts, output),
+        Command::SgCorrection(opts) => commands::sg_correction::calculate_and_print(opts, output),
+        Command::Timeline(opts) => commands::timeline::calculate_and_print(opts, output),
+        Command::Yeast(opts) => commands::yeast::search_and_print(opts, output),
+        Command::YeastViability(opts) => {
+            commands::yeast_viability::calculate_and_print(opts, output)
+        }
     }
-
-    Ok(())
 }
@@ -0,0 +1,93 @@
+//! Output formats shared by all commands
+
+use crate::units::UnitSystem;
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::{io, str::FromStr};
+use structopt::StructOpt;
+
+// Global options on how results are printed. Not a doc comment, as the doc
+// comment of a flattened struct replaces the about text of the command.
+#[derive(Debug, Clone, Copy, StructOpt)]
+pub struct Output {
+    #[structopt(
+        long = "output",
+        global = true,
+        default_value = "table",
+        possible_values = OutputFormat::VARIANTS,
+        case_insensitive = true
+    )]
+    /// Output format of the results
+    pub format: OutputFormat,
+
+    #[structopt(
+        long,
+        global = true,
+        env = "RUSTYBEER_UNITS",
+        default_value = "metric",
+        possible_values = UnitSystem::VARIANTS,
+        case_insensitive = true
+    )]
+    /// Unit system quantities are printed in
+    pub units: UnitSystem,
+}
+
+impl Output {
+    /// Prints the results of a command in the chosen format
+    pub fn print<R: Report>(self, report: &R) -> Result<()> {
+        match self.format {
+            OutputFormat::Table => report.print_table(self.units),
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(report)?),
+            OutputFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(io::stdout());
+                report.write_csv(&mut writer)?;
+                writer.flush()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How the results of a command are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human readable text and tables
+    Table,
+    /// A single JSON document
+    Json,
+    /// CSV with a header line
+    Csv,
+}
+
+impl OutputFormat {
+    pub const VARIANTS: &'static [&'static str] = &["table", "json", "csv"];
+}
+
+impl FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "table" => Ok(OutputFormat::Table),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            _ => Err(anyhow!("unknown output format '{}'", s)),
+        }
+    }
+}
+
+/// Results of a command that can be printed in any output format
+///
+/// Serialized field names are kept stable for scripts and
+/// carry the unit where there is one, e.g. `volume_ml`.
+pub trait Report: Serialize {
+    /// Prints the results as human readable text and tables,
+    /// with quantities in the given unit system
+    fn print_table(&self, units: UnitSystem);
+
+    /// Writes the results as CSV records. Results made of a list
+    /// write one record per list item instead of a single record.
+    fn write_csv<W: io::Write>(&self, writer: &mut csv::Writer<W>) -> csv::Result<()> {
+        writer.serialize(self)
+    }
+}
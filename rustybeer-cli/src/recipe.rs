@@ -0,0 +1,350 @@
+//! Recipe files and printing of recipes
+
+use crate::output::Report;
+use crate::units::UnitSystem;
+use anyhow::{anyhow, Context, Result};
+use rustybeer::calculators::abv::abv;
+use rustybeer::calculators::fg::fg;
+use rustybeer::calculators::timeline::BrewDay;
+use rustybeer::recipe::Recipe;
+use rustybeer::units::Gravity;
+use rustybeer_util::measurements::{Mass, Temperature};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{fs, io, path::Path};
+
+/// Reads a recipe, recipe design or brew session from a file. The format is picked
+/// by the file extension: TOML (`.toml`), YAML (`.yaml`, `.yml`) or JSON (`.json`).
+pub fn read_recipe<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let data = fs::read_to_string(path)
+        .with_context(|| format!("Could not read recipe {}", path.display()))?;
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_lowercase);
+    let recipe = match extension.as_deref() {
+        Some("toml") => toml::from_str(&data).map_err(anyhow::Error::from),
+        Some("yaml") | Some("yml") => serde_yaml::from_str(&data).map_err(anyhow::Error::from),
+        Some("json") => serde_json::from_str(&data).map_err(anyhow::Error::from),
+        _ => {
+            return Err(anyhow!(
+                "Recipe {} is not a .toml, .yaml, .yml or .json file",
+                path.display()
+            ))
+        }
+    };
+    recipe.with_context(|| format!("Could not parse recipe {}", path.display()))
+}
+
+/// Attenuation (%) used when a recipe file gives no final gravity
+const DEFAULT_ATTENUATION: u8 = 75;
+
+/// A recipe file: the recipe with the fermentation and
+/// packaging details its brew sheet is calculated from
+#[derive(Debug, Deserialize)]
+pub struct RecipeFile {
+    #[serde(flatten)]
+    pub recipe: Recipe,
+    /// expected final gravity
+    pub final_gravity: Option<f64>,
+    /// apparent attenuation of the yeast (%), used without a final gravity
+    pub attenuation: Option<u8>,
+    #[serde(default)]
+    pub packaging: Packaging,
+    #[serde(default)]
+    pub brew_day: BrewDay,
+}
+
+impl RecipeFile {
+    /// Final gravity given in the file, or from the attenuation
+    pub fn final_gravity(&self) -> rustybeer::Result<Gravity> {
+        match self.final_gravity {
+            Some(final_gravity) => Ok(Gravity::from_specific_gravity(final_gravity)),
+            None => fg(
+                self.recipe.original_gravity()?,
+                self.attenuation.unwrap_or(DEFAULT_ATTENUATION),
+            ),
+        }
+    }
+
+    /// Volume of beer packaged (liters), the batch volume unless given
+    pub fn packaged_volume_liters(&self) -> f64 {
+        self.packaging
+            .volume_liters
+            .unwrap_or(self.recipe.batch_volume_liters)
+    }
+}
+
+/// How a recipe is packaged and served
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Packaging {
+    /// volume of beer packaged (liters), defaults to the batch volume
+    pub volume_liters: Option<f64>,
+    /// wanted volumes of CO2
+    pub co2_volumes: f64,
+    /// temperature of the beer when packaged (°C)
+    pub temperature_celsius: f64,
+    /// priming sugars to weigh, defaults to all sugars
+    pub sugars: Vec<String>,
+    /// volume of one serving (ml)
+    pub serving_ml: f64,
+}
+
+impl Default for Packaging {
+    fn default() -> Self {
+        Self {
+            volume_liters: None,
+            co2_volumes: 2.4,
+            temperature_celsius: 20.,
+            sugars: vec![],
+            serving_ml: 330.,
+        }
+    }
+}
+
+/// One ingredient of the scaled recipe as a flat CSV record
+#[derive(Debug, Serialize)]
+struct IngredientRow<'a> {
+    ingredient: &'static str,
+    name: &'a str,
+    grams: Option<f64>,
+    time_mins: Option<u32>,
+    cells_billions: Option<f64>,
+}
+
+/// A recipe with its original gravity, color and IBU
+#[derive(Debug, Serialize)]
+pub struct RecipeReport {
+    pub original_gravity: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub final_gravity: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abv: Option<f64>,
+    pub color_srm: f64,
+    pub ibu: f64,
+    pub recipe: Recipe,
+}
+
+impl RecipeReport {
+    pub fn new(recipe: Recipe) -> rustybeer::Result<Self> {
+        Ok(Self {
+            original_gravity: recipe.original_gravity()?.as_specific_gravity(),
+            final_gravity: None,
+            abv: None,
+            color_srm: recipe.color()?.as_srm(),
+            ibu: recipe.ibu()?.as_ibu(),
+            recipe,
+        })
+    }
+
+    /// Adds the expected final gravity and the ABV it gives
+    pub fn with_final_gravity(mut self, final_gravity: Gravity) -> rustybeer::Result<Self> {
+        let original_gravity = Gravity::from_specific_gravity(self.original_gravity);
+        self.abv = Some(abv(original_gravity, final_gravity)?.as_percent());
+        self.final_gravity = Some(final_gravity.as_specific_gravity());
+        Ok(self)
+    }
+}
+
+impl Report for RecipeReport {
+    fn print_table(&self, units: UnitSystem) {
+        let recipe = &self.recipe;
+        println!("Recipe: {}", recipe.name);
+        println!("Batch volume: {}", units.volume(recipe.batch_volume()));
+        println!("Efficiency: {:.0}%", recipe.efficiency * 100.);
+        println!("Original gravity: {:.3}", self.original_gravity);
+        if let (Some(final_gravity), Some(abv)) = (self.final_gravity, self.abv) {
+            println!("Final gravity: {:.3}", final_gravity);
+            println!("ABV: {:.1}%", abv);
+        }
+        println!("Color: {:.1} SRM", self.color_srm);
+        println!("IBU: {:.1}", self.ibu);
+
+        println!("Fermentables:");
+        for fermentable in &recipe.fermentables {
+            println!(
+                "{:>23}: {}",
+                fermentable.name,
+                units.mass(Mass::from_grams(fermentable.weight_grams))
+            );
+        }
+        println!("Hops:");
+        for hop in &recipe.hops {
+            println!(
+                "{:>23}: {} at {} min",
+                hop.name,
+                units.mass(Mass::from_grams(hop.addition.weight_grams)),
+                hop.addition.time_mins
+            );
+        }
+        if !recipe.mash_steps.is_empty() {
+            println!("Mash:");
+            for mash_step in &recipe.mash_steps {
+                println!(
+                    "{:>23}: {} for {} min",
+                    mash_step.name,
+                    units.temperature(Temperature::from_celsius(mash_step.temperature_celsius)),
+                    mash_step.time_mins
+                );
+            }
+        }
+        if !recipe.salts.is_empty() {
+            println!("Salts:");
+            for salt in &recipe.salts {
+                println!(
+                    "{:>23}: {}",
+                    salt.name,
+                    units.mass(Mass::from_grams(salt.weight_grams))
+                );
+            }
+        }
+        if !recipe.yeasts.is_empty() {
+            println!("Yeast:");
+            for yeast in &recipe.yeasts {
+                println!(
+                    "{:>23}: {:.0} billion cells",
+                    yeast.name, yeast.cells_billions
+                );
+            }
+        }
+    }
+
+    fn write_csv<W: io::Write>(&self, writer: &mut csv::Writer<W>) -> csv::Result<()> {
+        let recipe = &self.recipe;
+        let fermentables = recipe.fermentables.iter().map(|f| IngredientRow {
+            ingredient: "fermentable",
+            name: &f.name,
+            grams: Some(f.weight_grams),
+            time_mins: None,
+            cells_billions: None,
+        });
+        let hops = recipe.hops.iter().map(|h| IngredientRow {
+            ingredient: "hop",
+            name: &h.name,
+            grams: Some(h.addition.weight_grams),
+            time_mins: Some(h.addition.time_mins),
+            cells_billions: None,
+        });
+        let salts = recipe.salts.iter().map(|s| IngredientRow {
+            ingredient: "salt",
+            name: &s.name,
+            grams: Some(s.weight_grams),
+            time_mins: None,
+            cells_billions: None,
+        });
+        let yeasts = recipe.yeasts.iter().map(|y| IngredientRow {
+            ingredient: "yeast",
+            name: &y.name,
+            grams: None,
+            time_mins: None,
+            cells_billions: Some(y.cells_billions),
+        });
+        fermentables
+            .chain(hops)
+            .chain(salts)
+            .chain(yeasts)
+            .try_for_each(|row| writer.serialize(row))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_recipe, RecipeFile};
+    use rustybeer::calculators::ibu::HopAdditionType;
+    use rustybeer::calculators::timeline::BrewDay;
+    use rustybeer_util::assert_approx;
+    use std::{env, fs};
+
+    static README: &str = include_str!("../../README.md");
+
+    /// The first code block in the given language of the README's recipe file docs
+    fn readme_example(language: &str) -> &'static str {
+        let docs = &README[README.find("## Recipe Files").unwrap()..];
+        let start = docs.find(&format!("```{}\n", language)).unwrap() + language.len() + 4;
+        let end = start + docs[start..].find("```").unwrap();
+        &docs[start..end]
+    }
+
+    /// Reads a recipe file with the given name and contents
+    fn read(name: &str, contents: &str) -> anyhow::Result<RecipeFile> {
+        let path = env::temp_dir().join(format!("rustybeer_recipe_test_{}", name));
+        fs::write(&path, contents).unwrap();
+        let recipe = read_recipe(&path);
+        fs::remove_file(&path).unwrap();
+        recipe
+    }
+
+    #[test]
+    fn readme_toml() {
+        let file = read("readme.toml", readme_example("toml")).unwrap();
+        let recipe = &file.recipe;
+        assert_eq!("Pale Ale", recipe.name);
+        assert_approx!(20., recipe.batch_volume_liters);
+        assert_eq!(1, recipe.fermentables.len());
+        assert!(matches!(
+            recipe.hops[0].addition.hop_type,
+            HopAdditionType::Pellet
+        ));
+        assert_eq!(1, recipe.salts.len());
+        assert_eq!(66., recipe.mash_steps[0].temperature_celsius);
+        assert_approx!(1.012, file.final_gravity().unwrap().as_specific_gravity());
+        assert_approx!(20., file.packaged_volume_liters());
+        assert_eq!(Some(60), file.brew_day.boil_mins);
+    }
+
+    #[test]
+    fn readme_yaml() {
+        let file = read("readme.yaml", readme_example("yaml")).unwrap();
+        assert_eq!("Pale Ale", file.recipe.name);
+        assert_eq!(1, file.recipe.hops.len());
+        assert!(file.recipe.mash_steps.is_empty());
+        assert_approx!(1.012, file.final_gravity().unwrap().as_specific_gravity());
+        assert_eq!(vec!["corn sugar".to_owned()], file.packaging.sugars);
+        assert_approx!(2.4, file.packaging.co2_volumes);
+        assert_approx!(330., file.packaging.serving_ml);
+        assert_approx!(20., file.packaged_volume_liters());
+        assert_eq!(BrewDay::default(), file.brew_day);
+    }
+
+    #[test]
+    fn recipe_defaults() {
+        let minimal = r#"{
+            "name": "Blonde",
+            "batch_volume_liters": 10,
+            "efficiency": 0.7,
+            "fermentables": [
+                { "name": "Pilsner Malt", "weight_grams": 2000, "potential": 1.037 }
+            ]
+        }"#;
+        let file = read("minimal.json", minimal).unwrap();
+        let og = file
+            .recipe
+            .original_gravity()
+            .unwrap()
+            .as_specific_gravity();
+        assert_approx!(
+            og - 0.75 * (og - 1.),
+            file.final_gravity().unwrap().as_specific_gravity()
+        );
+        assert_approx!(10., file.packaged_volume_liters());
+        assert_eq!(BrewDay::default(), file.brew_day);
+
+        let attenuation = minimal.replacen("{", r#"{ "attenuation": 80,"#, 1);
+        let file = read("attenuation.json", &attenuation).unwrap();
+        assert_approx!(
+            og - 0.8 * (og - 1.),
+            file.final_gravity().unwrap().as_specific_gravity()
+        );
+
+        let both = minimal.replacen("{", r#"{ "attenuation": 80, "final_gravity": 1.01,"#, 1);
+        let file = read("final_gravity.json", &both).unwrap();
+        assert_approx!(1.01, file.final_gravity().unwrap().as_specific_gravity());
+    }
+
+    #[test]
+    fn unknown_extension() {
+        let error = read("recipe.txt", r#"{ "name": "Blonde" }"#).unwrap_err();
+        assert!(error.to_string().contains("is not a .toml"));
+        assert!(read("recipe.toml", "name = ").is_err());
+    }
+}
@@ -0,0 +1,153 @@
+//! Unit systems used to print quantities
+
+use anyhow::{anyhow, Result};
+use rustybeer_util::measurements::{Length, Mass, Pressure, Temperature, Volume};
+use std::str::FromStr;
+
+/// Units in which quantities are printed as text
+///
+/// Only affects the human readable output, JSON and CSV always use the
+/// unit in the field name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitSystem {
+    /// Litres, grams, Celsius, bar and meters
+    Metric,
+    /// US gallons and fluid ounces, pounds, Fahrenheit, psi and feet
+    Us,
+    /// Imperial gallons and fluid ounces, pounds, Fahrenheit, psi and feet
+    Imperial,
+}
+
+impl UnitSystem {
+    pub const VARIANTS: &'static [&'static str] = &["metric", "us", "imperial"];
+
+    /// Formats a volume in litres or US/imperial gallons, falling back to
+    /// milliliters or fluid ounces for volumes below one litre or gallon,
+    /// either way from zero
+    pub fn volume(self, volume: Volume) -> String {
+        match self {
+            UnitSystem::Metric if volume.as_litres().abs() < 1.0 => {
+                format!("{:.1} ml", volume.as_milliliters())
+            }
+            UnitSystem::Metric => format!("{:.2} l", volume.as_litres()),
+            UnitSystem::Us if volume.as_gallons().abs() < 1.0 => {
+                format!("{:.1} fl oz", volume.as_fluid_ounces())
+            }
+            UnitSystem::Us => format!("{:.2} gal", volume.as_gallons()),
+            UnitSystem::Imperial if volume.as_gallons_uk().abs() < 1.0 => {
+                format!("{:.1} fl oz", volume.as_fluid_ounces_uk())
+            }
+            UnitSystem::Imperial => format!("{:.2} gal", volume.as_gallons_uk()),
+        }
+    }
+
+    /// Formats a flow rate given as volume per second
+    pub fn flow_rate(self, per_second: Volume) -> String {
+        match self {
+            UnitSystem::Metric => format!("{:.1} ml/s", per_second.as_milliliters()),
+            UnitSystem::Us => format!("{:.2} fl oz/s", per_second.as_fluid_ounces()),
+            UnitSystem::Imperial => format!("{:.2} fl oz/s", per_second.as_fluid_ounces_uk()),
+        }
+    }
+
+    /// Formats a mass in grams or ounces, switching to kilograms or
+    /// pounds from one kilogram or pound up
+    pub fn mass(self, mass: Mass) -> String {
+        match self {
+            UnitSystem::Metric if mass.as_kilograms().abs() < 1.0 => {
+                format!("{:.2} g", mass.as_grams())
+            }
+            UnitSystem::Metric => format!("{:.2} kg", mass.as_kilograms()),
+            UnitSystem::Us | UnitSystem::Imperial if mass.as_pounds().abs() < 1.0 => {
+                format!("{:.2} oz", mass.as_ounces())
+            }
+            UnitSystem::Us | UnitSystem::Imperial => format!("{:.2} lb", mass.as_pounds()),
+        }
+    }
+
+    /// Formats a temperature in Celsius or Fahrenheit
+    pub fn temperature(self, temperature: Temperature) -> String {
+        match self {
+            UnitSystem::Metric => format!("{:.1} C", temperature.as_celsius()),
+            UnitSystem::Us | UnitSystem::Imperial => {
+                format!("{:.1} F", temperature.as_fahrenheit())
+            }
+        }
+    }
+
+    /// Formats a pressure in bar or psi
+    pub fn pressure(self, pressure: Pressure) -> String {
+        match self {
+            UnitSystem::Metric => format!("{:.2} bar", pressure.as_bars()),
+            UnitSystem::Us | UnitSystem::Imperial => format!("{:.1} psi", pressure.as_psi()),
+        }
+    }
+
+    /// Formats a length in meters or feet
+    pub fn length(self, length: Length) -> String {
+        match self {
+            UnitSystem::Metric => format!("{:.2} m", length.as_meters()),
+            UnitSystem::Us | UnitSystem::Imperial => format!("{:.2} ft", length.as_feet()),
+        }
+    }
+}
+
+impl FromStr for UnitSystem {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "metric" => Ok(UnitSystem::Metric),
+            "us" => Ok(UnitSystem::Us),
+            "imperial" => Ok(UnitSystem::Imperial),
+            _ => Err(anyhow!("unknown unit system '{}'", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UnitSystem;
+    use rustybeer_util::measurements::Volume;
+
+    #[test]
+    fn volume_units() {
+        assert_eq!(
+            "500.0 ml",
+            UnitSystem::Metric.volume(Volume::from_litres(0.5))
+        );
+        assert_eq!(
+            "20.00 l",
+            UnitSystem::Metric.volume(Volume::from_litres(20.))
+        );
+        assert_eq!("5.00 gal", UnitSystem::Us.volume(Volume::from_gallons(5.)));
+        assert_eq!(
+            "12.0 fl oz",
+            UnitSystem::Imperial.volume(Volume::from_fluid_ounces_uk(12.))
+        );
+    }
+
+    #[test]
+    fn negative_volume_units() {
+        assert_eq!(
+            "-3.33 l",
+            UnitSystem::Metric.volume(Volume::from_litres(-3.333))
+        );
+        assert_eq!(
+            "-500.0 ml",
+            UnitSystem::Metric.volume(Volume::from_litres(-0.5))
+        );
+        assert_eq!(
+            "-2.64 gal",
+            UnitSystem::Us.volume(Volume::from_litres(-10.))
+        );
+        assert_eq!(
+            "-2.20 gal",
+            UnitSystem::Imperial.volume(Volume::from_litres(-10.))
+        );
+        assert_eq!(
+            "-16.0 fl oz",
+            UnitSystem::Us.volume(Volume::from_pints(-1.))
+        );
+    }
+}
@@ -1,8 +1,8 @@
 use once_cell::sync::Lazy;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 /// Data for 12 oz ABV% to approx. calories
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ABVCalories {
     pub abv: f32,
     pub calories_low: f32,
@@ -16,13 +16,14 @@ static ABV_CALORIES_JSON: &str = include_str!("json/abv_calories.json");
 ///
 /// Data will be loaded from JSON on the first use.
 pub static ABV_CALORIES: Lazy<Vec<ABVCalories>> = Lazy::new(|| {
-    serde_json::from_str(ABV_CALORIES_JSON).expect("ABV to calories data could not be deserialised")
+    serde_json::from_str(ABV_CALORIES_JSON)
+        .expect("bundled ABV to calories data, checked by the tests, could not be deserialised")
 });
 
 /// Criteria for selecting a ABV to calories.
 ///
 /// If an attribute is `None`, it is ignored.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Criteria {
     pub abv: Option<f32>,
 }
@@ -39,3 +40,15 @@ impl Criteria {
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Criteria, ABV_CALORIES};
+
+    #[test]
+    fn bundled_abv_calories() {
+        let criteria = Criteria { abv: Some(5.0) };
+        let cal = ABV_CALORIES.iter().find(|c| criteria.matches(c)).unwrap();
+        assert_eq!(5.0, cal.abv);
+    }
+}
@@ -1,21 +1,66 @@
 use once_cell::sync::Lazy;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
 /// Data about a particular style of beer.
-#[derive(Debug, Clone, Deserialize)]
+///
+/// Styles follow the BJCP style guidelines. Vital statistics are `None` for
+/// styles where they depend on the declared base style, e.g. fruit beers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BeerStyle {
+    /// Category and subcategory code, e.g. `21A`
+    ///
+    /// Sub-styles of a style share its code, e.g. all specialty IPAs are `21B`
+    /// and all historical beers are `27`.
+    pub code: String,
     pub name: String,
-    pub original_gravity_min: f32,
-    pub original_gravity_max: f32,
-    pub final_gravity_min: f32,
-    pub final_gravity_max: f32,
-    pub abv_min: f32,
-    pub abv_max: f32,
-    pub ibu_min: u8,
-    pub ibu_max: u8,
-    pub color_srm_min: f32,
-    pub color_srm_max: f32,
+    /// Name of the category, e.g. `IPA`
+    pub category: String,
+    /// Style this is a sub-style of, e.g. `Specialty IPA` for a Black IPA
+    #[serde(default)]
+    pub parent: Option<String>,
+    /// Year of the style guide the data is from
+    pub guide_year: u16,
+    pub original_gravity_min: Option<f32>,
+    pub original_gravity_max: Option<f32>,
+    pub final_gravity_min: Option<f32>,
+    pub final_gravity_max: Option<f32>,
+    pub abv_min: Option<f32>,
+    pub abv_max: Option<f32>,
+    pub ibu_min: Option<u8>,
+    pub ibu_max: Option<u8>,
+    pub color_srm_min: Option<f32>,
+    pub color_srm_max: Option<f32>,
+    /// Overall impression
     pub description: String,
+    #[serde(default)]
+    pub aroma: String,
+    #[serde(default)]
+    pub appearance: String,
+    #[serde(default)]
+    pub flavor: String,
+    #[serde(default)]
+    pub mouthfeel: String,
+    #[serde(default)]
+    pub commercial_examples: Vec<String>,
+    /// Style tags, e.g. `pale-color` or `top-fermented`
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl BeerStyle {
+    /// Category number of the style, e.g. `21` for `21A`
+    pub fn category_number(&self) -> u8 {
+        category_number(&self.code).unwrap_or_default()
+    }
+}
+
+// Leading digits of a style code
+fn category_number(code: &str) -> Option<u8> {
+    let digits = code
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(code.len());
+    code[..digits].parse().ok()
 }
 
 // Raw inlined style data
@@ -25,13 +70,150 @@ static BEER_STYLES_JSON: &str = include_str!("json/beer_styles.json");
 ///
 /// Data will be loaded from JSON on the first use.
 pub static BEER_STYLES: Lazy<Vec<BeerStyle>> = Lazy::new(|| {
-    serde_json::from_str(BEER_STYLES_JSON).expect("beer styles data could not be deserialised")
+    serde_json::from_str(BEER_STYLES_JSON)
+        .expect("bundled beer styles data, checked by the tests, could not be deserialised")
 });
 
+/// Beer styles with the given code, ignoring case.
+///
+/// A subcategory code such as `21A` selects the style and any sub-styles
+/// sharing the code, a category number such as `21` the whole category.
+pub fn styles_by_code(code: &str) -> Vec<&'static BeerStyle> {
+    let code = code.trim();
+    let whole_category = code.chars().all(|c| c.is_ascii_digit());
+
+    BEER_STYLES
+        .iter()
+        .filter(|style| {
+            if whole_category {
+                category_number(code) == Some(style.category_number())
+            } else {
+                style.code.eq_ignore_ascii_case(code)
+            }
+        })
+        .collect()
+}
+
+/// Beer style lookup by name or by a code identifying a single style, ignoring case.
+pub fn find_style(name_or_code: &str) -> Option<&'static BeerStyle> {
+    let query = name_or_code.trim();
+    BEER_STYLES
+        .iter()
+        .find(|style| style.name.eq_ignore_ascii_case(query))
+        .or_else(|| match styles_by_code(query).as_slice() {
+            [style] => Some(*style),
+            _ => None,
+        })
+}
+
+/// Vital statistic of a beer that is compared against style ranges
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Parameter {
+    Og,
+    Fg,
+    Abv,
+    Ibu,
+    Srm,
+}
+
+impl Parameter {
+    /// Range of the parameter in the given style, if the style has one
+    pub fn range(self, style: &BeerStyle) -> Option<(f32, f32)> {
+        let (min, max) = match self {
+            Parameter::Og => (style.original_gravity_min, style.original_gravity_max),
+            Parameter::Fg => (style.final_gravity_min, style.final_gravity_max),
+            Parameter::Abv => (style.abv_min, style.abv_max),
+            Parameter::Ibu => (style.ibu_min.map(f32::from), style.ibu_max.map(f32::from)),
+            Parameter::Srm => (style.color_srm_min, style.color_srm_max),
+        };
+        Some((min?, max?))
+    }
+
+    /// Typical width of a style range, used to normalise distances
+    ///
+    /// These are the median widths of the ranges in the style guide.
+    pub fn scale(self) -> f32 {
+        match self {
+            Parameter::Og => 0.014,
+            Parameter::Fg => 0.006,
+            Parameter::Abv => 1.5,
+            Parameter::Ibu => 15.0,
+            Parameter::Srm => 7.0,
+        }
+    }
+}
+
+impl fmt::Display for Parameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Parameter::Og => "OG",
+            Parameter::Fg => "FG",
+            Parameter::Abv => "ABV",
+            Parameter::Ibu => "IBU",
+            Parameter::Srm => "SRM",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Where a value lies relative to a style range
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Conformance {
+    Low,
+    InRange,
+    High,
+    /// The style has no range for the parameter
+    Unknown,
+}
+
+/// Comparison of one parameter of a beer against a style
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParameterConformance {
+    pub parameter: Parameter,
+    pub value: f32,
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+    pub conformance: Conformance,
+    /// Distance from the nearest end of the range, zero when in range
+    pub distance: f32,
+}
+
+impl ParameterConformance {
+    /// Compares the value of a parameter against the range of a style
+    pub fn new(parameter: Parameter, value: f32, style: &BeerStyle) -> Self {
+        let range = parameter.range(style);
+        let (conformance, distance) = match range {
+            None => (Conformance::Unknown, 0.0),
+            Some((min, _)) if value < min => (Conformance::Low, min - value),
+            Some((_, max)) if value > max => (Conformance::High, value - max),
+            Some(_) => (Conformance::InRange, 0.0),
+        };
+
+        Self {
+            parameter,
+            value,
+            min: range.map(|(min, _)| min),
+            max: range.map(|(_, max)| max),
+            conformance,
+            distance,
+        }
+    }
+}
+
+/// A beer style scored by how close a beer is to it
+#[derive(Debug, Clone, Serialize)]
+pub struct StyleScore<'a> {
+    pub style: &'a BeerStyle,
+    /// Normalised distance from the style ranges, zero when all criteria match
+    pub distance: f32,
+}
+
 /// Criteria for selecting a beer style.
 ///
 /// If an attribute is `None`, it is ignored.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Criteria {
     pub og: Option<f32>,
     pub fg: Option<f32>,
@@ -42,54 +224,112 @@ pub struct Criteria {
 
 impl Criteria {
     /// Whether the given beer style matches **all** criteria that are `Some`.
+    ///
+    /// A style without statistics for a criterion does not match it.
     pub fn matches(&self, style: &BeerStyle) -> bool {
-        if let Some(og) = self.og {
-            if og < style.original_gravity_min || og > style.original_gravity_max {
-                return false;
-            }
-        }
-        if let Some(fg) = self.fg {
-            if fg < style.final_gravity_min || fg > style.final_gravity_max {
-                return false;
-            }
-        }
-        if let Some(abv) = self.abv {
-            if abv < style.abv_min || abv > style.abv_max {
-                return false;
-            }
-        }
-        if let Some(ibu) = self.ibu {
-            if ibu < style.ibu_min || ibu > style.ibu_max {
-                return false;
-            }
-        }
-        if let Some(srm) = self.srm {
-            if srm < style.color_srm_min || srm > style.color_srm_max {
-                return false;
-            }
-        }
+        in_range(
+            self.og,
+            style.original_gravity_min,
+            style.original_gravity_max,
+        ) && in_range(self.fg, style.final_gravity_min, style.final_gravity_max)
+            && in_range(self.abv, style.abv_min, style.abv_max)
+            && in_range(self.ibu, style.ibu_min, style.ibu_max)
+            && in_range(self.srm, style.color_srm_min, style.color_srm_max)
+    }
+
+    /// Parameters that are `Some` with their values
+    pub fn values(&self) -> Vec<(Parameter, f32)> {
+        [
+            (Parameter::Og, self.og),
+            (Parameter::Fg, self.fg),
+            (Parameter::Abv, self.abv),
+            (Parameter::Ibu, self.ibu.map(f32::from)),
+            (Parameter::Srm, self.srm),
+        ]
+        .iter()
+        .filter_map(|&(parameter, value)| Some((parameter, value?)))
+        .collect()
+    }
+
+    /// Compares every criterion that is `Some` against the ranges of a style
+    pub fn conformance(&self, style: &BeerStyle) -> Vec<ParameterConformance> {
+        self.values()
+            .into_iter()
+            .map(|(parameter, value)| ParameterConformance::new(parameter, value, style))
+            .collect()
+    }
+
+    /// Normalised distance of the criteria that are `Some` from the ranges of a style.
+    ///
+    /// The distance of each parameter from its range is divided by the
+    /// [`Parameter::scale`] and the results are combined as an euclidean
+    /// distance. Returns `None` if the style has no range for a criterion.
+    pub fn distance(&self, style: &BeerStyle) -> Option<f32> {
+        self.conformance(style)
+            .iter()
+            .map(|row| match row.conformance {
+                Conformance::Unknown => None,
+                _ => Some((row.distance / row.parameter.scale()).powi(2)),
+            })
+            .sum::<Option<f32>>()
+            .map(f32::sqrt)
+    }
 
-        true
+    /// Scores the given styles, closest style first.
+    ///
+    /// Styles that can not be scored against the criteria are left out.
+    pub fn rank<'a>(&self, styles: &'a [BeerStyle]) -> Vec<StyleScore<'a>> {
+        let mut scores: Vec<StyleScore> = styles
+            .iter()
+            .filter_map(|style| {
+                Some(StyleScore {
+                    style,
+                    distance: self.distance(style)?,
+                })
+            })
+            .collect();
+        scores.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        scores
+    }
+}
+
+// Whether an optional value is inside an inclusive range
+fn in_range<T: PartialOrd>(value: Option<T>, min: Option<T>, max: Option<T>) -> bool {
+    match (value, min, max) {
+        (None, _, _) => true,
+        (Some(value), Some(min), Some(max)) => value >= min && value <= max,
+        _ => false,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::assert_approx;
 
     static TEST_BEER_STYLE: Lazy<BeerStyle> = Lazy::new(|| BeerStyle {
+        code: "99Z".to_owned(),
         name: "test beer style".to_owned(),
-        original_gravity_min: 0.0,
-        original_gravity_max: 1.0,
-        final_gravity_min: -2.3,
-        final_gravity_max: 4.8,
-        abv_min: 0.0,
-        abv_max: 0.0,
-        ibu_min: 8,
-        ibu_max: 16,
-        color_srm_min: -1234.5,
-        color_srm_max: 1234.5,
+        category: "Test Beer".to_owned(),
+        parent: None,
+        guide_year: 2021,
+        original_gravity_min: Some(0.0),
+        original_gravity_max: Some(1.0),
+        final_gravity_min: Some(-2.3),
+        final_gravity_max: Some(4.8),
+        abv_min: Some(0.0),
+        abv_max: Some(0.0),
+        ibu_min: Some(8),
+        ibu_max: Some(16),
+        color_srm_min: Some(-1234.5),
+        color_srm_max: Some(1234.5),
         description: String::from("Test"),
+        aroma: String::new(),
+        appearance: String::new(),
+        flavor: String::new(),
+        mouthfeel: String::new(),
+        commercial_examples: Vec::new(),
+        tags: Vec::new(),
     });
 
     #[test]
@@ -151,4 +391,159 @@ mod tests {
         criteria.abv = Some(0.0);
         assert!(criteria.matches(&TEST_BEER_STYLE));
     }
+
+    #[test]
+    fn criteria_without_statistics() {
+        let mut style = TEST_BEER_STYLE.clone();
+        style.ibu_min = None;
+        style.ibu_max = None;
+
+        let mut criteria = Criteria::default();
+        assert!(criteria.matches(&style));
+        criteria.ibu = Some(10);
+        assert!(!criteria.matches(&style));
+    }
+
+    #[test]
+    fn conformance_of_supplied_criteria() {
+        let criteria = Criteria {
+            og: Some(1.5),
+            ibu: Some(12),
+            srm: Some(-1300.0),
+            ..Criteria::default()
+        };
+        let report = criteria.conformance(&TEST_BEER_STYLE);
+        assert_eq!(report.len(), 3);
+
+        assert_eq!(report[0].parameter, Parameter::Og);
+        assert_eq!(report[0].conformance, Conformance::High);
+        assert_approx!(report[0].distance, 0.5);
+
+        assert_eq!(report[1].parameter, Parameter::Ibu);
+        assert_eq!(report[1].conformance, Conformance::InRange);
+        assert_approx!(report[1].distance, 0.0);
+
+        assert_eq!(report[2].parameter, Parameter::Srm);
+        assert_eq!(report[2].conformance, Conformance::Low);
+        assert_approx!(report[2].distance, 65.5);
+    }
+
+    #[test]
+    fn conformance_without_statistics() {
+        let mut style = TEST_BEER_STYLE.clone();
+        style.abv_max = None;
+
+        let criteria = Criteria {
+            abv: Some(5.0),
+            ..Criteria::default()
+        };
+        let report = criteria.conformance(&style);
+        assert_eq!(report[0].conformance, Conformance::Unknown);
+        assert_eq!(report[0].min, None);
+    }
+
+    #[test]
+    fn distance_is_normalised() {
+        let criteria = Criteria {
+            og: Some(1.014),
+            ibu: Some(12),
+            ..Criteria::default()
+        };
+        assert_approx!(
+            criteria.distance(&TEST_BEER_STYLE).unwrap(),
+            1.0,
+            epsilon = 1e-3
+        );
+
+        let criteria = Criteria {
+            og: Some(1.014),
+            ibu: Some(31),
+            ..Criteria::default()
+        };
+        assert_approx!(
+            criteria.distance(&TEST_BEER_STYLE).unwrap(),
+            2.0f32.sqrt(),
+            epsilon = 1e-3
+        );
+    }
+
+    #[test]
+    fn distance_without_statistics() {
+        let mut style = TEST_BEER_STYLE.clone();
+        style.ibu_min = None;
+
+        let criteria = Criteria {
+            ibu: Some(12),
+            ..Criteria::default()
+        };
+        assert_eq!(criteria.distance(&style), None);
+        assert_eq!(Criteria::default().distance(&style), Some(0.0));
+    }
+
+    #[test]
+    fn rank_closest_style_first() {
+        let criteria = Criteria {
+            og: Some(1.065),
+            fg: Some(1.012),
+            abv: Some(6.8),
+            ibu: Some(55),
+            srm: Some(7.0),
+        };
+        let ranked = criteria.rank(&BEER_STYLES);
+        assert!(ranked
+            .iter()
+            .take_while(|score| score.distance == 0.0)
+            .any(|score| score.style.name == "American IPA"));
+        assert!(ranked
+            .windows(2)
+            .all(|pair| pair[0].distance <= pair[1].distance));
+        // Styles without statistics can not be ranked
+        assert!(ranked.iter().all(|score| score.style.abv_min.is_some()));
+    }
+
+    #[test]
+    fn find_style_by_name_or_code() {
+        assert_eq!(find_style("hazy ipa").unwrap().code, "21C");
+        assert_eq!(find_style("21a").unwrap().name, "American IPA");
+        // Code shared by several specialty IPAs
+        assert!(find_style("21B").is_none());
+    }
+
+    #[test]
+    fn styles_by_subcategory_code() {
+        let styles = styles_by_code("21a");
+        assert_eq!(styles.len(), 1);
+        assert_eq!(styles[0].name, "American IPA");
+
+        let specialty_ipas = styles_by_code("21B");
+        assert_eq!(specialty_ipas.len(), 7);
+        assert!(specialty_ipas
+            .iter()
+            .all(|style| style.parent.as_deref() == Some("Specialty IPA")));
+
+        assert!(styles_by_code("21Z").is_empty());
+    }
+
+    #[test]
+    fn styles_by_category_number() {
+        let styles = styles_by_code("21");
+        assert_eq!(styles.len(), 9);
+        assert!(styles.iter().all(|style| style.category == "IPA"));
+
+        assert_eq!(styles_by_code("27").len(), 9);
+        assert_eq!(styles_by_code("34").len(), 3);
+    }
+
+    #[test]
+    fn all_categories_present() {
+        for category in 1..=34 {
+            assert!(
+                BEER_STYLES
+                    .iter()
+                    .any(|style| style.category_number() == category),
+                "category {} missing",
+                category
+            );
+        }
+    }
 }
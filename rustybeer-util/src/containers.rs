@@ -0,0 +1,99 @@
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::{env, fs::File, io, path::Path};
+
+/// A container beer can be packaged in,
+/// such as a bottle, can, growler or keg.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Container {
+    pub name: String,
+    /// volume of the container (ml)
+    pub volume: f64,
+}
+
+impl Container {
+    pub fn new(name: String, volume: f64) -> Self {
+        Self { name, volume }
+    }
+}
+
+// Raw inlined container data
+static CONTAINERS_JSON: &str = include_str!("json/containers.json");
+
+/// Environment variable pointing to a JSON file with user defined containers.
+pub static CONTAINERS_FILE_VAR: &str = "RUSTYBEER_CONTAINERS";
+
+/// Loads containers from a JSON file with the same format as the bundled data:
+///
+/// ```json
+/// [{ "name": "Crowler can", "volume": 946.0 }]
+/// ```
+pub fn load_containers<P: AsRef<Path>>(path: P) -> io::Result<Vec<Container>> {
+    let file = File::open(path)?;
+    Ok(serde_json::from_reader(io::BufReader::new(file))?)
+}
+
+/// Bundled containers.
+///
+/// Data will be loaded from JSON on the first use.
+pub static CONTAINERS: Lazy<Vec<Container>> = Lazy::new(|| {
+    serde_json::from_str(CONTAINERS_JSON)
+        .expect("bundled containers data, checked by the tests, could not be deserialised")
+});
+
+/// All available containers.
+///
+/// Containers from the file given in the `RUSTYBEER_CONTAINERS` environment
+/// variable are added after the [bundled](static.CONTAINERS.html) ones.
+/// The file is read on every call, so errors in it can be reported.
+pub fn all_containers() -> io::Result<Vec<Container>> {
+    let mut containers = CONTAINERS.clone();
+    if let Some(path) = env::var_os(CONTAINERS_FILE_VAR) {
+        containers.extend(load_containers(&path).map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!(
+                    "containers file {} could not be loaded: {}",
+                    path.to_string_lossy(),
+                    e
+                ),
+            )
+        })?);
+    }
+    Ok(containers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_containers, Container, CONTAINERS};
+    use std::{env, fs};
+
+    #[test]
+    fn bundled_containers() {
+        assert_eq!(
+            Some(&Container::new("330ml bottle".to_string(), 330.0)),
+            CONTAINERS.first()
+        );
+    }
+
+    #[test]
+    fn containers_from_file() {
+        let path = env::temp_dir().join("rustybeer_containers_test.json");
+        fs::write(
+            &path,
+            r#"[{ "name": "Crowler can", "volume": 946.0 }, { "name": "Corny keg", "volume": 18927.0 }]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                Container::new("Crowler can".to_string(), 946.0),
+                Container::new("Corny keg".to_string(), 18927.0),
+            ],
+            load_containers(&path).unwrap()
+        );
+        fs::remove_file(&path).unwrap();
+
+        assert!(load_containers(&path).is_err());
+    }
+}
@@ -1,6 +1,171 @@
-use measurements::{Energy, Mass, Temperature, Volume};
+use measurements::{Energy, Length, Mass, Pressure, Temperature, Volume};
 use regex::Regex;
-use std::num::ParseFloatError;
+use std::{error, fmt, num::ParseFloatError, ops::Add};
+
+/// Error returned when a quantity can not be parsed from a string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The number part of the quantity is not a valid number
+    Number(ParseFloatError),
+    /// The string does not follow the quantity grammar
+    Invalid(String),
+    /// The unit of the quantity is not known
+    UnknownUnit {
+        quantity: &'static str,
+        unit: String,
+        expected: &'static str,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Number(e) => write!(f, "invalid number: {}", e),
+            ParseError::Invalid(val) => write!(f, "'{}' is not a valid quantity", val),
+            ParseError::UnknownUnit {
+                quantity,
+                unit,
+                expected,
+            } => write!(
+                f,
+                "unknown {} unit '{}', expected one of {}",
+                quantity, unit, expected
+            ),
+        }
+    }
+}
+
+impl error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            ParseError::Number(e) => Some(e),
+            ParseError::Invalid(_) | ParseError::UnknownUnit { .. } => None,
+        }
+    }
+}
+
+impl From<ParseFloatError> for ParseError {
+    fn from(e: ParseFloatError) -> Self {
+        ParseError::Number(e)
+    }
+}
+
+/// Splits a quantity into its terms of a number and an optional unit.
+///
+/// The grammar shared by all parsers:
+/// * a quantity is one or more terms, e.g. `5 gal 2 qt` or `1 lb 4 oz`
+/// * a number is a decimal with a point or a comma as the decimal separator
+///   (`20.5`, `20,5`), in scientific notation (`1e3`), a fraction (`1/2`)
+///   or a whole number and a fraction (`1 1/2`). A comma followed by exactly
+///   three digits (`1,000`) is rejected, as it may as well be a thousands
+///   separator.
+/// * a unit follows its number with or without whitespace and may be made of
+///   several words (`imp gal`). Units are lowercased, and dots and degree
+///   signs are removed (`fl. oz.`, `°C`).
+fn terms(val: &str) -> Result<Vec<(f64, Option<String>)>, ParseError> {
+    let re = Regex::new(
+        r"^([-+]?(?:\d+\s+\d+/\d+|\d+/\d+|(?:\d+(?:[.,]\d*)?|[.,]\d+)(?:[eE][-+]?\d+)?))\s*([^\d\s.,+-][^\d\s]*\d?(?:\s+[^\d\s.,+-][^\d\s]*\d?)*)?",
+    )
+    .unwrap();
+
+    let mut rest = val.trim();
+    let mut terms = Vec::new();
+    while !rest.is_empty() {
+        let caps = re
+            .captures(rest)
+            .ok_or_else(|| ParseError::Invalid(val.to_string()))?;
+        let unit = caps.get(2).map(|unit| {
+            unit.as_str()
+                .to_lowercase()
+                .replace(['.', '°'], "")
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        });
+        terms.push((number(caps.get(1).unwrap().as_str(), val)?, unit));
+        rest = rest[caps.get(0).unwrap().end()..].trim_start();
+    }
+    Ok(terms)
+}
+
+/// Parses a number of the quantity grammar, see [terms](fn.terms.html)
+fn number(number: &str, val: &str) -> Result<f64, ParseError> {
+    if let Some((_, decimals)) = number.split_once(',') {
+        let decimals = decimals.split(['e', 'E']).next().unwrap_or_default();
+        if decimals.len() == 3 {
+            return Err(ParseError::Invalid(val.to_string()));
+        }
+    }
+    let number = number.replace(',', ".");
+    let (sign, number) = match number.strip_prefix('-') {
+        Some(number) => (-1.0, number),
+        None => (1.0, number.strip_prefix('+').unwrap_or(&number)),
+    };
+
+    let value = match number.split_once('/') {
+        Some((whole_numerator, denominator)) => {
+            let (whole, numerator) = whole_numerator
+                .rsplit_once(char::is_whitespace)
+                .unwrap_or(("0", whole_numerator));
+            let denominator = denominator.parse::<f64>()?;
+            if denominator == 0.0 {
+                return Err(ParseError::Invalid(val.to_string()));
+            }
+            whole.trim().parse::<f64>()? + numerator.parse::<f64>()? / denominator
+        }
+        None => number.parse::<f64>()?,
+    };
+    Ok(sign * value)
+}
+
+/// Creates a quantity from a number in some unit
+type FromUnit<Q> = fn(f64) -> Q;
+
+/// Returns how to create a quantity in a lowercase unit, if the unit is known
+type UnitLookup<Q> = fn(&str) -> Option<FromUnit<Q>>;
+
+/// Looks up the unit of a term, trying a singular of plural units too.
+/// Terms without a unit are in the default unit.
+fn from_unit<Q>(
+    name: Option<String>,
+    default: fn(f64) -> Q,
+    unit: UnitLookup<Q>,
+    quantity: &'static str,
+    expected: &'static str,
+) -> Result<FromUnit<Q>, ParseError> {
+    match name {
+        None => Ok(default),
+        Some(name) => unit(&name)
+            .or_else(|| name.strip_suffix('s').and_then(unit))
+            .ok_or(ParseError::UnknownUnit {
+                quantity,
+                unit: name,
+                expected,
+            }),
+    }
+}
+
+/// Parses a quantity with the given unit lookup, summing the terms of
+/// compound quantities. Numbers without a unit are in the default unit,
+/// which is only allowed for quantities of a single term.
+fn parse_quantity<Q: Add<Output = Q>>(
+    val: &str,
+    default: fn(f64) -> Q,
+    unit: UnitLookup<Q>,
+    quantity: &'static str,
+    expected: &'static str,
+) -> Result<Q, ParseError> {
+    let terms = terms(val)?;
+    if terms.len() > 1 && terms.iter().any(|(_, name)| name.is_none()) {
+        return Err(ParseError::Invalid(val.to_string()));
+    }
+
+    let mut sum = default(0.0);
+    for (value, name) in terms {
+        sum = sum + from_unit(name, default, unit, quantity, expected)?(value);
+    }
+    Ok(sum)
+}
 
 /// Used to build new measurements::Energy structs.
 ///
@@ -9,33 +174,73 @@ use std::num::ParseFloatError;
 pub struct EnergyParser;
 
 impl EnergyParser {
+    const UNITS: &'static str = "kcal, kj, j, wh, kwh, btu or ev";
+
     /// Creates measurements::Energy from string
     ///
     /// Tries to figure out the energy unit from the string. If the string value is plain
     /// number, it will be considered as kilocalories. Also empty strings are considered as
     /// zero kilocalories in Energy.
-    pub fn parse(val: &str) -> Result<Energy, ParseFloatError> {
-        if val.is_empty() {
-            return Ok(Energy::from_kcalories(0.0));
-        }
+    pub fn parse(val: &str) -> Result<Energy, ParseError> {
+        parse_quantity(
+            val,
+            Energy::from_kcalories,
+            Self::unit,
+            "energy",
+            Self::UNITS,
+        )
+    }
 
-        let re = Regex::new(r"([0-9.]*)\s?([a-zA-Zμ]{1,4})$").unwrap();
-        if let Some(caps) = re.captures(val) {
-            let float_val = caps.get(1).unwrap().as_str();
-            return Ok(
-                match caps.get(2).unwrap().as_str().to_uppercase().as_str() {
-                    "KCAL" => Energy::from_kcalories(float_val.parse::<f64>()?),
-                    "BTU" => Energy::from_btu(float_val.parse::<f64>()?),
-                    "EV" => Energy::from_e_v(float_val.parse::<f64>()?),
-                    "WH" => Energy::from_watt_hours(float_val.parse::<f64>()?),
-                    "KWH" => Energy::from_kilowatt_hours(float_val.parse::<f64>()?),
-                    "J" => Energy::from_joules(float_val.parse::<f64>()?),
-                    _ => Energy::from_kcalories(float_val.parse::<f64>()?),
-                },
-            );
-        }
+    fn unit(unit: &str) -> Option<fn(f64) -> Energy> {
+        let from_unit: fn(f64) -> Energy = match unit {
+            "kcal" | "kilocalorie" => Energy::from_kcalories,
+            "j" | "joule" => Energy::from_joules,
+            "kj" | "kilojoule" => |v| Energy::from_joules(v * 1000.0),
+            "wh" | "watt hour" => Energy::from_watt_hours,
+            "kwh" | "kilowatt hour" => Energy::from_kilowatt_hours,
+            "btu" => Energy::from_btu,
+            "ev" | "electronvolt" => Energy::from_e_v,
+            _ => return None,
+        };
+        Some(from_unit)
+    }
+}
+
+/// Used to build new measurements::Length structs.
+///
+/// To be removed if the dependency some time allows creating measurement units from
+/// strings.
+pub struct LengthParser;
+
+impl LengthParser {
+    const UNITS: &'static str = "mm, cm, m, in, ft or yd";
+
+    /// Creates measurements::Length from string
+    ///
+    /// Tries to figure out the length unit from the string. If the string value is plain
+    /// number, it will be considered as meters. Also empty strings are considered as
+    /// zero meters in Length.
+    ///
+    /// ```
+    /// use rustybeer_util::{assert_approx, conversions::LengthParser};
+    ///
+    /// assert_approx!(6.5, LengthParser::parse("6 ft 6 in").unwrap().as_feet());
+    /// ```
+    pub fn parse(val: &str) -> Result<Length, ParseError> {
+        parse_quantity(val, Length::from_meters, Self::unit, "length", Self::UNITS)
+    }
 
-        Ok(Energy::from_kcalories(val.parse::<f64>()?))
+    fn unit(unit: &str) -> Option<fn(f64) -> Length> {
+        let from_unit: fn(f64) -> Length = match unit {
+            "mm" | "millimeter" | "millimetre" => Length::from_millimeters,
+            "cm" | "centimeter" | "centimetre" => Length::from_centimeters,
+            "m" | "meter" | "metre" => Length::from_meters,
+            "in" | "inch" | "inches" => Length::from_inches,
+            "ft" | "foot" | "feet" => Length::from_feet,
+            "yd" | "yard" => Length::from_yards,
+            _ => return None,
+        };
+        Some(from_unit)
     }
 }
 
@@ -46,36 +251,73 @@ impl EnergyParser {
 pub struct MassParser;
 
 impl MassParser {
+    const UNITS: &'static str = "ug, mg, g, kg, t, ct, gr, dwt, oz, lb or st";
+
     /// Creates measurements::Mass from string
     ///
     /// Tries to figure out the mass unit from the string. If the string value is plain
     /// number, it will be considered as grams. Also empty strings are considered as
     /// zero grams in Mass.
-    pub fn parse(val: &str) -> Result<Mass, ParseFloatError> {
-        if val.is_empty() {
-            return Ok(Mass::from_grams(0.0));
-        }
+    ///
+    /// ```
+    /// use rustybeer_util::{assert_approx, conversions::MassParser};
+    ///
+    /// assert_approx!(20.0, MassParser::parse("1 lb 4 oz").unwrap().as_ounces());
+    /// assert_approx!(0.5, MassParser::parse("1/2 oz").unwrap().as_ounces());
+    /// ```
+    pub fn parse(val: &str) -> Result<Mass, ParseError> {
+        parse_quantity(val, Mass::from_grams, Self::unit, "mass", Self::UNITS)
+    }
 
-        let re = Regex::new(r"([0-9.]*)\s?([a-zA-Zμ]{1,3})$").unwrap();
-        if let Some(caps) = re.captures(val) {
-            let float_val = caps.get(1).unwrap().as_str();
-            return Ok(match caps.get(2).unwrap().as_str() {
-                "ug" | "μg" => Mass::from_micrograms(float_val.parse::<f64>()?),
-                "mg" => Mass::from_milligrams(float_val.parse::<f64>()?),
-                "ct" => Mass::from_carats(float_val.parse::<f64>()?),
-                "g" => Mass::from_grams(float_val.parse::<f64>()?),
-                "kg" => Mass::from_kilograms(float_val.parse::<f64>()?),
-                "T" => Mass::from_metric_tons(float_val.parse::<f64>()?),
-                "gr" => Mass::from_grains(float_val.parse::<f64>()?),
-                "dwt" => Mass::from_pennyweights(float_val.parse::<f64>()?),
-                "oz" => Mass::from_ounces(float_val.parse::<f64>()?),
-                "st" => Mass::from_stones(float_val.parse::<f64>()?),
-                "lbs" => Mass::from_pounds(float_val.parse::<f64>()?),
-                _ => Mass::from_grams(float_val.parse::<f64>()?),
-            });
-        }
+    fn unit(unit: &str) -> Option<fn(f64) -> Mass> {
+        let from_unit: fn(f64) -> Mass = match unit {
+            "ug" | "μg" | "microgram" => Mass::from_micrograms,
+            "mg" | "milligram" => Mass::from_milligrams,
+            "g" | "gram" => Mass::from_grams,
+            "kg" | "kilogram" | "kilo" => Mass::from_kilograms,
+            "t" | "tonne" => Mass::from_metric_tons,
+            "ct" | "carat" => Mass::from_carats,
+            "gr" | "grain" => Mass::from_grains,
+            "dwt" | "pennyweight" => Mass::from_pennyweights,
+            "oz" | "ounce" => Mass::from_ounces,
+            "lb" | "lbs" | "pound" => Mass::from_pounds,
+            "st" | "stone" => Mass::from_stones,
+            _ => return None,
+        };
+        Some(from_unit)
+    }
+}
+
+/// Used to build new measurements::Pressure structs.
+///
+/// To be removed if the dependency some time allows creating measurement units from
+/// strings.
+pub struct PressureParser;
 
-        Ok(Mass::from_grams(val.parse::<f64>()?))
+impl PressureParser {
+    const UNITS: &'static str = "psi, bar, mbar, pa, hpa, kpa or atm";
+
+    /// Creates measurements::Pressure from string
+    ///
+    /// Tries to figure out the pressure unit from the string. If the string value is plain
+    /// number, it will be considered as psi. Also empty strings are considered as
+    /// zero psi in Pressure.
+    pub fn parse(val: &str) -> Result<Pressure, ParseError> {
+        parse_quantity(val, Pressure::from_psi, Self::unit, "pressure", Self::UNITS)
+    }
+
+    fn unit(unit: &str) -> Option<fn(f64) -> Pressure> {
+        let from_unit: fn(f64) -> Pressure = match unit {
+            "psi" => Pressure::from_psi,
+            "bar" => Pressure::from_bars,
+            "mb" | "mbar" | "millibar" => Pressure::from_millibars,
+            "pa" | "pascal" => Pressure::from_pascals,
+            "hpa" | "hectopascal" => Pressure::from_hectopascals,
+            "kpa" | "kilopascal" => Pressure::from_kilopascals,
+            "atm" | "atmosphere" => Pressure::from_atmospheres,
+            _ => return None,
+        };
+        Some(from_unit)
     }
 }
 
@@ -86,31 +328,41 @@ impl MassParser {
 pub struct TemperatureParser;
 
 impl TemperatureParser {
+    const UNITS: &'static str = "C, F, K or R";
+
     /// Creates measurements::Temperature from string
     ///
     /// Tries to figure out the temperature unit from the string. If the string value is plain
     /// number, it will be considered as Celsius. Also empty strings are considered as
-    /// zero Celsius in Temperature.
-    pub fn parse(val: &str) -> Result<Temperature, ParseFloatError> {
-        if val.is_empty() {
-            return Ok(Temperature::from_celsius(0.0));
-        }
-
-        let re = Regex::new(r"([0-9.]*)\s?([a-zA-Z]{1})$").unwrap();
-        if let Some(caps) = re.captures(val) {
-            let float_val = caps.get(1).unwrap().as_str();
-            return Ok(
-                match caps.get(2).unwrap().as_str().to_uppercase().as_str() {
-                    "F" => Temperature::from_fahrenheit(float_val.parse::<f64>()?),
-                    "C" => Temperature::from_celsius(float_val.parse::<f64>()?),
-                    "K" => Temperature::from_kelvin(float_val.parse::<f64>()?),
-                    "R" => Temperature::from_rankine(float_val.parse::<f64>()?),
-                    _ => Temperature::from_celsius(val.parse::<f64>()?),
-                },
-            );
+    /// zero Celsius in Temperature. Temperatures can not be compound quantities.
+    pub fn parse(val: &str) -> Result<Temperature, ParseError> {
+        let mut terms = terms(val)?;
+        match terms.len() {
+            0 => Ok(Temperature::from_celsius(0.0)),
+            1 => {
+                let (value, name) = terms.remove(0);
+                let from_unit = from_unit(
+                    name,
+                    Temperature::from_celsius,
+                    Self::unit,
+                    "temperature",
+                    Self::UNITS,
+                )?;
+                Ok(from_unit(value))
+            }
+            _ => Err(ParseError::Invalid(val.to_string())),
         }
+    }
 
-        Ok(Temperature::from_celsius(val.parse::<f64>()?))
+    fn unit(unit: &str) -> Option<fn(f64) -> Temperature> {
+        let from_unit: fn(f64) -> Temperature = match unit {
+            "c" | "celsius" => Temperature::from_celsius,
+            "f" | "fahrenheit" => Temperature::from_fahrenheit,
+            "k" | "kelvin" => Temperature::from_kelvin,
+            "r" | "rankine" => Temperature::from_rankine,
+            _ => return None,
+        };
+        Some(from_unit)
     }
 }
 
@@ -121,53 +373,89 @@ impl TemperatureParser {
 pub struct VolumeParser;
 
 impl VolumeParser {
+    const UNITS: &'static str = "ml, cl, dl, l, hl, m3, cm3, in3, ft3, yd3, tsp, tbsp, cup, \
+        floz, pt, qt, gal or bbl, where floz, pt, qt, gal and bbl can be prefixed \
+        with 'us' or 'imp'";
+
     /// Creates measurements::Volume from string
     ///
     /// Tries to figure out the volume unit from the string. If the string value is plain
     /// number, it will be considered as litres. Also empty strings are considered as
     /// zero litres in Volume.
-    pub fn parse(val: &str) -> Result<Volume, ParseFloatError> {
-        if val.is_empty() {
-            return Ok(Volume::from_litres(0.0));
-        }
-
-        let re = Regex::new(r"([0-9.]*)\s?([a-zA-Z]{1,3}[0-9]{0,1})$").unwrap();
-        if let Some(caps) = re.captures(val) {
-            let float_val = caps.get(1).unwrap().as_str();
-            return Ok(
-                match caps.get(2).unwrap().as_str().to_lowercase().as_str() {
-                    "cm3" => Volume::from_cubic_centimeters(float_val.parse::<f64>()?),
-                    "ft3" => Volume::from_cubic_feet(float_val.parse::<f64>()?),
-                    "yd3" => Volume::from_cubic_yards(float_val.parse::<f64>()?),
-                    "in3" => Volume::from_cubic_inches(float_val.parse::<f64>()?),
-                    "gal" => Volume::from_gallons(float_val.parse::<f64>()?),
-                    "cup" => Volume::from_cups(float_val.parse::<f64>()?),
-                    "tsp" => Volume::from_teaspoons(float_val.parse::<f64>()?),
-                    "ml" => Volume::from_milliliters(float_val.parse::<f64>()?),
-                    "m3" => Volume::from_cubic_meters(float_val.parse::<f64>()?),
-                    "μl" => Volume::from_drops(float_val.parse::<f64>()?),
-                    "dr" => Volume::from_drams(float_val.parse::<f64>()?),
-                    "l" => Volume::from_litres(float_val.parse::<f64>()?),
-                    "p" => Volume::from_pints(float_val.parse::<f64>()?),
-                    "ʒ" => Volume::from_pints(float_val.parse::<f64>()?),
-                    _ => Volume::from_litres(val.parse::<f64>()?),
-                },
-            );
-        }
+    ///
+    /// US customary units are used for fluid ounces, pints, quarts, gallons and
+    /// barrels unless prefixed with `imp` (e.g. `5 imp gal`). A barrel (`bbl`) is a
+    /// beer barrel of 31 US or 36 imperial gallons. Unknown units are an error.
+    ///
+    /// ```
+    /// use rustybeer_util::{assert_approx, conversions::VolumeParser};
+    ///
+    /// assert_approx!(18.9271, VolumeParser::parse("5 gal").unwrap().as_litres());
+    /// assert_approx!(22.7305, VolumeParser::parse("5 imp gal").unwrap().as_litres());
+    /// assert_approx!(5.5, VolumeParser::parse("5 gal 2 qt").unwrap().as_gallons());
+    /// assert_approx!(20.5, VolumeParser::parse("20,5 l").unwrap().as_litres());
+    /// assert!(VolumeParser::parse("5 buckets").is_err());
+    /// ```
+    pub fn parse(val: &str) -> Result<Volume, ParseError> {
+        parse_quantity(val, Volume::from_litres, Self::unit, "volume", Self::UNITS)
+    }
 
-        Ok(Volume::from_litres(val.parse::<f64>()?))
+    /// Returns the function creating a Volume in the given lowercase unit
+    fn unit(unit: &str) -> Option<fn(f64) -> Volume> {
+        let (imperial, customary) = match unit.split_once(' ') {
+            Some(("imp" | "imperial" | "uk", unit)) => (true, unit),
+            Some(("us", unit)) => (false, unit),
+            _ => (false, unit),
+        };
+
+        let from_unit: fn(f64) -> Volume = match (imperial, customary) {
+            (false, "floz" | "fl oz" | "fluid ounce") => Volume::from_fluid_ounces,
+            (true, "floz" | "fl oz" | "fluid ounce") => Volume::from_fluid_ounces_uk,
+            (false, "p" | "pt" | "pint") => Volume::from_pints,
+            (true, "pt" | "pint") => Volume::from_pints_uk,
+            (false, "qt" | "quart") => Volume::from_quarts,
+            (true, "qt" | "quart") => |v| Volume::from_pints_uk(v * 2.0),
+            (false, "gal" | "gallon") => Volume::from_gallons,
+            (true, "gal" | "gallon") => Volume::from_gallons_uk,
+            (false, "bbl" | "barrel") => |v| Volume::from_gallons(v * 31.0),
+            (true, "bbl" | "barrel") => |v| Volume::from_gallons_uk(v * 36.0),
+            _ if customary != unit => return None,
+            (_, "μl") => Volume::from_drops,
+            (_, "ml" | "milliliter" | "millilitre") => Volume::from_milliliters,
+            (_, "cl" | "centiliter" | "centilitre") => |v| Volume::from_milliliters(v * 10.0),
+            (_, "dl" | "deciliter" | "decilitre") => |v| Volume::from_milliliters(v * 100.0),
+            (_, "l" | "liter" | "litre") => Volume::from_litres,
+            (_, "hl" | "hectoliter" | "hectolitre") => |v| Volume::from_litres(v * 100.0),
+            (_, "m3") => Volume::from_cubic_meters,
+            (_, "cm3" | "cc") => Volume::from_cubic_centimeters,
+            (_, "in3") => Volume::from_cubic_inches,
+            (_, "ft3") => Volume::from_cubic_feet,
+            (_, "yd3") => Volume::from_cubic_yards,
+            (_, "dr") => Volume::from_drams,
+            (_, "ʒ") => Volume::from_pints,
+            (_, "tsp" | "teaspoon") => Volume::from_teaspoons,
+            (_, "tbsp" | "tablespoon") => Volume::from_tablespoons,
+            (_, "cup") => Volume::from_cups,
+            _ => return None,
+        };
+        Some(from_unit)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{EnergyParser, MassParser, TemperatureParser, VolumeParser};
+    use super::{
+        EnergyParser, LengthParser, MassParser, ParseError, PressureParser, TemperatureParser,
+        VolumeParser,
+    };
     use approx::assert_relative_eq;
 
     #[test]
     fn default_from_string() {
         assert_relative_eq!(123.0, EnergyParser::parse("123").unwrap().as_kcalories(),);
+        assert_relative_eq!(123.0, LengthParser::parse("123").unwrap().as_meters(),);
         assert_relative_eq!(123.0, MassParser::parse("123").unwrap().as_grams(),);
+        assert_relative_eq!(123.0, PressureParser::parse("123").unwrap().as_psi(),);
         assert_relative_eq!(123.0, TemperatureParser::parse("123").unwrap().as_celsius(),);
         assert_relative_eq!(123.0, VolumeParser::parse("123").unwrap().as_litres(),);
     }
@@ -176,6 +464,7 @@ mod tests {
     fn zero_from_string() {
         assert_relative_eq!(0., EnergyParser::parse("").unwrap().as_kcalories());
         assert_relative_eq!(0., MassParser::parse("").unwrap().as_grams());
+        assert_relative_eq!(0., PressureParser::parse("").unwrap().as_psi());
         assert_relative_eq!(0., TemperatureParser::parse("").unwrap().as_celsius(),);
         assert_relative_eq!(0., VolumeParser::parse("").unwrap().as_litres());
     }
@@ -274,6 +563,24 @@ mod tests {
         assert_relative_eq!(123.0, EnergyParser::parse("123 J").unwrap().as_joules(),);
     }
 
+    // Length
+    #[test]
+    fn lengths_from_string() {
+        assert_relative_eq!(1.5, LengthParser::parse("1.5m").unwrap().as_meters());
+        assert_relative_eq!(25.0, LengthParser::parse("25 cm").unwrap().as_centimeters());
+        assert_relative_eq!(
+            5.0,
+            LengthParser::parse("5 feet").unwrap().as_feet(),
+            epsilon = 1e-9
+        );
+        assert_relative_eq!(
+            18.0,
+            LengthParser::parse("1 ft 6 in").unwrap().as_inches(),
+            epsilon = 1e-9
+        );
+        assert!(LengthParser::parse("5 furlongs").is_err());
+    }
+
     // Mass
     #[test]
     fn micrograms_from_string() {
@@ -343,6 +650,45 @@ mod tests {
         assert_relative_eq!(123.0, MassParser::parse("123 lbs").unwrap().as_pounds(),);
     }
 
+    // Pressure
+    #[test]
+    fn psi_from_string() {
+        assert_relative_eq!(12.0, PressureParser::parse("12psi").unwrap().as_psi(),);
+        assert_relative_eq!(12.0, PressureParser::parse("12 psi").unwrap().as_psi(),);
+        assert_relative_eq!(12.0, PressureParser::parse("12 PSI").unwrap().as_psi(),);
+    }
+
+    #[test]
+    fn bars_from_string() {
+        assert_relative_eq!(0.8, PressureParser::parse("0.8bar").unwrap().as_bars(),);
+        assert_relative_eq!(0.8, PressureParser::parse("0.8 bar").unwrap().as_bars(),);
+        assert_relative_eq!(0.8, PressureParser::parse("0.8 BAR").unwrap().as_bars(),);
+    }
+
+    #[test]
+    fn kilopascals_from_string() {
+        assert_relative_eq!(
+            80.0,
+            PressureParser::parse("80kPa").unwrap().as_kilopascals(),
+        );
+        assert_relative_eq!(
+            80.0,
+            PressureParser::parse("80 kpa").unwrap().as_kilopascals(),
+        );
+    }
+
+    #[test]
+    fn atmospheres_from_string() {
+        assert_relative_eq!(
+            1.5,
+            PressureParser::parse("1.5atm").unwrap().as_atmospheres(),
+        );
+        assert_relative_eq!(
+            1.5,
+            PressureParser::parse("1.5 atm").unwrap().as_atmospheres(),
+        );
+    }
+
     // Temperature
     #[test]
     fn fahrenheit_from_string() {
@@ -449,5 +795,165 @@ mod tests {
         assert_relative_eq!(123.0, VolumeParser::parse("123p").unwrap().as_pints(),);
         assert_relative_eq!(123.0, VolumeParser::parse("123 p").unwrap().as_pints(),);
         assert_relative_eq!(123.0, VolumeParser::parse("123 P").unwrap().as_pints(),);
+        assert_relative_eq!(123.0, VolumeParser::parse("123 pints").unwrap().as_pints(),);
+        assert_relative_eq!(
+            123.0,
+            VolumeParser::parse("123 imp pt").unwrap().as_pints_uk(),
+        );
+    }
+
+    #[test]
+    fn gallons_from_string() {
+        assert_relative_eq!(123.0, VolumeParser::parse("123gal").unwrap().as_gallons(),);
+        assert_relative_eq!(
+            123.0,
+            VolumeParser::parse("123 US gallons").unwrap().as_gallons(),
+        );
+        assert_relative_eq!(
+            123.0,
+            VolumeParser::parse("123 imp gal").unwrap().as_gallons_uk(),
+        );
+        assert_relative_eq!(
+            123.0,
+            VolumeParser::parse("123 imperial gallons")
+                .unwrap()
+                .as_gallons_uk(),
+        );
+    }
+
+    #[test]
+    fn quarts_and_fluid_ounces_from_string() {
+        assert_relative_eq!(123.0, VolumeParser::parse("123qt").unwrap().as_quarts(),);
+        assert_relative_eq!(
+            123.0,
+            VolumeParser::parse("123 quarts").unwrap().as_quarts(),
+        );
+        assert_relative_eq!(
+            123.0,
+            VolumeParser::parse("123 imp qt").unwrap().as_pints_uk() / 2.0,
+        );
+        assert_relative_eq!(
+            123.0,
+            VolumeParser::parse("123floz").unwrap().as_fluid_ounces(),
+        );
+        assert_relative_eq!(
+            123.0,
+            VolumeParser::parse("123 fl. oz.")
+                .unwrap()
+                .as_fluid_ounces(),
+        );
+        assert_relative_eq!(
+            123.0,
+            VolumeParser::parse("123 imp fl oz")
+                .unwrap()
+                .as_fluid_ounces_uk(),
+        );
+    }
+
+    #[test]
+    fn barrels_and_hectoliters_from_string() {
+        assert_relative_eq!(
+            117.347765,
+            VolumeParser::parse("1bbl").unwrap().as_litres(),
+            epsilon = 1e-6
+        );
+        assert_relative_eq!(
+            163.65924,
+            VolumeParser::parse("1 imp barrel").unwrap().as_litres(),
+            epsilon = 1e-4
+        );
+        assert_relative_eq!(1200.0, VolumeParser::parse("12hl").unwrap().as_litres(),);
+        assert_relative_eq!(
+            1200.0,
+            VolumeParser::parse("12 hectolitres").unwrap().as_litres(),
+        );
+    }
+
+    #[test]
+    fn unknown_volume_unit() {
+        match VolumeParser::parse("5 buckets") {
+            Err(ParseError::UnknownUnit { quantity, unit, .. }) => {
+                assert_eq!("volume", quantity);
+                assert_eq!("buckets", unit);
+            }
+            other => panic!("expected unknown unit, got {:?}", other),
+        }
+        assert!(VolumeParser::parse("5 imp ml").is_err());
+        assert!(matches!(
+            VolumeParser::parse("five l"),
+            Err(ParseError::Invalid(_))
+        ));
+    }
+
+    // Quantity grammar
+    #[test]
+    fn compound_quantities() {
+        assert_relative_eq!(
+            5.5,
+            VolumeParser::parse("5 gal 2 qt").unwrap().as_gallons(),
+            epsilon = 1e-9
+        );
+        assert_relative_eq!(
+            20.0,
+            MassParser::parse("1 lb 4 oz").unwrap().as_ounces(),
+            epsilon = 1e-9
+        );
+        assert_relative_eq!(
+            1.25,
+            MassParser::parse("1kg 250g").unwrap().as_kilograms(),
+            epsilon = 1e-9
+        );
+        assert!(VolumeParser::parse("5 gal 2").is_err());
+        assert!(TemperatureParser::parse("20 C 5 F").is_err());
+    }
+
+    #[test]
+    fn decimal_comma() {
+        assert_relative_eq!(20.5, VolumeParser::parse("20,5 l").unwrap().as_litres());
+        assert_relative_eq!(0.5, VolumeParser::parse(",5").unwrap().as_litres());
+        assert_relative_eq!(
+            12.5,
+            TemperatureParser::parse("12,5°C").unwrap().as_celsius()
+        );
+        assert_relative_eq!(1.25, VolumeParser::parse("1,25 l").unwrap().as_litres());
+        assert_relative_eq!(1.0, VolumeParser::parse("1.000 l").unwrap().as_litres());
+        assert_eq!(
+            Err(ParseError::Invalid("1,000 l".to_string())),
+            VolumeParser::parse("1,000 l")
+        );
+        assert!(MassParser::parse("2,500kg").is_err());
+    }
+
+    #[test]
+    fn fractions() {
+        assert_relative_eq!(0.5, MassParser::parse("1/2 oz").unwrap().as_ounces());
+        assert_relative_eq!(
+            1.5,
+            VolumeParser::parse("1 1/2 gal").unwrap().as_gallons(),
+            epsilon = 1e-9
+        );
+        assert_relative_eq!(
+            -0.75,
+            TemperatureParser::parse("-3/4 C").unwrap().as_celsius()
+        );
+        assert!(MassParser::parse("1/0 oz").is_err());
+    }
+
+    #[test]
+    fn scientific_notation() {
+        assert_relative_eq!(
+            1000.0,
+            VolumeParser::parse("1e3 ml").unwrap().as_milliliters()
+        );
+        assert_relative_eq!(0.015, MassParser::parse("1.5E-2kg").unwrap().as_kilograms());
+        assert_relative_eq!(2.5, EnergyParser::parse("2.5ev").unwrap().as_e_v());
+    }
+
+    #[test]
+    fn unknown_units() {
+        assert!(EnergyParser::parse("5 calories").is_err());
+        assert!(MassParser::parse("5 bags").is_err());
+        assert!(PressureParser::parse("5 torr").is_err());
+        assert!(TemperatureParser::parse("5 X").is_err());
     }
 }
@@ -1,17 +1,33 @@
 /// Hops list curated from https://www.brewersfriend.com/2010/02/27/hops-alpha-acid-table-2009/
 use once_cell::sync::Lazy;
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Hop {
     pub name: String,
-    #[serde(alias = "aa_min", deserialize_with = "percentage_to_float")]
+    #[serde(
+        alias = "aa_min",
+        deserialize_with = "percentage_to_float",
+        serialize_with = "float_to_percentage"
+    )]
     pub alpha_acid_min: f64,
-    #[serde(alias = "aa_max", deserialize_with = "percentage_to_float")]
+    #[serde(
+        alias = "aa_max",
+        deserialize_with = "percentage_to_float",
+        serialize_with = "float_to_percentage"
+    )]
     pub alpha_acid_max: f64,
-    #[serde(alias = "ba_min", deserialize_with = "percentage_to_float")]
+    #[serde(
+        alias = "ba_min",
+        deserialize_with = "percentage_to_float",
+        serialize_with = "float_to_percentage"
+    )]
     pub beta_acid_min: f64,
-    #[serde(alias = "ba_max", deserialize_with = "percentage_to_float")]
+    #[serde(
+        alias = "ba_max",
+        deserialize_with = "percentage_to_float",
+        serialize_with = "float_to_percentage"
+    )]
     pub beta_acid_max: f64,
     pub purpose: Vec<String>,
     pub country: String,
@@ -28,6 +44,13 @@ where
     Ok(f64::deserialize(deserializer)? / 100.)
 }
 
+fn float_to_percentage<S>(value: &f64, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_f64(value * 100.)
+}
+
 impl Hop {
     /// Returns average alpha acid for the hop.
     pub fn average_alpha_acid(&self) -> f64 {
@@ -35,11 +58,17 @@ impl Hop {
     }
 }
 
-pub static HOPS: Lazy<Vec<Hop>> = Lazy::new(|| serde_json::from_str(HOPS_JSON).unwrap());
+/// All available hops.
+///
+/// Data will be loaded from JSON on the first use.
+pub static HOPS: Lazy<Vec<Hop>> = Lazy::new(|| {
+    serde_json::from_str(HOPS_JSON)
+        .expect("bundled hops data, checked by the tests, could not be deserialised")
+});
 
 #[cfg(test)]
 pub mod tests {
-    use super::HOPS;
+    use super::{Hop, HOPS};
     use approx::assert_relative_eq;
 
     #[test]
@@ -52,4 +81,12 @@ pub mod tests {
                 .alpha_acid_min
         );
     }
+
+    #[test]
+    fn hop_round_trip() {
+        let hop = &HOPS[0];
+        let parsed: Hop = serde_json::from_str(&serde_json::to_string(hop).unwrap()).unwrap();
+        assert_relative_eq!(hop.alpha_acid_min, parsed.alpha_acid_min);
+        assert_eq!(hop.name, parsed.name);
+    }
 }
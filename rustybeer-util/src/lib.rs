@@ -1,13 +1,19 @@
 //! Utilities for working with `rustybeer`:
 //! * List of beer styles that can be indexed and requested
+//! * List of containers for packaging, extendable with a user file
 //! * List of hops and their AA%
+//! * List of malts and their extract potential and color
+//! * List of priming sugars and their fermentability
 //! * List of yeasts and their properties
 //! * Units conversions from strings
 
 pub mod abv_calories;
 pub mod beer_styles;
+pub mod containers;
 pub mod conversions;
 pub mod hops;
+pub mod malts;
+pub mod sugars;
 pub mod yeasts;
 
 pub use measurements;
@@ -0,0 +1,56 @@
+/// Malt list curated from typical maltster specifications
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+/// The role a malt usually has in the grain bill
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MaltKind {
+    /// Pale malt making up most of the grist
+    Base,
+    /// Malted or flaked wheat
+    Wheat,
+    /// Unmalted grain, like flaked oats or maize
+    Adjunct,
+    /// Crystal and caramel malts, for sweetness and color
+    Caramel,
+    /// Kilned malts with toasty and bready flavor
+    Kilned,
+    /// Roasted malts and barley for dark beers
+    Roasted,
+}
+
+/// Data about a malt used in the grain bill.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Malt {
+    pub name: String,
+    pub kind: MaltKind,
+    /// extract potential as specific gravity of one pound in one US gallon, e.g. 1.037
+    pub potential: f64,
+    /// color of the malt (°L)
+    pub color_lovibond: f64,
+    /// recommended maximum share of the grist in percent
+    pub max_percent: u8,
+}
+
+// Raw inlined malt data
+static MALTS_JSON: &str = include_str!("json/malts.json");
+
+/// All available malts.
+///
+/// Data will be loaded from JSON on the first use.
+pub static MALTS: Lazy<Vec<Malt>> = Lazy::new(|| {
+    serde_json::from_str(MALTS_JSON)
+        .expect("bundled malts data, checked by the tests, could not be deserialised")
+});
+
+#[cfg(test)]
+mod tests {
+    use super::{MaltKind, MALTS};
+
+    #[test]
+    fn maris_otter() {
+        let malt = MALTS.iter().find(|&m| m.name == "Maris Otter").unwrap();
+        assert_eq!(MaltKind::Base, malt.kind);
+        approx::assert_relative_eq!(1.038, malt.potential);
+    }
+}
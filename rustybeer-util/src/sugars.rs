@@ -0,0 +1,48 @@
+/// Priming sugar list curated from https://www.brewersfriend.com/beer-priming-calculator/
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+/// The form a sugar is usually bought in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SugarForm {
+    /// Dry sugar measured by weight or spoons
+    Solid,
+    /// Liquid sugar measured by weight or volume
+    Syrup,
+}
+
+/// Data about a sugar used for priming.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrimingSugar {
+    pub name: String,
+    /// CO2 produced relative to the same weight of table sugar (sucrose)
+    pub fermentability: f64,
+    /// extract potential in gravity points per pound per gallon (PPG)
+    pub extract: u8,
+    pub form: SugarForm,
+    /// weight of the sugar per volume as usually measured (g/ml)
+    pub density: f64,
+}
+
+// Raw inlined sugar data
+static SUGARS_JSON: &str = include_str!("json/sugars.json");
+
+/// All available priming sugars.
+///
+/// Data will be loaded from JSON on the first use.
+pub static SUGARS: Lazy<Vec<PrimingSugar>> = Lazy::new(|| {
+    serde_json::from_str(SUGARS_JSON)
+        .expect("bundled sugars data, checked by the tests, could not be deserialised")
+});
+
+#[cfg(test)]
+mod tests {
+    use super::{SugarForm, SUGARS};
+
+    #[test]
+    fn honey() {
+        let honey = SUGARS.iter().find(|&s| s.name == "Honey").unwrap();
+        assert_eq!(SugarForm::Syrup, honey.form);
+        approx::assert_relative_eq!(0.74, honey.fermentability);
+    }
+}
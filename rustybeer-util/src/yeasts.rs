@@ -1,19 +1,22 @@
 /// Yeast list curated from https://www.brewersfriend.com/yeast/
 use measurements::temperature::Temperature;
 use once_cell::sync::Lazy;
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Level {
     Low,
+    #[serde(rename = "Med-Low")]
     MedLow,
     Medium,
+    #[serde(rename = "Med-High")]
     MedHigh,
     High,
+    #[serde(rename = "Very High")]
     VeryHigh,
 }
 
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Yeast {
     pub company: String,
     pub name: String,
@@ -24,9 +27,21 @@ pub struct Yeast {
     pub attenuation_level: Option<Level>,
     #[serde(deserialize_with = "level_from_str")]
     pub flocculation: Option<Level>,
-    #[serde(deserialize_with = "temp_from_str")]
+    /// serialized in Fahrenheit as `min_temp_fahrenheit`
+    #[serde(
+        rename = "min_temp_fahrenheit",
+        alias = "min_temp",
+        deserialize_with = "temp_from_str",
+        serialize_with = "temp_to_fahrenheit"
+    )]
     pub min_temp: Option<Temperature>,
-    #[serde(deserialize_with = "temp_from_str")]
+    /// serialized in Fahrenheit as `max_temp_fahrenheit`
+    #[serde(
+        rename = "max_temp_fahrenheit",
+        alias = "max_temp",
+        deserialize_with = "temp_from_str",
+        serialize_with = "temp_to_fahrenheit"
+    )]
     pub max_temp: Option<Temperature>,
     pub alc_tolerance: Option<u8>,
     #[serde(deserialize_with = "level_from_str")]
@@ -56,17 +71,55 @@ fn temp_from_str<'de, D>(deserializer: D) -> Result<Option<Temperature>, D::Erro
 where
     D: Deserializer<'de>,
 {
-    let t = u8::deserialize(deserializer);
+    let t = f64::deserialize(deserializer);
     match t {
-        Ok(v) => Ok(Some(Temperature::from_fahrenheit(v as f64))),
+        Ok(v) => Ok(Some(Temperature::from_fahrenheit(v))),
         Err(_) => Ok(None),
     }
 }
 
+/// Serializes a temperature in Fahrenheit, rounded to hundredths to drop
+/// the error of converting through Kelvin
+fn temp_to_fahrenheit<S>(temp: &Option<Temperature>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match temp {
+        Some(t) => serializer.serialize_some(&((t.as_fahrenheit() * 100.).round() / 100.)),
+        None => serializer.serialize_none(),
+    }
+}
+
 static YEASTS_JSON: &str = include_str!("json/yeasts.json");
 
 /// All available yeasts.
 ///
 /// Data will be loaded from JSON on the first use.
-pub static YEASTS: Lazy<Vec<Yeast>> =
-    Lazy::new(|| serde_json::from_str(YEASTS_JSON).expect("yeasts data could not be deserialised"));
+pub static YEASTS: Lazy<Vec<Yeast>> = Lazy::new(|| {
+    serde_json::from_str(YEASTS_JSON)
+        .expect("bundled yeasts data, checked by the tests, could not be deserialised")
+});
+
+#[cfg(test)]
+mod tests {
+    use super::YEASTS;
+
+    #[test]
+    fn bundled_yeasts() {
+        assert!(YEASTS.iter().any(|y| y.name.contains("California Ale")));
+    }
+
+    #[test]
+    fn yeast_round_trip() {
+        let yeast = &YEASTS[0];
+        let json = serde_json::to_string(yeast).unwrap();
+        let parsed: super::Yeast = serde_json::from_str(&json).unwrap();
+        assert_eq!(yeast.name, parsed.name);
+        assert_eq!(yeast.flocculation, parsed.flocculation);
+        assert_eq!(
+            yeast.min_temp.map(|t| t.as_fahrenheit()),
+            parsed.min_temp.map(|t| t.as_fahrenheit())
+        );
+        assert!(json.contains(r#""min_temp_fahrenheit":55.0,"#));
+    }
+}
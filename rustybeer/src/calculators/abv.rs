@@ -5,13 +5,51 @@
 //!
 //! // Takes the arguments original gravity
 //! // and final gravity
-//! assert_eq!(1050., calculate_abv(10., 2.));
+//! assert_eq!(Ok(1050.), calculate_abv(10., 2.));
 //! ```
+//!
+//! The same with typed quantities:
+//! ```
+//! use rustybeer::calculators::abv::abv;
+//! use rustybeer::units::Gravity;
+//! use rustybeer_util::assert_approx;
+//!
+//! let og = Gravity::from_specific_gravity(1.050);
+//! let fg = Gravity::from_specific_gravity(1.010);
+//! assert_approx!(5.25, abv(og, fg).unwrap().as_percent());
+//! ```
+
+use crate::error::{self, Result};
+use crate::units::{Abv, Gravity};
+
+/// Calculates alcohol by volume from original gravity and final gravity
+pub fn abv(og: Gravity, fg: Gravity) -> Result<Abv> {
+    let og = og.as_specific_gravity();
+    let fg = fg.as_specific_gravity();
+    error::gravities(og, fg)?;
+    Ok(Abv::from_percent((og - fg) * 131.25))
+}
+
+/// Calculates final gravity from original gravity and alcohol by volume
+pub fn fg(og: Gravity, abv: Abv) -> Result<Gravity> {
+    let abv = error::in_range("ABV (%)", abv.as_percent(), 0.0, 100.0)?;
+    Ok(Gravity::from_specific_gravity(
+        og.as_specific_gravity() - (abv / 131.25),
+    ))
+}
 
-pub fn calculate_abv(og: f32, fg: f32) -> f32 {
-    (og - fg) * 131.25
+pub fn calculate_abv(og: f32, fg: f32) -> Result<f32> {
+    Ok(abv(
+        Gravity::from_specific_gravity(og.into()),
+        Gravity::from_specific_gravity(fg.into()),
+    )?
+    .as_percent() as f32)
 }
 
-pub fn calculate_fg(og: f32, abv: f32) -> f32 {
-    og - (abv / 131.25)
+pub fn calculate_fg(og: f32, abv: f32) -> Result<f32> {
+    Ok(fg(
+        Gravity::from_specific_gravity(og.into()),
+        Abv::from_percent(abv.into()),
+    )?
+    .as_specific_gravity() as f32)
 }
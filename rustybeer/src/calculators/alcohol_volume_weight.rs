@@ -7,14 +7,17 @@
 //! assert_eq!(4., calculate_abv_abw(5.));
 //! ```
 
+use crate::error::{self, Result};
+
 static ETHANOL_DENSITY: f64 = 0.789;
 
 pub fn calculate_abv_abw(percent: f64) -> f64 {
     percent * 0.8
 }
 
-pub fn calculate_abv_abw_density(percent: f64, total_density: f64) -> f64 {
-    percent * (ETHANOL_DENSITY / total_density)
+pub fn calculate_abv_abw_density(percent: f64, total_density: f64) -> Result<f64> {
+    let total_density = error::positive("density", total_density)?;
+    Ok(percent * (ETHANOL_DENSITY / total_density))
 }
 
 pub fn calculate_abw_abv(percent: f64) -> f64 {
@@ -2,21 +2,71 @@
 //! calorie content
 //! ```
 //! use rustybeer::calculators::calorie_counter::calculate_total_calories;
+//! use rustybeer_util::assert_approx;
 //!
 //! // Takes the arguments original gravity
 //! // and final gravity
-//! assert_eq!(178.89642, calculate_total_calories(1.054, 1.010));
+//! assert_approx!(178.8963, calculate_total_calories(1.054, 1.010).unwrap());
 //! ```
 
+use crate::error::{self, Result};
+use crate::units::{Energy, Gravity};
+
+/// The formula divides by the distance of original gravity to this
+static MAX_GRAVITY: f64 = 1.775;
+
+/// returns calories for a 12 oz. serving size
+pub fn total_calories(og: Gravity, fg: Gravity) -> Result<Energy> {
+    Ok(Energy::from_kcalories(
+        alcohol_calories(og, fg)?.as_kcalories() + carbs_calories(og, fg)?.as_kcalories(),
+    ))
+}
+
+/// returns calories from alcohol for a 12 oz. serving size
+pub fn alcohol_calories(og: Gravity, fg: Gravity) -> Result<Energy> {
+    let (og, fg) = validate(og, fg)?;
+    Ok(Energy::from_kcalories(
+        1881.22 * fg * (og - fg) / (MAX_GRAVITY - og),
+    ))
+}
+
+/// returns calories from carbohydrates for a 12 oz. serving size
+pub fn carbs_calories(og: Gravity, fg: Gravity) -> Result<Energy> {
+    let (og, fg) = validate(og, fg)?;
+    Ok(Energy::from_kcalories(
+        3550.0 * fg * ((0.1808 * og) + (0.8192 * fg) - 1.0004),
+    ))
+}
+
+fn validate(og: Gravity, fg: Gravity) -> Result<(f64, f64)> {
+    let og = og.as_specific_gravity();
+    let fg = fg.as_specific_gravity();
+    error::in_range("original gravity", og, 1.0, MAX_GRAVITY - f64::EPSILON)?;
+    error::gravities(og, fg)?;
+    Ok((og, fg))
+}
+
 /// returns calories for a 12 oz. serving size
-pub fn calculate_total_calories(og: f32, fg: f32) -> f32 {
-    calculate_alcohol_calories(og, fg) + calculate_carbs_calories(og, fg)
+pub fn calculate_total_calories(og: f32, fg: f32) -> Result<f32> {
+    Ok(total_calories(
+        Gravity::from_specific_gravity(og.into()),
+        Gravity::from_specific_gravity(fg.into()),
+    )?
+    .as_kcalories() as f32)
 }
 
-pub fn calculate_alcohol_calories(og: f32, fg: f32) -> f32 {
-    1881.22 * fg * (og - fg) / (1.775 - og)
+pub fn calculate_alcohol_calories(og: f32, fg: f32) -> Result<f32> {
+    Ok(alcohol_calories(
+        Gravity::from_specific_gravity(og.into()),
+        Gravity::from_specific_gravity(fg.into()),
+    )?
+    .as_kcalories() as f32)
 }
 
-pub fn calculate_carbs_calories(og: f32, fg: f32) -> f32 {
-    3550.0 * fg * ((0.1808 * og) + (0.8192 * fg) - 1.0004)
+pub fn calculate_carbs_calories(og: f32, fg: f32) -> Result<f32> {
+    Ok(carbs_calories(
+        Gravity::from_specific_gravity(og.into()),
+        Gravity::from_specific_gravity(fg.into()),
+    )?
+    .as_kcalories() as f32)
 }
@@ -0,0 +1,563 @@
+//! Compares two versions of a recipe
+//!
+//! Lists the ingredients that were added, removed or changed in
+//! amount or in an attribute such as a hop's alpha acid or boil
+//! time, and how the predicted OG, FG, ABV, IBU, color and BU:GU
+//! moved. Ingredients are matched by name. Additions of the same hop
+//! are paired by boil time first, so a hop moved from one time to
+//! another is a changed addition. Repeated entries are paired in
+//! recipe order.
+//! ```
+//! use rustybeer::calculators::comparison::{calculate_comparison, Change};
+//! use rustybeer::recipe::{Fermentable, Recipe};
+//! use rustybeer_util::assert_approx;
+//!
+//! let batch_1 = Recipe {
+//!     name: "Pale Ale".to_owned(),
+//!     batch_volume_liters: 20.,
+//!     efficiency: 0.75,
+//!     fermentables: vec![Fermentable::grain("Pale Malt", 4500., 1.037, 3.)],
+//!     ..Default::default()
+//! };
+//! let mut batch_2 = batch_1.clone();
+//! batch_2.fermentables[0].weight_grams = 5000.;
+//!
+//! let comparison = calculate_comparison(&batch_1, &batch_2, 1.012, 1.012).unwrap();
+//! assert_eq!(Change::Changed, comparison.ingredients[0].change);
+//! assert_approx!(0.0058, comparison.original_gravity.delta);
+//! ```
+
+use crate::calculators::{abv::abv, ibu::bu_gu};
+use crate::error::Result;
+use crate::recipe::Recipe;
+use crate::units::Gravity;
+use std::fmt;
+
+/// Amounts closer than this are considered unchanged
+const AMOUNT_TOLERANCE: f64 = 1e-6;
+
+/// An enum of recipe ingredient kinds
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum IngredientKind {
+    Fermentable,
+    Hop,
+    Salt,
+    Yeast,
+}
+
+/// An enum of the ways an ingredient differs between two recipes
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Change {
+    /// Only in the second recipe
+    Added,
+    /// Only in the first recipe
+    Removed,
+    /// In both recipes with a different amount or attributes
+    Changed,
+}
+
+/// An ingredient that differs between two recipes
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IngredientChange {
+    pub kind: IngredientKind,
+    pub name: String,
+    /// boil time of a hop addition (min), in the first recipe unless added
+    pub time_mins: Option<u32>,
+    pub change: Change,
+    /// amount in the first recipe, grams or billions of yeast cells
+    pub before: f64,
+    /// amount in the second recipe, grams or billions of yeast cells
+    pub after: f64,
+    /// attributes other than the amount that differ
+    pub attributes: Vec<AttributeChange>,
+}
+
+/// An attribute of an ingredient that differs between two recipes
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AttributeChange {
+    /// name of the attribute in the recipe, e.g. `alpha_acid_percentage`
+    pub attribute: String,
+    pub before: String,
+    pub after: String,
+}
+
+/// A predicted value of both recipes
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Delta {
+    pub before: f64,
+    pub after: f64,
+    /// `after - before`
+    pub delta: f64,
+}
+
+impl Delta {
+    pub fn new(before: f64, after: f64) -> Self {
+        Self {
+            before,
+            after,
+            delta: after - before,
+        }
+    }
+}
+
+/// Differences between two recipes
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecipeComparison {
+    /// ingredients that were added, removed or changed
+    pub ingredients: Vec<IngredientChange>,
+    /// original gravity (sg)
+    pub original_gravity: Delta,
+    /// final gravity (sg)
+    pub final_gravity: Delta,
+    /// alcohol by volume (%)
+    pub abv: Delta,
+    pub ibu: Delta,
+    /// color (SRM)
+    pub color_srm: Delta,
+    pub bu_gu: Delta,
+}
+
+/// Compares two recipes with final gravities given as specific gravity
+pub fn calculate_comparison(
+    before: &Recipe,
+    after: &Recipe,
+    before_final_gravity: f64,
+    after_final_gravity: f64,
+) -> Result<RecipeComparison> {
+    compare(
+        before,
+        after,
+        Gravity::from_specific_gravity(before_final_gravity),
+        Gravity::from_specific_gravity(after_final_gravity),
+    )
+}
+
+/// Compares two recipes, each fermented to the given final gravity
+pub fn compare(
+    before: &Recipe,
+    after: &Recipe,
+    before_final_gravity: Gravity,
+    after_final_gravity: Gravity,
+) -> Result<RecipeComparison> {
+    let before_values = Predicted::new(before, before_final_gravity)?;
+    let after_values = Predicted::new(after, after_final_gravity)?;
+
+    let mut ingredients = Vec::new();
+    for kind in [
+        IngredientKind::Fermentable,
+        IngredientKind::Hop,
+        IngredientKind::Salt,
+        IngredientKind::Yeast,
+    ] {
+        let before_entries = entries(before, kind);
+        let after_entries = entries(after, kind);
+        let pairs = pair(&before_entries, &after_entries);
+        for (entry, paired) in before_entries.iter().zip(&pairs) {
+            let change = match paired {
+                None => entry.change(kind, Change::Removed, None),
+                Some(index) => {
+                    let after = &after_entries[*index];
+                    let change = entry.change(kind, Change::Changed, Some(after));
+                    if (after.amount - entry.amount).abs() <= AMOUNT_TOLERANCE
+                        && change.attributes.is_empty()
+                    {
+                        continue;
+                    }
+                    change
+                }
+            };
+            ingredients.push(change);
+        }
+        for (index, entry) in after_entries.iter().enumerate() {
+            if !pairs.contains(&Some(index)) {
+                ingredients.push(entry.change(kind, Change::Added, None));
+            }
+        }
+    }
+
+    let delta =
+        |value: fn(&Predicted) -> f64| Delta::new(value(&before_values), value(&after_values));
+    Ok(RecipeComparison {
+        ingredients,
+        original_gravity: delta(|p| p.original_gravity),
+        final_gravity: delta(|p| p.final_gravity),
+        abv: delta(|p| p.abv),
+        ibu: delta(|p| p.ibu),
+        color_srm: delta(|p| p.color_srm),
+        bu_gu: delta(|p| p.bu_gu),
+    })
+}
+
+/// Values predicted for one recipe
+struct Predicted {
+    original_gravity: f64,
+    final_gravity: f64,
+    abv: f64,
+    ibu: f64,
+    color_srm: f64,
+    bu_gu: f64,
+}
+
+impl Predicted {
+    fn new(recipe: &Recipe, final_gravity: Gravity) -> Result<Self> {
+        let original_gravity = recipe.original_gravity()?;
+        let ibu = recipe.ibu()?;
+        Ok(Self {
+            original_gravity: original_gravity.as_specific_gravity(),
+            final_gravity: final_gravity.as_specific_gravity(),
+            abv: abv(original_gravity, final_gravity)?.as_percent(),
+            ibu: ibu.as_ibu(),
+            color_srm: recipe.color()?.as_srm(),
+            bu_gu: bu_gu(ibu, original_gravity)?,
+        })
+    }
+}
+
+/// An ingredient of a recipe with its attributes as text
+struct Entry {
+    name: String,
+    time_mins: Option<u32>,
+    /// grams or billions of yeast cells
+    amount: f64,
+    attributes: Vec<(&'static str, String)>,
+}
+
+impl Entry {
+    fn new(name: &str, time_mins: Option<u32>, amount: f64) -> Self {
+        Self {
+            name: name.to_owned(),
+            time_mins,
+            amount,
+            attributes: vec![],
+        }
+    }
+
+    fn attribute(mut self, attribute: &'static str, value: impl fmt::Debug) -> Self {
+        self.attributes.push((attribute, format!("{:?}", value)));
+        self
+    }
+
+    /// The change from this entry to the one it is paired with, if any.
+    /// An added entry is from the second recipe.
+    fn change(
+        &self,
+        kind: IngredientKind,
+        change: Change,
+        after: Option<&Entry>,
+    ) -> IngredientChange {
+        let attributes = match after {
+            Some(after) => self
+                .attributes
+                .iter()
+                .zip(&after.attributes)
+                .filter(|((_, before), (_, after))| before != after)
+                .map(|((attribute, before), (_, after))| AttributeChange {
+                    attribute: attribute.to_string(),
+                    before: before.clone(),
+                    after: after.clone(),
+                })
+                .collect(),
+            None => vec![],
+        };
+        let (before, after_amount) = match change {
+            Change::Added => (0., self.amount),
+            _ => (self.amount, after.map_or(0., |after| after.amount)),
+        };
+        IngredientChange {
+            kind,
+            name: self.name.clone(),
+            time_mins: self.time_mins,
+            change,
+            before,
+            after: after_amount,
+            attributes,
+        }
+    }
+}
+
+/// Ingredients of a kind in recipe order. Repeated entries of the same
+/// ingredient are kept apart, so their attributes are compared one by one.
+fn entries(recipe: &Recipe, kind: IngredientKind) -> Vec<Entry> {
+    match kind {
+        IngredientKind::Fermentable => recipe
+            .fermentables
+            .iter()
+            .map(|f| {
+                Entry::new(&f.name, None, f.weight_grams)
+                    .attribute("potential", f.potential)
+                    .attribute("color_lovibond", f.color_lovibond)
+                    .attribute("type", f.fermentable_type)
+            })
+            .collect(),
+        IngredientKind::Hop => recipe
+            .hops
+            .iter()
+            .map(|h| {
+                let addition = &h.addition;
+                Entry::new(&h.name, Some(addition.time_mins), addition.weight_grams)
+                    .attribute("alpha_acid_percentage", addition.alpha_acid_percentage)
+                    .attribute("time_mins", addition.time_mins)
+                    .attribute("hop_type", addition.hop_type)
+            })
+            .collect(),
+        IngredientKind::Salt => recipe
+            .salts
+            .iter()
+            .map(|s| Entry::new(&s.name, None, s.weight_grams))
+            .collect(),
+        IngredientKind::Yeast => recipe
+            .yeasts
+            .iter()
+            .map(|y| Entry::new(&y.name, None, y.cells_billions))
+            .collect(),
+    }
+}
+
+/// Index of the entry after each entry before is paired with. Entries of
+/// the same name and boil time are paired first, then the remaining
+/// entries of the same name in recipe order.
+fn pair(before: &[Entry], after: &[Entry]) -> Vec<Option<usize>> {
+    let mut pairs: Vec<Option<usize>> = vec![None; before.len()];
+    let mut paired = vec![false; after.len()];
+    for same_time in [true, false] {
+        for (entry, pair) in before.iter().zip(pairs.iter_mut()) {
+            if pair.is_some() {
+                continue;
+            }
+            *pair = after.iter().enumerate().position(|(index, other)| {
+                !paired[index]
+                    && other.name == entry.name
+                    && (!same_time || other.time_mins == entry.time_mins)
+            });
+            if let Some(index) = *pair {
+                paired[index] = true;
+            }
+        }
+    }
+    pairs
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::{compare, AttributeChange, Change, IngredientKind};
+    use crate::calculators::ibu::{HopAddition, HopAdditionType};
+    use crate::recipe::{Fermentable, Recipe, RecipeHop, RecipeYeast};
+    use crate::units::Gravity;
+    use crate::Error;
+    use rustybeer_util::assert_approx;
+
+    fn batch_1() -> Recipe {
+        Recipe {
+            name: "Session IPA".to_owned(),
+            batch_volume_liters: 20.,
+            efficiency: 0.75,
+            fermentables: vec![
+                Fermentable::grain("Pale Malt", 4000., 1.037, 3.),
+                Fermentable::grain("Crystal 40", 300., 1.034, 40.),
+            ],
+            hops: vec![
+                RecipeHop::new(
+                    "Magnum",
+                    HopAddition::new(15., 0.12, 60, HopAdditionType::Pellet),
+                ),
+                RecipeHop::new(
+                    "Citra",
+                    HopAddition::new(30., 0.12, 10, HopAdditionType::Pellet),
+                ),
+            ],
+            salts: vec![],
+            yeasts: vec![RecipeYeast {
+                name: "US-05".to_owned(),
+                cells_billions: 200.,
+            }],
+            mash_steps: vec![],
+        }
+    }
+
+    #[test]
+    fn unchanged_recipe() {
+        let gravity = Gravity::from_specific_gravity(1.010);
+        let comparison = compare(&batch_1(), &batch_1(), gravity, gravity).unwrap();
+        assert!(comparison.ingredients.is_empty());
+        assert_approx!(0., comparison.original_gravity.delta);
+        assert_approx!(0., comparison.bu_gu.delta);
+    }
+
+    #[test]
+    fn changed_ingredients() {
+        let mut batch_2 = batch_1();
+        batch_2.fermentables.remove(1);
+        batch_2.fermentables[0].weight_grams = 4500.;
+        // Same hop at another time is a changed addition
+        batch_2.hops[1].addition.time_mins = 5;
+
+        let comparison = compare(
+            &batch_1(),
+            &batch_2,
+            Gravity::from_specific_gravity(1.010),
+            Gravity::from_specific_gravity(1.008),
+        )
+        .unwrap();
+
+        let changes: Vec<_> = comparison
+            .ingredients
+            .iter()
+            .map(|i| (i.kind, i.name.as_str(), i.time_mins, i.change))
+            .collect();
+        assert_eq!(
+            vec![
+                (
+                    IngredientKind::Fermentable,
+                    "Pale Malt",
+                    None,
+                    Change::Changed
+                ),
+                (
+                    IngredientKind::Fermentable,
+                    "Crystal 40",
+                    None,
+                    Change::Removed
+                ),
+                (IngredientKind::Hop, "Citra", Some(10), Change::Changed),
+            ],
+            changes
+        );
+        assert_approx!(
+            500.,
+            comparison.ingredients[0].after - comparison.ingredients[0].before
+        );
+        assert_approx!(0., comparison.ingredients[1].after);
+        assert!(comparison.ingredients[0].attributes.is_empty());
+        assert_eq!(
+            vec![AttributeChange {
+                attribute: "time_mins".to_owned(),
+                before: "10".to_owned(),
+                after: "5".to_owned(),
+            }],
+            comparison.ingredients[2].attributes
+        );
+
+        assert!(comparison.original_gravity.delta > 0.);
+        assert!(comparison.color_srm.delta < 0.);
+        assert!(comparison.ibu.delta < 0.);
+        assert_approx!(-0.002, comparison.final_gravity.delta);
+        assert_approx!(
+            comparison.abv.after - comparison.abv.before,
+            comparison.abv.delta
+        );
+    }
+
+    #[test]
+    fn changed_hop_attributes() {
+        let mut batch_2 = batch_1();
+        batch_2.hops[0].addition.alpha_acid_percentage = 0.14;
+        batch_2.hops[1].addition.hop_type = HopAdditionType::Whole;
+        // A second addition of a hop already in the recipe
+        batch_2.hops.push(RecipeHop::new(
+            "Citra",
+            HopAddition::new(50., 0.12, 0, HopAdditionType::Pellet),
+        ));
+
+        let gravity = Gravity::from_specific_gravity(1.010);
+        let comparison = compare(&batch_1(), &batch_2, gravity, gravity).unwrap();
+
+        let changes: Vec<_> = comparison
+            .ingredients
+            .iter()
+            .map(|i| {
+                let attributes: Vec<_> = i
+                    .attributes
+                    .iter()
+                    .map(|a| (a.attribute.as_str(), a.before.as_str(), a.after.as_str()))
+                    .collect();
+                (i.name.as_str(), i.time_mins, i.change, attributes)
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                (
+                    "Magnum",
+                    Some(60),
+                    Change::Changed,
+                    vec![("alpha_acid_percentage", "0.12", "0.14")]
+                ),
+                (
+                    "Citra",
+                    Some(10),
+                    Change::Changed,
+                    vec![("hop_type", "Pellet", "Whole")]
+                ),
+                ("Citra", Some(0), Change::Added, vec![]),
+            ],
+            changes
+        );
+        assert_approx!(15., comparison.ingredients[0].after);
+        assert_approx!(0., comparison.ingredients[2].before);
+        assert_approx!(50., comparison.ingredients[2].after);
+    }
+
+    #[test]
+    fn repeated_hop_additions() {
+        let mut batch_1 = batch_1();
+        batch_1.hops.push(RecipeHop::new(
+            "Citra",
+            HopAddition::new(20., 0.12, 10, HopAdditionType::Pellet),
+        ));
+        // Only the second addition of Citra at 10 min changes
+        let mut batch_2 = batch_1.clone();
+        batch_2.hops[2].addition.alpha_acid_percentage = 0.14;
+        batch_2.hops[2].addition.hop_type = HopAdditionType::Whole;
+
+        let gravity = Gravity::from_specific_gravity(1.010);
+        let comparison = compare(&batch_1, &batch_2, gravity, gravity).unwrap();
+
+        assert_eq!(1, comparison.ingredients.len());
+        let citra = &comparison.ingredients[0];
+        assert_eq!(
+            ("Citra", Some(10), Change::Changed),
+            (citra.name.as_str(), citra.time_mins, citra.change)
+        );
+        assert_approx!(20., citra.before);
+        assert_approx!(20., citra.after);
+        let attributes: Vec<_> = citra
+            .attributes
+            .iter()
+            .map(|a| (a.attribute.as_str(), a.before.as_str(), a.after.as_str()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("alpha_acid_percentage", "0.12", "0.14"),
+                ("hop_type", "Pellet", "Whole")
+            ],
+            attributes
+        );
+    }
+
+    #[test]
+    fn invalid_comparison() {
+        let mut batch_2 = batch_1();
+        batch_2.fermentables.clear();
+        let gravity = Gravity::from_specific_gravity(1.);
+        assert!(matches!(
+            compare(&batch_1(), &batch_2, gravity, gravity),
+            Err(Error::NotPositive { .. })
+        ));
+        assert!(matches!(
+            compare(
+                &batch_1(),
+                &batch_1(),
+                Gravity::from_specific_gravity(1.010),
+                Gravity::from_specific_gravity(1.100)
+            ),
+            Err(Error::FinalAboveOriginalGravity { .. })
+        ));
+    }
+}
@@ -0,0 +1,494 @@
+//! Designs a recipe by solving grain and hop weights for
+//! a target original gravity, color and IBU
+//!
+//! Grains given as a percentage share that part of the grist weight
+//! and are weighed to hit the target gravity. Grains given by weight
+//! keep it, unless a target color is set: then they are scaled
+//! together, keeping their relative weights, to hit the color.
+//! Without grains given by weight the grain bill fixes the color,
+//! and a target color is only checked against it.
+//!
+//! Hops given as a percentage share that part of the IBU left after
+//! the hops given by weight, like
+//! [bittering_weight](../ibu/fn.bittering_weight.html) does for a
+//! single bittering hop.
+//! ```
+//! use rustybeer::calculators::design::{DesignGrain, DesignHop, RecipeDesign};
+//! use rustybeer_util::assert_approx;
+//!
+//! let design = RecipeDesign {
+//!     name: "Pale Ale".to_owned(),
+//!     batch_volume_liters: 20.,
+//!     efficiency: 0.75,
+//!     original_gravity: 1.052,
+//!     color_srm: None,
+//!     ibu: 35.,
+//!     grains: vec![
+//!         DesignGrain::percent("Pale Malt", 90., 1.037, 3.),
+//!         DesignGrain::percent("Crystal 40", 10., 1.034, 40.),
+//!     ],
+//!     hops: vec![DesignHop::percent("Centennial", 100., 0.085, 60)],
+//! };
+//! let recipe = design.solve().unwrap();
+//! assert_approx!(52., recipe.original_gravity().unwrap().as_points());
+//! assert_approx!(35., recipe.ibu().unwrap().as_ibu());
+//! ```
+
+use crate::calculators::ibu::{ibu, HopAddition, HopAdditionType};
+use crate::error::{self, Error, Result};
+use crate::recipe::{malt_color_units, morey, Fermentable, FermentableType, Recipe, RecipeHop};
+use crate::units::{Color, Gravity, Ibu};
+
+/// A color target this close to the color fixed by the grain bill is reached
+const COLOR_TOLERANCE_SRM: f64 = 0.05;
+
+/// Determinants this small relative to their terms are taken as zero
+const DETERMINANT_EPSILON: f64 = 1e-9;
+
+/// A grain of the design, given as a share of the grist or by weight
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DesignGrain {
+    pub name: String,
+    /// share of the grist weight in percent
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub percent: Option<f64>,
+    /// weight of a grain without a share (gm)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub weight_grams: Option<f64>,
+    /// extract potential as specific gravity of one pound in one US gallon, e.g. 1.037
+    pub potential: f64,
+    /// color of the fermentable (°L)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub color_lovibond: f64,
+    /// type of the fermentable. [default() = FermentableType::Grain]
+    #[cfg_attr(feature = "serde", serde(default, rename = "type"))]
+    pub fermentable_type: FermentableType,
+}
+
+impl DesignGrain {
+    /// Creates a mashed grain with a share of the grist
+    pub fn percent(name: &str, percent: f64, potential: f64, color_lovibond: f64) -> Self {
+        Self {
+            name: name.to_owned(),
+            percent: Some(percent),
+            weight_grams: None,
+            potential,
+            color_lovibond,
+            fermentable_type: FermentableType::Grain,
+        }
+    }
+
+    /// Creates a mashed grain with a weight (gm)
+    pub fn weight(name: &str, weight_grams: f64, potential: f64, color_lovibond: f64) -> Self {
+        Self {
+            percent: None,
+            weight_grams: Some(weight_grams),
+            ..Self::percent(name, 0., potential, color_lovibond)
+        }
+    }
+
+    fn fermentable(&self, weight_grams: f64) -> Fermentable {
+        Fermentable {
+            name: self.name.clone(),
+            weight_grams,
+            potential: self.potential,
+            color_lovibond: self.color_lovibond,
+            fermentable_type: self.fermentable_type,
+        }
+    }
+}
+
+/// A hop addition of the design, given as a share of the IBU or by weight
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DesignHop {
+    pub name: String,
+    /// share of the IBU in percent
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub ibu_percent: Option<f64>,
+    /// weight of a hop without a share (gm)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub weight_grams: Option<f64>,
+    /// AA% of the hop variety
+    pub alpha_acid_percentage: f64,
+    /// boil time (min)
+    pub time_mins: u32,
+    /// type of hop added: whole or pellets. [default() = HopAdditionType::Whole]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub hop_type: HopAdditionType,
+}
+
+impl DesignHop {
+    /// Creates a whole hop addition with a share of the IBU
+    pub fn percent(
+        name: &str,
+        ibu_percent: f64,
+        alpha_acid_percentage: f64,
+        time_mins: u32,
+    ) -> Self {
+        Self {
+            name: name.to_owned(),
+            ibu_percent: Some(ibu_percent),
+            weight_grams: None,
+            alpha_acid_percentage,
+            time_mins,
+            hop_type: HopAdditionType::Whole,
+        }
+    }
+
+    /// Creates a whole hop addition with a weight (gm)
+    pub fn weight(
+        name: &str,
+        weight_grams: f64,
+        alpha_acid_percentage: f64,
+        time_mins: u32,
+    ) -> Self {
+        Self {
+            ibu_percent: None,
+            weight_grams: Some(weight_grams),
+            ..Self::percent(name, 0., alpha_acid_percentage, time_mins)
+        }
+    }
+
+    fn recipe_hop(&self, weight_grams: f64) -> RecipeHop {
+        RecipeHop::new(
+            &self.name,
+            HopAddition::new(
+                weight_grams,
+                self.alpha_acid_percentage,
+                self.time_mins,
+                self.hop_type,
+            ),
+        )
+    }
+}
+
+/// Targets and ingredients of a recipe to design
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecipeDesign {
+    pub name: String,
+    /// volume of wort at the end of the boil (liters)
+    pub batch_volume_liters: f64,
+    /// brewhouse efficiency as a fraction, e.g. 0.72
+    pub efficiency: f64,
+    /// target original gravity
+    pub original_gravity: f64,
+    /// target color (SRM), solved with the grains given by weight
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub color_srm: Option<f64>,
+    /// target IBU
+    pub ibu: f64,
+    pub grains: Vec<DesignGrain>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub hops: Vec<DesignHop>,
+}
+
+impl RecipeDesign {
+    /// Solves the grain and hop weights of the design
+    pub fn solve(&self) -> Result<Recipe> {
+        design(
+            self,
+            Gravity::from_specific_gravity(self.original_gravity),
+            self.color_srm.map(Color::from_srm),
+            Ibu::new(self.ibu),
+        )
+    }
+}
+
+/// Sum of a property of the grains with a share, weighted by their share
+/// of the grist, and of the grains with a weight, weighted by their weight
+fn grain_sums(
+    grains: &[DesignGrain],
+    total_percent: f64,
+    property: impl Fn(&Fermentable) -> f64,
+) -> (f64, f64) {
+    grains.iter().fold((0., 0.), |(shared, weighed), grain| {
+        match (grain.percent, grain.weight_grams) {
+            (Some(percent), _) => (
+                shared + property(&grain.fermentable(percent / total_percent)),
+                weighed,
+            ),
+            (None, weight) => (
+                shared,
+                weighed + property(&grain.fermentable(weight.unwrap_or_default())),
+            ),
+        }
+    })
+}
+
+/// Solves the grain and hop weights of a design for a target
+/// original gravity, an optional target color and target IBU
+pub fn design(
+    recipe_design: &RecipeDesign,
+    original_gravity: Gravity,
+    color: Option<Color>,
+    target_ibu: Ibu,
+) -> Result<Recipe> {
+    let volume = error::positive("batch volume", recipe_design.batch_volume_liters)?;
+    let efficiency = error::positive("efficiency", recipe_design.efficiency)?;
+    error::in_range("efficiency", efficiency, 0., 1.)?;
+    error::non_negative("target IBU", target_ibu.as_ibu())?;
+    let grains = &recipe_design.grains;
+    for grain in grains {
+        match (grain.percent, grain.weight_grams) {
+            (Some(percent), _) => error::positive("grain percent", percent)?,
+            (None, weight) => error::non_negative("grain weight", weight.unwrap_or_default())?,
+        };
+    }
+
+    // Gravity points and malt color units in one litre are linear in the
+    // grist weight of the shared grains and the scale of the weighed ones
+    let total_percent: f64 = grains.iter().filter_map(|grain| grain.percent).sum();
+    let (shared_points, weighed_points) =
+        grain_sums(grains, total_percent, |f| f.gravity_points(efficiency));
+    let (shared_color, weighed_color) = grain_sums(grains, total_percent, Fermentable::color_units);
+    let points = original_gravity.as_points() * volume;
+
+    let unreachable_gravity = Error::Unreachable {
+        target: "original gravity",
+        value: original_gravity.as_specific_gravity(),
+    };
+    let fixed_grist = (points - weighed_points) / shared_points;
+    let (grist, scale) = match color {
+        None => (fixed_grist, 1.),
+        Some(color) => {
+            let color_units = malt_color_units(color) * volume;
+            let determinant = shared_points * weighed_color - weighed_points * shared_color;
+            let magnitude =
+                (shared_points * weighed_color).abs() + (weighed_points * shared_color).abs();
+            if determinant.abs() > DETERMINANT_EPSILON * magnitude {
+                (
+                    (points * weighed_color - weighed_points * color_units) / determinant,
+                    (shared_points * color_units - shared_color * points) / determinant,
+                )
+            } else {
+                // Without weighed grains, or with weighed grains of the same
+                // color per gravity point as the shared ones, the color is fixed
+                if !(fixed_grist.is_finite() && fixed_grist >= 0.) {
+                    return Err(unreachable_gravity);
+                }
+                let fixed_color = morey((shared_color * fixed_grist + weighed_color) / volume);
+                if (fixed_color.as_srm() - color.as_srm()).abs() > COLOR_TOLERANCE_SRM {
+                    return Err(if weighed_points == 0. && weighed_color == 0. {
+                        Error::MissingInput {
+                            target: "SRM",
+                            value: color.as_srm(),
+                            input: "a grain given by weight",
+                        }
+                    } else {
+                        Error::Unreachable {
+                            target: "SRM",
+                            value: color.as_srm(),
+                        }
+                    });
+                }
+                (fixed_grist, 1.)
+            }
+        }
+    };
+    if !(grist.is_finite() && grist >= 0.) {
+        return Err(unreachable_gravity);
+    }
+    if !(scale.is_finite() && scale >= 0.) {
+        return Err(Error::Unreachable {
+            target: "SRM",
+            value: color.map_or(0., |color| color.as_srm()),
+        });
+    }
+
+    let fermentables = grains
+        .iter()
+        .map(|grain| match grain.percent {
+            Some(percent) => grain.fermentable(grist * percent / total_percent),
+            None => grain.fermentable(grain.weight_grams.unwrap_or_default() * scale),
+        })
+        .collect();
+    let mut recipe = Recipe {
+        name: recipe_design.name.clone(),
+        batch_volume_liters: volume,
+        efficiency,
+        fermentables,
+        ..Default::default()
+    };
+
+    // IBU is linear in hop weight, so the IBU of one gram of each
+    // shared hop gives its weight
+    let hops = &recipe_design.hops;
+    let gravity = recipe.original_gravity()?;
+    let weighed_hops: Vec<HopAddition> = hops
+        .iter()
+        .filter(|hop| hop.ibu_percent.is_none())
+        .map(|hop| {
+            hop.recipe_hop(hop.weight_grams.unwrap_or_default())
+                .addition
+        })
+        .collect();
+    let shared_ibu =
+        target_ibu.as_ibu() - ibu(&weighed_hops, recipe.batch_volume(), gravity)?.as_ibu();
+    let total_ibu_percent: f64 = hops.iter().filter_map(|hop| hop.ibu_percent).sum();
+    let unreachable = Error::Unreachable {
+        target: "IBU",
+        value: target_ibu.as_ibu(),
+    };
+    if shared_ibu < 0. || (shared_ibu > 0. && total_ibu_percent <= 0.) {
+        return Err(unreachable);
+    }
+
+    for hop in hops {
+        let weight = match hop.ibu_percent {
+            Some(percent) => {
+                let addition = hop.recipe_hop(1.).addition;
+                let ibu_per_gram = ibu(&[addition], recipe.batch_volume(), gravity)?.as_ibu();
+                if ibu_per_gram <= 0. {
+                    return Err(unreachable);
+                }
+                shared_ibu * percent / total_ibu_percent / ibu_per_gram
+            }
+            None => hop.weight_grams.unwrap_or_default(),
+        };
+        recipe.hops.push(hop.recipe_hop(weight));
+    }
+
+    Ok(recipe)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::{DesignGrain, DesignHop, RecipeDesign};
+    use crate::calculators::ibu::ibu;
+    use crate::Error;
+    use rustybeer_util::assert_approx;
+
+    fn pale_ale() -> RecipeDesign {
+        RecipeDesign {
+            name: "Pale Ale".to_owned(),
+            batch_volume_liters: 20.,
+            efficiency: 0.72,
+            original_gravity: 1.052,
+            color_srm: None,
+            ibu: 35.,
+            grains: vec![
+                DesignGrain::percent("Pale Malt", 85., 1.037, 3.),
+                DesignGrain::percent("Munich", 10., 1.037, 9.),
+                DesignGrain::weight("Crystal 60", 300., 1.034, 60.),
+            ],
+            hops: vec![
+                DesignHop::percent("Magnum", 75., 0.12, 60),
+                DesignHop::percent("Cascade", 25., 0.06, 15),
+                DesignHop::weight("Cascade", 30., 0.06, 0),
+            ],
+        }
+    }
+
+    #[test]
+    fn solve_gravity_and_ibu() {
+        let recipe = pale_ale().solve().unwrap();
+        assert_approx!(52., recipe.original_gravity().unwrap().as_points());
+        assert_approx!(35., recipe.ibu().unwrap().as_ibu());
+
+        // Shares of the grist and weighed grains
+        let pale = recipe.fermentables[0].weight_grams;
+        let munich = recipe.fermentables[1].weight_grams;
+        assert_approx!(8.5, pale / munich);
+        assert_approx!(300., recipe.fermentables[2].weight_grams);
+
+        // Shares of the IBU, the flameout hop adds none
+        let gravity = recipe.original_gravity().unwrap();
+        let hop_ibu = |index: usize| {
+            ibu(
+                &[recipe.hops[index].addition],
+                recipe.batch_volume(),
+                gravity,
+            )
+            .unwrap()
+            .as_ibu()
+        };
+        assert_approx!(26.25, hop_ibu(0));
+        assert_approx!(8.75, hop_ibu(1));
+        assert_approx!(30., recipe.hops[2].addition.weight_grams);
+    }
+
+    #[test]
+    fn solve_color() {
+        let mut design = pale_ale();
+        design.color_srm = Some(12.);
+        let recipe = design.solve().unwrap();
+        assert_approx!(52., recipe.original_gravity().unwrap().as_points());
+        assert_approx!(12., recipe.color().unwrap().as_srm());
+        assert_approx!(
+            8.5,
+            recipe.fermentables[0].weight_grams / recipe.fermentables[1].weight_grams
+        );
+    }
+
+    #[test]
+    fn unreachable_targets() {
+        let mut design = pale_ale();
+        design.color_srm = Some(2.);
+        assert!(matches!(
+            design.solve(),
+            Err(Error::Unreachable { target: "SRM", .. })
+        ));
+
+        let mut design = pale_ale();
+        design.ibu = 1.;
+        design.hops[2].time_mins = 60;
+        assert!(matches!(
+            design.solve(),
+            Err(Error::Unreachable { target: "IBU", .. })
+        ));
+
+        let mut design = pale_ale();
+        design.original_gravity = 1.002;
+        assert!(matches!(
+            design.solve(),
+            Err(Error::Unreachable {
+                target: "original gravity",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn color_without_weighed_grain() {
+        let mut design = pale_ale();
+        design.grains[2] = DesignGrain::percent("Crystal 60", 5., 1.034, 60.);
+        let color = design.solve().unwrap().color().unwrap().as_srm();
+
+        // The grain bill fixes the color
+        design.color_srm = Some(color + 0.01);
+        let recipe = design.solve().unwrap();
+        assert_approx!(52., recipe.original_gravity().unwrap().as_points());
+        assert_approx!(color, recipe.color().unwrap().as_srm());
+
+        design.color_srm = Some(color + 2.);
+        assert!(matches!(
+            design.solve(),
+            Err(Error::MissingInput { target: "SRM", value, .. }) if value == color + 2.
+        ));
+    }
+
+    #[test]
+    fn color_with_weighed_grain_of_same_color() {
+        // Same color per gravity point as the shared grain, so
+        // scaling the weighed grain does not change the color
+        let mut design = pale_ale();
+        design.grains = vec![
+            DesignGrain::percent("Pale Malt", 100., 1.037, 3.),
+            DesignGrain::weight("Pale Malt", 1000., 1.037, 3.),
+        ];
+        let color = design.solve().unwrap().color().unwrap().as_srm();
+
+        design.color_srm = Some(color);
+        let recipe = design.solve().unwrap();
+        assert_approx!(1000., recipe.fermentables[1].weight_grams);
+
+        design.color_srm = Some(color + 2.);
+        assert!(matches!(
+            design.solve(),
+            Err(Error::Unreachable { target: "SRM", .. })
+        ));
+    }
+}
@@ -5,17 +5,67 @@
 //!
 //! // This displays how a target volume based off the
 //! // current volume, current gravity, and target gravity
-//! assert_eq!(17.28, calculate_new_volume(3.16, 50., 7.25));
+//! assert_eq!(Ok(17.28), calculate_new_volume(3.16, 50., 7.25));
 //! ```
 
+use crate::error::{self, Result};
+use crate::units::{Gravity, Volume};
+
+/// Calculates the new gravity based off a current gravity,
+/// a current volume of wort, and a target volume of wort
+pub fn new_gravity(
+    current_gravity: Gravity,
+    current_volume: Volume,
+    target_volume: Volume,
+) -> Result<Gravity> {
+    let current_volume = error::non_negative("current volume", current_volume.as_litres())?;
+    let target_volume = error::positive("target volume", target_volume.as_litres())?;
+    Ok(Gravity::from_specific_gravity(
+        (current_gravity.as_specific_gravity() - 1.0) * (current_volume / target_volume) + 1.0,
+    ))
+}
+
+/// Calculates the new volume based off a current
+/// gravity, a current volume, and a target gravity
+pub fn new_volume(
+    current_gravity: Gravity,
+    current_volume: Volume,
+    target_gravity: Gravity,
+) -> Result<Volume> {
+    let current_volume = error::non_negative("current volume", current_volume.as_litres())?;
+    error::positive("target gravity points", target_gravity.as_points())?;
+    Ok(Volume::from_litres(
+        current_volume * (current_gravity.as_specific_gravity() - 1.)
+            / (target_gravity.as_specific_gravity() - 1.),
+    ))
+}
+
 /// Calculates the new gravity based off a current gravity,
 /// a current volume of wort, and a target volume of wort
-pub fn calculate_new_gravity(current_gravity: f32, current_volume: f32, target_volume: f32) -> f32 {
-    (current_gravity - 1.0) * (current_volume / target_volume) + 1.0
+pub fn calculate_new_gravity(
+    current_gravity: f32,
+    current_volume: f32,
+    target_volume: f32,
+) -> Result<f32> {
+    Ok(new_gravity(
+        Gravity::from_specific_gravity(current_gravity.into()),
+        Volume::from_litres(current_volume.into()),
+        Volume::from_litres(target_volume.into()),
+    )?
+    .as_specific_gravity() as f32)
 }
 
 /// Calculates the new volume based off a current
 /// gravity, a current volume, and a target gravity
-pub fn calculate_new_volume(current_gravity: f32, current_volume: f32, target_gravity: f32) -> f32 {
-    current_volume * (current_gravity - 1.) / (target_gravity - 1.)
+pub fn calculate_new_volume(
+    current_gravity: f32,
+    current_volume: f32,
+    target_gravity: f32,
+) -> Result<f32> {
+    Ok(new_volume(
+        Gravity::from_specific_gravity(current_gravity.into()),
+        Volume::from_litres(current_volume.into()),
+        Gravity::from_specific_gravity(target_gravity.into()),
+    )?
+    .as_litres() as f32)
 }
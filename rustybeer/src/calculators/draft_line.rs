@@ -0,0 +1,93 @@
+//! A calculator used to balance a draught system
+//! by finding the beer line length that matches the
+//! serving pressure, so that beer pours without foaming
+//! ```
+//! use rustybeer::calculators::draft_line::calculate_line_length;
+//! use rustybeer_util::assert_approx;
+//!
+//! // Takes the arguments serving pressure (psi), line resistance
+//! // (psi/ft), vertical rise (ft) and flow rate (ml/s)
+//! assert_approx!(3.3333, calculate_line_length(12., 3., 2., 63.0901).unwrap());
+//! ```
+
+use crate::error::{self, Error, Result};
+
+/// Flow rate (ml/s) at which line resistances are given, 1 US gallon per minute
+pub static STANDARD_FLOW_RATE: f64 = 63.0901;
+
+/// Pressure (psi) lost for each foot of vertical rise
+static RISE_PRESSURE_PSI_PER_FOOT: f64 = 0.5;
+
+/// Pressure (psi) left at the faucet for a calm pour
+static FAUCET_PRESSURE_PSI: f64 = 1.0;
+
+/// A type of beer line tubing with a name and
+/// resistance in psi per foot at the
+/// [standard flow rate](static.STANDARD_FLOW_RATE.html)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BeerLine {
+    pub name: String,
+    pub resistance: f64,
+}
+
+impl BeerLine {
+    pub fn new(name: String, resistance: f64) -> Self {
+        Self { name, resistance }
+    }
+}
+
+// A function to store the common beer line types.
+// Can be extended as needed
+pub fn beer_lines() -> Vec<BeerLine> {
+    vec![
+        BeerLine::new("3/16 in vinyl".to_string(), 3.0),
+        BeerLine::new("1/4 in vinyl".to_string(), 0.85),
+        BeerLine::new("5/16 in vinyl".to_string(), 0.4),
+        BeerLine::new("3/8 in vinyl".to_string(), 0.2),
+        BeerLine::new("1/2 in vinyl".to_string(), 0.025),
+        BeerLine::new("1/4 in stainless".to_string(), 1.2),
+        BeerLine::new("5/16 in stainless".to_string(), 0.5),
+        BeerLine::new("3/8 in stainless".to_string(), 0.2),
+        BeerLine::new("3/8 in barrier".to_string(), 0.11),
+    ]
+}
+
+/// Calculates the resistance (psi/ft) of a beer line at the
+/// given flow rate (ml/s) from its resistance at the standard
+/// flow rate. Resistance grows with the square of the flow rate.
+pub fn calculate_resistance(resistance: f64, flow_rate: f64) -> f64 {
+    resistance * (flow_rate / STANDARD_FLOW_RATE).powf(2.0)
+}
+
+/// Calculates the beer line length (ft) needed to balance
+/// the serving pressure (psi) for a line resistance (psi/ft)
+/// at the standard flow rate, vertical rise (ft) from the keg
+/// to the faucet and the wanted flow rate (ml/s)
+///
+/// Fails when the serving pressure cannot push
+/// the beer up the given rise.
+pub fn calculate_line_length(
+    serving_psi: f64,
+    resistance: f64,
+    rise_feet: f64,
+    flow_rate: f64,
+) -> Result<f64> {
+    let resistance = error::positive("line resistance", resistance)?;
+    let flow_rate = error::positive("flow rate", flow_rate)?;
+    let line_psi = serving_psi - rise_feet * RISE_PRESSURE_PSI_PER_FOOT - FAUCET_PRESSURE_PSI;
+    if line_psi <= 0.0 {
+        return Err(Error::Unreachable {
+            target: "vertical rise (ft)",
+            value: rise_feet,
+        });
+    }
+
+    Ok(line_psi / calculate_resistance(resistance, flow_rate))
+}
+
+/// Calculates the time (s) it takes to pour
+/// the given volume (ml) at a flow rate (ml/s)
+pub fn calculate_pour_time(volume: f64, flow_rate: f64) -> Result<f64> {
+    Ok(volume / error::positive("flow rate", flow_rate)?)
+}
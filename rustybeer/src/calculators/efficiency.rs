@@ -0,0 +1,192 @@
+//! Efficiencies of a brewhouse from its grain bill and measured gravities and volumes
+//!
+//! * Conversion efficiency: share of the grain extract dissolved in the mash,
+//!   from the gravity of the first wort and the mash water volume
+//! * Mash efficiency: share of the grain extract collected in the kettle
+//! * Lauter efficiency: share of the dissolved extract collected in the kettle,
+//!   the mash efficiency divided by the conversion efficiency
+//! * Brewhouse efficiency: share of the grain extract in the wort at the end of
+//!   the boil. It is the `efficiency` of a [Recipe](../../recipe/struct.Recipe.html)
+//!   and can be used to predict the next batch.
+//!
+//! Sugars and extracts are taken to be added to the kettle, and to give
+//! all of their extract.
+//!
+//! See: <http://braukaiser.com/wiki/index.php/Understanding_Efficiency>
+//! ```
+//! use rustybeer::calculators::efficiency::calculate_brewhouse_efficiency;
+//! use rustybeer::recipe::Fermentable;
+//! use rustybeer_util::assert_approx;
+//!
+//! let grains = [Fermentable::grain("Pale Malt", 4500., 1.037, 3.)];
+//! assert_approx!(0.7197, calculate_brewhouse_efficiency(&grains, 1.050, 20.).unwrap());
+//! ```
+
+use crate::error::{self, Result};
+use crate::recipe::{Fermentable, FermentableType};
+use crate::units::{Gravity, Volume};
+
+/// Extract potential of sucrose, 100% extract (specific gravity points of 1 lb in 1 US gallon)
+const SUCROSE_POINTS: f64 = 46.214;
+
+/// Calculates the conversion efficiency from the first wort gravity and the mash water (liters)
+pub fn calculate_conversion_efficiency(
+    fermentables: &[Fermentable],
+    first_wort_gravity: f64,
+    mash_water_liters: f64,
+) -> Result<f64> {
+    conversion_efficiency(
+        fermentables,
+        Gravity::from_specific_gravity(first_wort_gravity),
+        Volume::from_litres(mash_water_liters),
+    )
+}
+
+/// Calculates the share of the grain extract dissolved in the mash
+/// from the gravity of the first wort and the volume of mash water
+pub fn conversion_efficiency(
+    fermentables: &[Fermentable],
+    first_wort_gravity: Gravity,
+    mash_water: Volume,
+) -> Result<f64> {
+    let water_kg = error::positive("mash water", mash_water.as_litres())?;
+    let plato = error::in_range(
+        "first wort gravity (°P)",
+        first_wort_gravity.as_plato(),
+        0.,
+        99.,
+    )?;
+    let potential_kg: f64 = grains(fermentables)
+        .map(|f| f.weight_grams / 1000. * (f.potential - 1.) * 1000. / SUCROSE_POINTS)
+        .sum();
+    let potential_kg = error::positive("grain extract potential", potential_kg)?;
+
+    // Extract dissolved in the water of the mash
+    Ok(water_kg * plato / (100. - plato) / potential_kg)
+}
+
+/// Calculates the mash efficiency from the pre-boil gravity and volume (liters)
+pub fn calculate_mash_efficiency(
+    fermentables: &[Fermentable],
+    pre_boil_gravity: f64,
+    pre_boil_volume_liters: f64,
+) -> Result<f64> {
+    mash_efficiency(
+        fermentables,
+        Gravity::from_specific_gravity(pre_boil_gravity),
+        Volume::from_litres(pre_boil_volume_liters),
+    )
+}
+
+/// Calculates the share of the grain extract collected in the kettle
+/// from the gravity and volume of the wort before sugars and extracts are added
+pub fn mash_efficiency(
+    fermentables: &[Fermentable],
+    pre_boil_gravity: Gravity,
+    pre_boil_volume: Volume,
+) -> Result<f64> {
+    let volume = error::positive("pre-boil volume", pre_boil_volume.as_litres())?;
+    Ok(pre_boil_gravity.as_points() * volume / grain_points(fermentables)?)
+}
+
+/// Calculates the lauter efficiency from the mash and conversion efficiencies
+pub fn lauter_efficiency(mash_efficiency: f64, conversion_efficiency: f64) -> Result<f64> {
+    let conversion = error::positive("conversion efficiency", conversion_efficiency)?;
+    Ok(error::non_negative("mash efficiency", mash_efficiency)? / conversion)
+}
+
+/// Calculates the brewhouse efficiency from the original gravity and post-boil volume (liters)
+pub fn calculate_brewhouse_efficiency(
+    fermentables: &[Fermentable],
+    original_gravity: f64,
+    post_boil_volume_liters: f64,
+) -> Result<f64> {
+    brewhouse_efficiency(
+        fermentables,
+        Gravity::from_specific_gravity(original_gravity),
+        Volume::from_litres(post_boil_volume_liters),
+    )
+}
+
+/// Calculates the share of the grain extract in the wort at the end of
+/// the boil from its original gravity and volume
+pub fn brewhouse_efficiency(
+    fermentables: &[Fermentable],
+    original_gravity: Gravity,
+    post_boil_volume: Volume,
+) -> Result<f64> {
+    let volume = error::positive("post-boil volume", post_boil_volume.as_litres())?;
+    let kettle_points: f64 = fermentables
+        .iter()
+        .filter(|f| f.fermentable_type != FermentableType::Grain)
+        .map(|f| f.gravity_points(1.))
+        .sum();
+    Ok((original_gravity.as_points() * volume - kettle_points) / grain_points(fermentables)?)
+}
+
+fn grains(fermentables: &[Fermentable]) -> impl Iterator<Item = &Fermentable> {
+    fermentables
+        .iter()
+        .filter(|f| f.fermentable_type == FermentableType::Grain)
+}
+
+/// Gravity points the grains give in one litre of wort at full efficiency
+fn grain_points(fermentables: &[Fermentable]) -> Result<f64> {
+    error::positive(
+        "grain extract potential",
+        grains(fermentables).map(|f| f.gravity_points(1.)).sum(),
+    )
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::{
+        calculate_brewhouse_efficiency, calculate_conversion_efficiency, calculate_mash_efficiency,
+        lauter_efficiency,
+    };
+    use crate::recipe::{Fermentable, FermentableType};
+    use crate::Error;
+    use rustybeer_util::assert_approx;
+
+    fn grain_bill() -> Vec<Fermentable> {
+        vec![
+            Fermentable::grain("Pilsner Malt", 4000., 1.037, 2.),
+            Fermentable {
+                name: "Dextrose".to_owned(),
+                weight_grams: 300.,
+                potential: 1.046,
+                color_lovibond: 0.,
+                fermentable_type: FermentableType::Sugar,
+            },
+        ]
+    }
+
+    #[test]
+    fn efficiencies() {
+        let conversion = calculate_conversion_efficiency(&grain_bill(), 1.075, 12.).unwrap();
+        assert_approx!(0.8336, conversion);
+        let mash = calculate_mash_efficiency(&grain_bill(), 1.040, 24.).unwrap();
+        assert_approx!(0.7773, mash);
+        assert_approx!(0.9324, lauter_efficiency(mash, conversion).unwrap());
+        assert_approx!(
+            0.7366,
+            calculate_brewhouse_efficiency(&grain_bill(), 1.050, 20.5).unwrap()
+        );
+    }
+
+    #[test]
+    fn invalid_efficiencies() {
+        assert!(matches!(
+            calculate_mash_efficiency(&grain_bill()[1..], 1.040, 24.),
+            Err(Error::NotPositive { .. })
+        ));
+        assert!(matches!(
+            calculate_conversion_efficiency(&grain_bill(), 1.075, 0.),
+            Err(Error::NotPositive { .. })
+        ));
+        assert!(matches!(
+            lauter_efficiency(0.7, 0.),
+            Err(Error::NotPositive { .. })
+        ));
+    }
+}
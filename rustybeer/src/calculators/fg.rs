@@ -3,9 +3,21 @@
 //! use rustybeer::calculators::fg::calculate_fg;
 //!
 //! // Takes the arguments original gravity and yeast attenuation
-//! assert_eq!(1.0125, calculate_fg(1.050, 75));
+//! assert_eq!(Ok(1.0125), calculate_fg(1.050, 75));
 //! ```
 
-pub fn calculate_fg(og: f32, att: u8) -> f32 {
-    og - (att as f32 / 100.0) * (og - 1.0)
+use crate::error::{self, Result};
+use crate::units::Gravity;
+
+/// Calculates final gravity from original gravity and yeast attenuation (%)
+pub fn fg(og: Gravity, att: u8) -> Result<Gravity> {
+    let att = error::in_range("attenuation (%)", att.into(), 0.0, 100.0)?;
+    let og = og.as_specific_gravity();
+    Ok(Gravity::from_specific_gravity(
+        og - (att / 100.0) * (og - 1.0),
+    ))
+}
+
+pub fn calculate_fg(og: f32, att: u8) -> Result<f32> {
+    Ok(fg(Gravity::from_specific_gravity(og.into()), att)?.as_specific_gravity() as f32)
 }
@@ -0,0 +1,98 @@
+//! A calculator used to determine the regulator
+//! pressure needed to force carbonate beer in a keg,
+//! and the CO2 volumes reached at a given pressure
+//! ```
+//! use rustybeer::calculators::forced_carbonation::calculate_pressure;
+//! use rustybeer_util::assert_approx;
+//!
+//! // Takes the arguments beer temperature (Fahrenheit)
+//! // and wanted volumes of CO2
+//! assert_approx!(11.2462, calculate_pressure(38., 2.5).unwrap());
+//! ```
+
+use crate::error::{self, Error, Result};
+use crate::units::{Length, Pressure, Temperature};
+
+/// Atmospheric pressure at sea level (psi)
+static SEA_LEVEL_PRESSURE_PSI: f64 = 14.6959;
+
+/// Altitude (m) where the barometric formula reaches zero pressure
+static MAX_ALTITUDE_METERS: f64 = 44_330.0;
+
+/// Calculates the gauge pressure that carbonates
+/// beer at a given temperature to the wanted
+/// volumes of CO2 at sea level
+pub fn pressure(temperature: Temperature, co2_volumes: f64) -> Result<Pressure> {
+    let co2_volumes = error::non_negative("CO2 volumes", co2_volumes)?;
+    let fahrenheit = temperature.as_fahrenheit();
+    Ok(Pressure::from_psi(
+        -16.6999 - 0.0101059 * fahrenheit
+            + 0.00116512 * fahrenheit.powf(2.0)
+            + 0.173354 * fahrenheit * co2_volumes
+            + 4.24267 * co2_volumes
+            - 0.0684226 * co2_volumes.powf(2.0),
+    ))
+}
+
+/// Calculates the volumes of CO2 reached when beer
+/// at a given temperature is held at a given gauge
+/// pressure at sea level
+///
+/// This is the inverse of [pressure](fn.pressure.html)
+pub fn co2_volumes(temperature: Temperature, pressure: Pressure) -> Result<f64> {
+    let fahrenheit = temperature.as_fahrenheit();
+    let a = -0.0684226;
+    let b = 0.173354 * fahrenheit + 4.24267;
+    let c =
+        -16.6999 - 0.0101059 * fahrenheit + 0.00116512 * fahrenheit.powf(2.0) - pressure.as_psi();
+
+    let discriminant = b.powf(2.0) - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return Err(Error::Unreachable {
+            target: "pressure (psi)",
+            value: pressure.as_psi(),
+        });
+    }
+
+    Ok((-b + discriminant.sqrt()) / (2.0 * a))
+}
+
+/// Calculates how much the regulator gauge pressure
+/// should be raised at a given altitude to compensate
+/// for the lower atmospheric pressure
+pub fn altitude_correction(altitude: Length) -> Result<Pressure> {
+    let altitude = error::in_range(
+        "altitude (m)",
+        altitude.as_meters(),
+        -MAX_ALTITUDE_METERS,
+        MAX_ALTITUDE_METERS,
+    )?;
+    let ambient_psi = SEA_LEVEL_PRESSURE_PSI * (1.0 - 2.25577e-5 * altitude).powf(5.25588);
+    Ok(Pressure::from_psi(SEA_LEVEL_PRESSURE_PSI - ambient_psi))
+}
+
+/// Calculates the gauge pressure (psi) that carbonates
+/// beer at a given temperature (Fahrenheit) to
+/// the wanted volumes of CO2 at sea level
+pub fn calculate_pressure(fahrenheit: f64, co2_volumes: f64) -> Result<f64> {
+    Ok(pressure(Temperature::from_fahrenheit(fahrenheit), co2_volumes)?.as_psi())
+}
+
+/// Calculates the volumes of CO2 reached when beer
+/// at a given temperature (Fahrenheit) is held at a
+/// given gauge pressure (psi) at sea level
+///
+/// This is the inverse of [calculate_pressure](fn.calculate_pressure.html)
+pub fn calculate_co2_volumes(fahrenheit: f64, psi: f64) -> Result<f64> {
+    co2_volumes(
+        Temperature::from_fahrenheit(fahrenheit),
+        Pressure::from_psi(psi),
+    )
+}
+
+/// Calculates how much (psi) the regulator gauge
+/// pressure should be raised at a given altitude (meters)
+/// to compensate for the lower atmospheric pressure
+pub fn calculate_altitude_correction(altitude_meters: f64) -> Result<f64> {
+    Ok(altitude_correction(Length::from_meters(altitude_meters))?.as_psi())
+}
@@ -0,0 +1,549 @@
+//! Generates a starting recipe for a beer style
+//!
+//! Malts, hops and a yeast are picked from the bundled lists and the
+//! grain and hop weights are solved with [design](../design/fn.design.html)
+//! to land in the middle of the OG, SRM and IBU ranges of the style.
+//! The yeast is picked for an attenuation that gives a final gravity
+//! in the middle of the range.
+//!
+//! Picks are made with a seeded pseudo-random generator, so the same
+//! seed always gives the same recipe.
+//! ```
+//! use rustybeer::calculators::generator::calculate_generated_recipe;
+//! use rustybeer_util::assert_approx;
+//! use rustybeer_util::beer_styles::find_style;
+//!
+//! let style = find_style("American Pale Ale").unwrap();
+//! let generated = calculate_generated_recipe(style, 20., 0.72, 7).unwrap();
+//! let recipe = generated.recipe;
+//! assert_approx!(1.0525, recipe.original_gravity().unwrap().as_specific_gravity());
+//! assert_approx!(40., recipe.ibu().unwrap().as_ibu());
+//! ```
+
+use crate::calculators::design::{DesignGrain, DesignHop, RecipeDesign};
+use crate::calculators::ibu::HopAdditionType;
+use crate::error::{Error, Result};
+use crate::recipe::{Recipe, RecipeYeast};
+use crate::units::{Gravity, Volume};
+use rustybeer_util::beer_styles::{BeerStyle, Parameter};
+use rustybeer_util::hops::{Hop, HOPS};
+use rustybeer_util::malts::{Malt, MaltKind, MALTS};
+use rustybeer_util::yeasts::{Yeast, YEASTS};
+
+/// Grain bills tried before falling back to base malts only
+const GRAIN_BILL_ATTEMPTS: usize = 16;
+
+/// Attenuation a yeast named like the style may miss by
+const ATTENUATION_TOLERANCE: f64 = 0.03;
+
+/// Yeast pitch rate (million cells / ml / °P) for ales and lagers
+const ALE_PITCH_RATE: f64 = 0.75;
+const LAGER_PITCH_RATE: f64 = 1.5;
+
+/// Words of yeasts with a distinct character, picked only
+/// for styles named with the same word
+const CHARACTER_WORDS: [&str; 6] = ["wheat", "weizen", "hefeweizen", "wit", "witbier", "saison"];
+
+/// Words too common in style and yeast names to tell them apart
+const COMMON_WORDS: [&str; 12] = [
+    "ale", "lager", "beer", "yeast", "style", "blend", "strong", "pale", "dark", "ii", "iii", "iv",
+];
+
+/// A generated recipe with the final gravity expected from its yeast
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GeneratedRecipe {
+    pub recipe: Recipe,
+    /// final gravity with the average attenuation of the yeast
+    pub final_gravity: Gravity,
+}
+
+/// A xorshift pseudo-random generator, enough for picking ingredients
+struct Picker(u64);
+
+impl Picker {
+    fn new(seed: u64) -> Self {
+        // xorshift gets stuck on zero, so the seed is spread first
+        Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A number between min and max
+    fn between(&mut self, min: f64, max: f64) -> f64 {
+        min + (max - min) * (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn pick<'a, T>(&mut self, items: &[&'a T]) -> Option<&'a T> {
+        match items.len() {
+            0 => None,
+            len => Some(items[(self.next() % len as u64) as usize]),
+        }
+    }
+}
+
+/// Generates a recipe of a style for a batch volume (liters) and
+/// brewhouse efficiency (fraction, e.g. 0.72) from a seed
+pub fn calculate_generated_recipe(
+    style: &BeerStyle,
+    batch_volume_liters: f64,
+    efficiency: f64,
+    seed: u64,
+) -> Result<GeneratedRecipe> {
+    generate(
+        style,
+        Volume::from_litres(batch_volume_liters),
+        efficiency,
+        seed,
+    )
+}
+
+/// Generates a recipe landing in the middle of the ranges of a style
+pub fn generate(
+    style: &BeerStyle,
+    batch_volume: Volume,
+    efficiency: f64,
+    seed: u64,
+) -> Result<GeneratedRecipe> {
+    let original_gravity = mid_range(style, Parameter::Og)?;
+    let final_gravity = mid_range(style, Parameter::Fg)?;
+    let color_srm = mid_range(style, Parameter::Srm)?;
+    let ibu = mid_range(style, Parameter::Ibu)?;
+    let mut picker = Picker::new(seed);
+
+    let lager = has_tag(style, "bottom-fermented");
+    let attenuation = (original_gravity - final_gravity) / (original_gravity - 1.);
+    let (yeast, attenuation) = pick_yeast(style, lager, attenuation, &mut picker)
+        .ok_or_else(|| Error::Data(format!("no yeast found for {}", style.name)))?;
+    let hops = hop_schedule(style, ibu, batch_volume.as_litres(), &mut picker)
+        .ok_or_else(|| Error::Data(format!("no hops found for {}", style.name)))?;
+
+    let mut design = RecipeDesign {
+        name: style.name.clone(),
+        batch_volume_liters: batch_volume.as_litres(),
+        efficiency,
+        original_gravity,
+        color_srm: Some(color_srm),
+        ibu,
+        grains: vec![],
+        hops,
+    };
+    // The bill closest to the recommended shares of its malts is kept
+    let mut best: Option<(f64, Recipe)> = None;
+    for _ in 0..GRAIN_BILL_ATTEMPTS {
+        let malts = grain_bill(style, color_srm, &mut picker)?;
+        design.grains = malts.iter().map(|(grain, _)| grain.clone()).collect();
+        if let Ok(solved) = design.solve() {
+            let excess = max_percent_excess(&solved, &malts);
+            let better = match &best {
+                Some((best_excess, _)) => excess < *best_excess,
+                None => true,
+            };
+            if better {
+                best = Some((excess, solved));
+            }
+            if excess <= 0. {
+                break;
+            }
+        }
+    }
+    let mut recipe = match best {
+        Some((_, recipe)) => recipe,
+        None => {
+            // Paler than the base malts alone: the color
+            // is left to the base malts
+            design.color_srm = None;
+            design.grains = base_malts(style, color_srm, &mut picker)?
+                .into_iter()
+                .map(|(grain, _)| grain)
+                .collect();
+            design.solve()?
+        }
+    };
+
+    let pitch_rate = if lager {
+        LAGER_PITCH_RATE
+    } else {
+        ALE_PITCH_RATE
+    };
+    let plato = Gravity::from_specific_gravity(original_gravity).as_plato();
+    recipe.yeasts.push(RecipeYeast {
+        name: yeast_name(yeast),
+        cells_billions: pitch_rate * batch_volume.as_litres() * plato,
+    });
+
+    Ok(GeneratedRecipe {
+        recipe,
+        final_gravity: Gravity::from_specific_gravity(
+            1. + (original_gravity - 1.) * (1. - attenuation),
+        ),
+    })
+}
+
+fn mid_range(style: &BeerStyle, parameter: Parameter) -> Result<f64> {
+    parameter
+        .range(style)
+        .map(|(min, max)| f64::from(min + max) / 2.)
+        .ok_or_else(|| Error::Data(format!("{} has no {} range", style.name, parameter)))
+}
+
+fn has_tag(style: &BeerStyle, tag: &str) -> bool {
+    style.tags.iter().any(|t| t == tag)
+}
+
+/// Lowercase words of a name that tell it apart
+fn words(name: &str) -> Vec<String> {
+    name.split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|word| !word.is_empty() && !COMMON_WORDS.contains(&word.as_str()))
+        .collect()
+}
+
+/// Picks a yeast of the fermentation type of the style with an attenuation
+/// range around the given one, preferring yeasts named like the style
+fn pick_yeast(
+    style: &BeerStyle,
+    lager: bool,
+    attenuation: f64,
+    picker: &mut Picker,
+) -> Option<(&'static Yeast, f64)> {
+    let style_words = [words(&style.name), words(&style.category)].concat();
+    let shared_words = |yeast: &Yeast| {
+        words(&yeast.name)
+            .iter()
+            .filter(|word| style_words.contains(word))
+            .count()
+    };
+    let candidates: Vec<&Yeast> = YEASTS
+        .iter()
+        .filter(|yeast| yeast.min_attenuation.is_some() && yeast.max_attenuation.is_some())
+        .filter(|yeast| match yeast.max_temp {
+            Some(max_temp) => (max_temp.as_fahrenheit() <= 60.) == lager,
+            None => false,
+        })
+        .filter(|yeast| {
+            words(&yeast.name)
+                .iter()
+                .all(|word| !CHARACTER_WORDS.contains(&word.as_str()) || style_words.contains(word))
+        })
+        .collect();
+
+    let range = |yeast: &Yeast| {
+        (
+            f64::from(yeast.min_attenuation.unwrap_or_default()) / 100.,
+            f64::from(yeast.max_attenuation.unwrap_or_default()) / 100.,
+        )
+    };
+    let miss = |yeast: &Yeast| {
+        let (min, max) = range(yeast);
+        (min - attenuation).max(attenuation - max).max(0.)
+    };
+    let least_miss = candidates
+        .iter()
+        .map(|&yeast| miss(yeast))
+        .min_by(f64::total_cmp)?;
+    // A yeast named like the style may miss the attenuation by a little
+    let fitting: Vec<&Yeast> = candidates
+        .into_iter()
+        .filter(|&yeast| miss(yeast) <= least_miss + ATTENUATION_TOLERANCE)
+        .collect();
+    let most_shared = fitting.iter().map(|&yeast| shared_words(yeast)).max()?;
+    let fitting: Vec<&Yeast> = fitting
+        .into_iter()
+        .filter(|&yeast| shared_words(yeast) == most_shared)
+        .collect();
+
+    let yeast = picker.pick(&fitting)?;
+    let (min, max) = range(yeast);
+    Some((yeast, attenuation.clamp(min, max)))
+}
+
+fn yeast_name(yeast: &Yeast) -> String {
+    match &yeast.id {
+        Some(id) if !id.is_empty() => format!("{} {} ({})", yeast.company, yeast.name, id),
+        _ => format!("{} {}", yeast.company, yeast.name),
+    }
+}
+
+/// Countries of the hops usually used in the region of a style
+fn hop_countries(style: &BeerStyle) -> Vec<&'static str> {
+    style
+        .tags
+        .iter()
+        .flat_map(|tag| match tag.as_str() {
+            "north-america" => vec!["US"],
+            "british-isles" => vec!["UK"],
+            "central-europe" => vec!["Germany", "Czech", "Czechoslovakia", "Austria", "Slovenia"],
+            "eastern-europe" => vec!["Czech", "Czechoslovakia", "Slovenia"],
+            "western-europe" => vec!["Belgium", "France", "Germany"],
+            "pacific" => vec!["New Zealand", "Australia", "Japan"],
+            _ => vec![],
+        })
+        .collect()
+}
+
+fn pick_hop(hops: &[&'static Hop], purpose: &str, picker: &mut Picker) -> Option<&'static Hop> {
+    let for_purpose: Vec<&Hop> = hops
+        .iter()
+        .copied()
+        .filter(|hop| hop.purpose.iter().any(|p| p == purpose))
+        .collect();
+    picker.pick(&for_purpose)
+}
+
+fn design_hop(hop: &Hop, ibu_percent: Option<f64>, weight_grams: f64, time_mins: u32) -> DesignHop {
+    let alpha_acid = hop.average_alpha_acid();
+    let mut design_hop = match ibu_percent {
+        Some(percent) => DesignHop::percent(&hop.name, percent, alpha_acid, time_mins),
+        None => DesignHop::weight(&hop.name, weight_grams, alpha_acid, time_mins),
+    };
+    design_hop.hop_type = HopAdditionType::Pellet;
+    design_hop
+}
+
+/// A bittering addition, with flavor and aroma additions
+/// for the bitter and hoppy styles
+fn hop_schedule(
+    style: &BeerStyle,
+    ibu: f64,
+    batch_volume_liters: f64,
+    picker: &mut Picker,
+) -> Option<Vec<DesignHop>> {
+    let countries = hop_countries(style);
+    let regional: Vec<&Hop> = HOPS
+        .iter()
+        .filter(|hop| countries.contains(&hop.country.as_str()))
+        .collect();
+    let all: Vec<&Hop> = HOPS.iter().collect();
+    let mut pick =
+        |purpose| pick_hop(&regional, purpose, picker).or_else(|| pick_hop(&all, purpose, picker));
+
+    let bittering = pick("Bittering")?;
+    let schedule = if has_tag(style, "hoppy") {
+        let aroma = pick("Aroma")?;
+        vec![
+            design_hop(bittering, Some(70.), 0., 60),
+            design_hop(aroma, Some(30.), 0., 10),
+            // Flameout hops give aroma, not bitterness
+            design_hop(aroma, None, 2. * batch_volume_liters, 0),
+        ]
+    } else if ibu >= 20. {
+        let aroma = pick("Aroma")?;
+        vec![
+            design_hop(bittering, Some(85.), 0., 60),
+            design_hop(aroma, Some(15.), 0., 15),
+        ]
+    } else {
+        vec![design_hop(bittering, Some(100.), 0., 60)]
+    };
+    Some(schedule)
+}
+
+fn design_grain(malt: &Malt, percent: Option<f64>, weight_grams: f64) -> DesignGrain {
+    match percent {
+        Some(percent) => {
+            DesignGrain::percent(&malt.name, percent, malt.potential, malt.color_lovibond)
+        }
+        None => DesignGrain::weight(
+            &malt.name,
+            weight_grams,
+            malt.potential,
+            malt.color_lovibond,
+        ),
+    }
+}
+
+fn malts_of(kinds: &[MaltKind], max_lovibond: f64) -> Vec<&'static Malt> {
+    MALTS
+        .iter()
+        .filter(|malt| kinds.contains(&malt.kind) && malt.color_lovibond <= max_lovibond)
+        .collect()
+}
+
+/// A base malt paler than the style, with wheat for wheat beers
+fn base_malts(
+    style: &BeerStyle,
+    color_srm: f64,
+    picker: &mut Picker,
+) -> Result<Vec<(DesignGrain, &'static Malt)>> {
+    let lightest = MALTS
+        .iter()
+        .filter(|malt| malt.kind == MaltKind::Base)
+        .min_by(|a, b| a.color_lovibond.total_cmp(&b.color_lovibond))
+        .ok_or_else(|| Error::Data("malts data has no base malts".to_owned()))?;
+    // The base malt is most of the grist and darkens the wort
+    // by about twice its own color
+    let base = picker
+        .pick(&malts_of(&[MaltKind::Base], color_srm / 2.))
+        .unwrap_or(lightest);
+
+    let malts = match picker.pick(&malts_of(&[MaltKind::Wheat], color_srm.max(2.))) {
+        Some(wheat) if has_tag(style, "wheat-beer-family") => {
+            let wheat_percent = f64::from(wheat.max_percent).min(50.);
+            vec![
+                (design_grain(base, Some(100. - wheat_percent), 0.), base),
+                (design_grain(wheat, Some(wheat_percent), 0.), wheat),
+            ]
+        }
+        _ => vec![(design_grain(base, Some(100.), 0.), base)],
+    };
+    Ok(malts)
+}
+
+/// Base malts with specialty malts weighed to solve the color:
+/// caramel or kilned malts, and a roasted malt for dark styles
+fn grain_bill(
+    style: &BeerStyle,
+    color_srm: f64,
+    picker: &mut Picker,
+) -> Result<Vec<(DesignGrain, &'static Malt)>> {
+    let mut bill = base_malts(style, color_srm, picker)?;
+    let mut specialty = vec![];
+    let mut roasted = malts_of(&[MaltKind::Roasted], f64::MAX);
+    let roasted_count = if color_srm >= 30. {
+        2
+    } else if color_srm >= 20. || has_tag(style, "roasty") {
+        1
+    } else {
+        0
+    };
+    for _ in 0..roasted_count {
+        if let Some(malt) = picker.pick(&roasted) {
+            roasted.retain(|&other| other.name != malt.name);
+            specialty.push(malt);
+        }
+    }
+    let mut pool = malts_of(
+        &[MaltKind::Caramel, MaltKind::Kilned],
+        (8. * color_srm).max(20.),
+    );
+    let count = if color_srm >= 10. && specialty.is_empty() {
+        2
+    } else {
+        1
+    };
+    for _ in 0..count {
+        if let Some(malt) = picker.pick(&pool) {
+            pool.retain(|&other| other.name != malt.name);
+            specialty.push(malt);
+        }
+    }
+
+    for malt in specialty {
+        // Relative weights only, the design scales them to the color
+        let weight = f64::from(malt.max_percent) * picker.between(0.6, 1.);
+        bill.push((design_grain(malt, None, weight), malt));
+    }
+    Ok(bill)
+}
+
+/// How far, in percent of the grist, the solved malts go
+/// above their recommended share
+fn max_percent_excess(recipe: &Recipe, malts: &[(DesignGrain, &Malt)]) -> f64 {
+    let total: f64 = recipe.fermentables.iter().map(|f| f.weight_grams).sum();
+    recipe
+        .fermentables
+        .iter()
+        .zip(malts)
+        .map(|(fermentable, (_, malt))| {
+            fermentable.weight_grams / total * 100. - f64::from(malt.max_percent)
+        })
+        .fold(f64::MIN, f64::max)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::calculate_generated_recipe;
+    use crate::Error;
+    use rustybeer_util::assert_approx;
+    use rustybeer_util::beer_styles::{find_style, BEER_STYLES};
+    use rustybeer_util::malts::{MaltKind, MALTS};
+
+    #[test]
+    fn generated_in_mid_range() {
+        let style = find_style("Irish Stout").unwrap();
+        let generated = calculate_generated_recipe(style, 20., 0.72, 1).unwrap();
+        let recipe = &generated.recipe;
+
+        assert_approx!(
+            1.04,
+            recipe.original_gravity().unwrap().as_specific_gravity()
+        );
+        assert_approx!(35., recipe.color().unwrap().as_srm());
+        assert_approx!(35., recipe.ibu().unwrap().as_ibu());
+        assert_approx!(1.01, generated.final_gravity.as_specific_gravity());
+        assert!(recipe.yeasts[0].name.contains("Irish Ale"));
+    }
+
+    #[test]
+    fn same_seed_same_recipe() {
+        let style = find_style("Weissbier").unwrap();
+        let names = |seed| {
+            let recipe = calculate_generated_recipe(style, 20., 0.72, seed)
+                .unwrap()
+                .recipe;
+            let mut names: Vec<String> = recipe.fermentables.into_iter().map(|f| f.name).collect();
+            names.extend(recipe.hops.into_iter().map(|h| h.name));
+            names.extend(recipe.yeasts.into_iter().map(|y| y.name));
+            names
+        };
+
+        assert_eq!(names(3), names(3));
+        assert!(names(3).iter().any(|name| name.contains("Wheat")));
+    }
+
+    #[test]
+    fn every_style_in_range() {
+        let base_only = |names: Vec<&String>| {
+            names.into_iter().all(|name| {
+                MALTS.iter().any(|malt| {
+                    &malt.name == name && matches!(malt.kind, MaltKind::Base | MaltKind::Wheat)
+                })
+            })
+        };
+
+        for style in BEER_STYLES.iter().filter(|s| s.color_srm_min.is_some()) {
+            for seed in 0..4 {
+                let recipe = calculate_generated_recipe(style, 20., 0.72, seed)
+                    .unwrap()
+                    .recipe;
+                let og = recipe.original_gravity().unwrap().as_specific_gravity() as f32;
+                let srm = recipe.color().unwrap().as_srm() as f32;
+                let ibu = recipe.ibu().unwrap().as_ibu() as f32;
+
+                assert_approx!(
+                    (style.original_gravity_min.unwrap() + style.original_gravity_max.unwrap())
+                        / 2.,
+                    og
+                );
+                assert_approx!(
+                    f32::from(style.ibu_min.unwrap() + style.ibu_max.unwrap()) / 2.,
+                    ibu
+                );
+                // Only base malts are paler than the palest styles allow
+                assert!(
+                    style.color_srm_min.unwrap() <= srm
+                        && (srm <= style.color_srm_max.unwrap()
+                            || base_only(recipe.fermentables.iter().map(|f| &f.name).collect())),
+                    "{} SRM {}",
+                    style.name,
+                    srm
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn style_without_ranges() {
+        let style = find_style("28A").unwrap();
+        assert!(matches!(
+            calculate_generated_recipe(style, 20., 0.72, 1),
+            Err(Error::Data(_))
+        ));
+    }
+}
@@ -1,14 +1,17 @@
-/// A module for calculating IBU using Tinseth Formula:
-///
-/// IBUs = decimal alpha acid utilization * mg/l of added alpha acids
-///
-///
-/// See:
-/// https://www.realbeer.com/hops/research.html
-/// http://www.backtoschoolbrewing.com/blog/2016/9/5/how-to-calculate-ibus
-/// https://straighttothepint.com/ibu-calculator/
-/// https://www.brewersfriend.com/2010/02/27/hops-alpha-acid-table-2009/
-///
+//! A module for calculating IBU using Tinseth Formula:
+//!
+//! IBUs = decimal alpha acid utilization * mg/l of added alpha acids
+//!
+//!
+//! See:
+//! https://www.realbeer.com/hops/research.html
+//! http://www.backtoschoolbrewing.com/blog/2016/9/5/how-to-calculate-ibus
+//! https://straighttothepint.com/ibu-calculator/
+//! https://www.brewersfriend.com/2010/02/27/hops-alpha-acid-table-2009/
+//!
+
+use crate::error::{self, Error, Result};
+use crate::units::{Gravity, Ibu, Mass, Volume};
 
 /// Internal function to calculate Aplha Acid Utilization (Tinseth formula)
 /// given Boil Time and Wort Original Gravity
@@ -49,9 +52,11 @@ fn _calculate_ibu_single_hop(
 }
 
 /// An enum of hop types
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HopAdditionType {
     /// Whole, default
+    #[default]
     Whole,
     // Plugs, same utilization as whole hops
     Plug,
@@ -59,12 +64,6 @@ pub enum HopAdditionType {
     Pellet,
 }
 
-impl Default for HopAdditionType {
-    fn default() -> Self {
-        HopAdditionType::Whole
-    }
-}
-
 /// A representation of one hop addition
 ///
 /// Example:
@@ -80,7 +79,7 @@ impl Default for HopAdditionType {
 ///```
 ///
 #[derive(Debug, Copy, Clone)]
-// TODO: YAML/JSON serialization
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HopAddition {
     /// the weight of the hop addition (gm)
     pub weight_grams: f64,
@@ -89,6 +88,7 @@ pub struct HopAddition {
     /// boil time (min)
     pub time_mins: u32,
     /// type of hop added: whole or pellets. [default() = HopAdditionType::Whole]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub hop_type: HopAdditionType,
 }
 
@@ -108,9 +108,6 @@ impl HopAddition {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct NegativeIbuError;
-
 /// Calculates IBU contributed by hop additions
 ///
 /// # Arguments
@@ -128,30 +125,76 @@ pub struct NegativeIbuError;
 /// ```
 /// use rustybeer::calculators::ibu::{HopAddition, calculate_ibu};
 /// use rustybeer_util::assert_approx;
-/// assert_approx!(18.9723, calculate_ibu(vec![HopAddition::new(28.0, 0.064, 45, Default::default())], 20.0, 1.050));
+/// assert_approx!(18.9723, calculate_ibu(vec![HopAddition::new(28.0, 0.064, 45, Default::default())], 20.0, 1.050).unwrap());
 /// ```
 ///
 pub fn calculate_ibu(
     hop_additions: Vec<HopAddition>,
     finished_volume_liters: f64,
     gravity_boil: f64,
-) -> f64 {
-    hop_additions
-        .into_iter()
-        .map(|h| {
-            _calculate_ibu_single_hop(
-                h.weight_grams,
-                h.alpha_acid_percentage,
-                h.time_mins,
-                finished_volume_liters,
-                gravity_boil,
-                match h.hop_type {
-                    HopAdditionType::Whole | HopAdditionType::Plug => 1.,
-                    HopAdditionType::Pellet => 1.1,
-                },
-            )
-        })
-        .sum()
+) -> Result<f64> {
+    Ok(ibu(
+        &hop_additions,
+        Volume::from_litres(finished_volume_liters),
+        Gravity::from_specific_gravity(gravity_boil),
+    )?
+    .as_ibu())
+}
+
+/// Calculates IBU contributed by hop additions
+/// to the finished volume of wort with the given
+/// original gravity
+pub fn ibu(
+    hop_additions: &[HopAddition],
+    finished_volume: Volume,
+    gravity_boil: Gravity,
+) -> Result<Ibu> {
+    let finished_volume = error::positive("finished volume", finished_volume.as_litres())?;
+    Ok(Ibu::new(
+        hop_additions
+            .iter()
+            .map(|h| {
+                _calculate_ibu_single_hop(
+                    h.weight_grams,
+                    h.alpha_acid_percentage,
+                    h.time_mins,
+                    finished_volume,
+                    gravity_boil.as_specific_gravity(),
+                    match h.hop_type {
+                        HopAdditionType::Whole | HopAdditionType::Plug => 1.,
+                        HopAdditionType::Pellet => 1.1,
+                    },
+                )
+            })
+            .sum(),
+    ))
+}
+
+/// Calculates the BU:GU ratio, the bitterness balance of a beer
+/// as IBU divided by the original gravity points
+///
+/// # Examples
+///
+/// * IBU: 40
+/// * Original Gravity: 1.050
+/// ```
+/// use rustybeer::calculators::ibu::calculate_bu_gu;
+/// use rustybeer_util::assert_approx;
+///
+/// assert_approx!(0.8, calculate_bu_gu(40., 1.050).unwrap());
+/// ```
+pub fn calculate_bu_gu(ibu: f64, original_gravity: f64) -> Result<f64> {
+    bu_gu(
+        Ibu::new(ibu),
+        Gravity::from_specific_gravity(original_gravity),
+    )
+}
+
+/// Calculates the BU:GU ratio of a beer with the given
+/// bitterness and original gravity
+pub fn bu_gu(ibu: Ibu, original_gravity: Gravity) -> Result<f64> {
+    let points = error::positive("original gravity points", original_gravity.as_points())?;
+    Ok(ibu.as_ibu() / points)
 }
 
 /// Calculates the needed amount of bittering hop to reach a target IBU for given variety alpha
@@ -184,7 +227,7 @@ pub fn calculate_ibu(
 /// With addition of 20gm of Centennial (8.5% AA) for 60min boil,
 /// can't get IBU down to just 10
 ///
-/// ```{.should_panic}
+/// ```should_panic
 /// use rustybeer::calculators::ibu::calculate_bittering_weight;
 /// use rustybeer::calculators::ibu::HopAddition;
 /// let bittering = calculate_bittering_weight(Some(vec![
@@ -205,33 +248,59 @@ pub fn calculate_bittering_weight(
     finished_volume_liters: f64,
     gravity_boil: f64,
     target_ibu: f64,
-) -> Result<f64, NegativeIbuError> {
+) -> Result<f64> {
+    bittering_weight(
+        hop_additions.as_deref(),
+        bittering_alpha_acid_percentage,
+        bittering_time_mins,
+        Volume::from_litres(finished_volume_liters),
+        Gravity::from_specific_gravity(gravity_boil),
+        Ibu::new(target_ibu),
+    )
+    .map(|weight| weight.as_grams())
+}
+
+/// Calculates the needed weight of bittering hop to reach a target IBU
+/// for given variety alpha acid percentage and boil time of the hop
+pub fn bittering_weight(
+    hop_additions: Option<&[HopAddition]>,
+    bittering_alpha_acid_percentage: f64,
+    bittering_time_mins: Option<u32>,
+    finished_volume: Volume,
+    gravity_boil: Gravity,
+    target_ibu: Ibu,
+) -> Result<Mass> {
+    error::positive("finished volume", finished_volume.as_litres())?;
+    error::positive("bittering alpha acid", bittering_alpha_acid_percentage)?;
     let bittering_ibu = match hop_additions {
-        Some(h) => target_ibu - calculate_ibu(h, finished_volume_liters, gravity_boil),
-        None => target_ibu,
+        Some(h) => target_ibu.as_ibu() - ibu(h, finished_volume, gravity_boil)?.as_ibu(),
+        None => target_ibu.as_ibu(),
     };
 
     match bittering_ibu.is_sign_positive() {
         true => {
             let bittering_time = bittering_time_mins.unwrap_or(60);
             let bittering_alpha_acid_utilization =
-                _calculate_utilization(gravity_boil, bittering_time);
+                _calculate_utilization(gravity_boil.as_specific_gravity(), bittering_time);
 
-            let bittering_weight = (bittering_ibu * finished_volume_liters)
+            let bittering_weight = (bittering_ibu * finished_volume.as_litres())
                 / (bittering_alpha_acid_utilization * bittering_alpha_acid_percentage)
                 / 1000.0;
 
-            Ok(bittering_weight)
+            Ok(Mass::from_grams(bittering_weight))
         }
-        false => Err(NegativeIbuError),
+        false => Err(Error::Unreachable {
+            target: "IBU",
+            value: target_ibu.as_ibu(),
+        }),
     }
 }
 
 #[cfg(test)]
 pub mod tests {
     use super::{
-        calculate_bittering_weight, calculate_ibu, HopAddition, HopAdditionType, NegativeIbuError,
-        _calculate_ibu_single_hop, _calculate_utilization,
+        _calculate_ibu_single_hop, _calculate_utilization, calculate_bittering_weight,
+        calculate_bu_gu, calculate_ibu, HopAddition, HopAdditionType,
     };
     use rustybeer_util::assert_approx;
 
@@ -270,7 +339,8 @@ pub mod tests {
                 ],
                 22.0,
                 1.058
-            ),
+            )
+            .unwrap(),
         );
     }
 
@@ -286,7 +356,8 @@ pub mod tests {
                 ],
                 22.0,
                 1.058
-            ),
+            )
+            .unwrap(),
         );
     }
 
@@ -310,7 +381,7 @@ pub mod tests {
     }
 
     #[test]
-    fn bitter_hops_weight() -> Result<(), NegativeIbuError> {
+    fn bitter_hops_weight() -> Result<(), crate::Error> {
         assert_approx!(
             13.2611,
             calculate_bittering_weight(
@@ -330,6 +401,15 @@ pub mod tests {
 
     #[test]
     fn zero_hops_ibu() {
-        assert_approx!(0., calculate_ibu(vec![], 22.0, 1.058));
+        assert_approx!(0., calculate_ibu(vec![], 22.0, 1.058).unwrap());
+    }
+
+    #[test]
+    fn bu_gu_ratio() {
+        assert_approx!(0.6923, calculate_bu_gu(45., 1.065).unwrap());
+        assert!(matches!(
+            calculate_bu_gu(20., 1.),
+            Err(crate::Error::NotPositive { .. })
+        ));
     }
 }
@@ -1,12 +1,20 @@
 pub mod abv;
 pub mod alcohol_volume_weight;
 pub mod calorie_counter;
+pub mod comparison;
+pub mod design;
 pub mod diluting;
+pub mod draft_line;
+pub mod efficiency;
 pub mod fg;
+pub mod forced_carbonation;
+pub mod generator;
 pub mod ibu;
 pub mod num_bottles;
 pub mod priming;
+pub mod scaling;
 pub mod sg_correction;
+pub mod timeline;
 pub mod yeast_viability;
 
 #[cfg(test)]
@@ -1,36 +1,134 @@
-// A function to store the different bottle types.
-// Can be extended as needed
-pub fn bottles() -> Vec<(String, f64)> {
-    vec![
-        ("330ml bottle".to_string(), 330.0),
-        ("Twelve ounce bottle".to_string(), 355.0),
-        ("Groish bottle".to_string(), 450.0),
-        ("Half liter bottle".to_string(), 500.0),
-        ("Pint".to_string(), 568.0),
-        ("Graft bottle".to_string(), 650.0),
-        ("Wine bottle".to_string(), 750.0),
-        ("Grenade jug".to_string(), 946.0),
-        ("Growler jug".to_string(), 1893.0),
-        ("Gallon jug".to_string(), 3785.0),
-        ("5 liter mini keg".to_string(), 5000.0),
-        ("50 liter keg".to_string(), 50_000.0),
-    ]
-}
+use crate::error::{self, Result};
+use rustybeer_util::containers::{all_containers, Container};
 
 /// Prints out the quantity of bottles needed to store a given volume
 ///
 /// # Arguments
 /// * 'volume' - A volume to bottle in milliliters
 ///
-pub fn calculate_num_bottles(volume: f64) -> Vec<(String, i32)> {
-    let bottle_types = bottles();
-    let mut bottle_counter: Vec<(String, i32)> = Vec::with_capacity(bottle_types.len());
+pub fn calculate_num_bottles(volume: f64) -> Result<Vec<(String, i32)>> {
+    let volume = error::non_negative("volume", volume)?;
+    let containers = all_containers()?;
+    let mut bottle_counter: Vec<(String, i32)> = Vec::with_capacity(containers.len());
+
+    for container in containers {
+        let container_volume = error::positive("container volume", container.volume)?;
+        let num_bottles: i32 = (volume / container_volume).ceil() as i32;
+        bottle_counter.push((container.name, num_bottles));
+    }
+    Ok(bottle_counter)
+}
+
+/// Bottles at hand for packaging, or bottles
+/// filled when returned in a [PackingPlan](struct.PackingPlan.html)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BottleStock {
+    pub container: Container,
+    /// number of bottles
+    pub quantity: u32,
+}
+
+impl BottleStock {
+    pub fn new(container: Container, quantity: u32) -> Self {
+        Self {
+            container,
+            quantity,
+        }
+    }
+}
+
+/// Bottles to fill, and the beer left over, when
+/// packaging a volume into the bottles at hand
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PackingPlan {
+    /// bottles to fill, in the order they were given
+    pub bottles: Vec<BottleStock>,
+    /// beer volume packaged into the bottles (ml)
+    pub packaged: f64,
+    /// beer volume that does not fit in full bottles (ml)
+    pub leftover: f64,
+}
+
+/// Finds which of the bottles at hand to fill so that the
+/// least amount of beer is left over. On a tie, the plan using
+/// fewer bottles is picked.
+///
+/// # Arguments
+/// * 'volume' - A volume to bottle in milliliters
+/// * 'trub_loss' - A volume lost to trub and not bottled in milliliters
+/// * 'headspace' - The share of each bottle left empty (0.0 - 1.0)
+/// * 'stock' - The bottles at hand
+///
+pub fn calculate_packing(
+    volume: f64,
+    trub_loss: f64,
+    headspace: f64,
+    stock: &[BottleStock],
+) -> Result<PackingPlan> {
+    let volume = error::non_negative("volume", volume)?;
+    let trub_loss = error::non_negative("trub loss", trub_loss)?;
+    let headspace = error::in_range("headspace", headspace, 0.0, 1.0)?;
+    let available = (volume - trub_loss).max(0.0);
+    let capacity = available.floor() as usize;
+    let fills: Vec<usize> = stock
+        .iter()
+        .map(|b| (b.container.volume * (1.0 - headspace)).floor().max(0.0) as usize)
+        .collect();
+
+    // Least bottles needed to package exactly n milliliters, and the
+    // number of each bottle type used to get there
+    let mut least_bottles: Vec<Option<u32>> = vec![None; capacity + 1];
+    least_bottles[0] = Some(0);
+    let mut used: Vec<Vec<u32>> = Vec::with_capacity(stock.len());
+
+    for (bottle, &fill) in stock.iter().zip(fills.iter()) {
+        let previous = least_bottles.clone();
+        let mut used_here = vec![0; capacity + 1];
+        for n in 0..=capacity {
+            let max_quantity = n
+                .checked_div(fill)
+                .map_or(0, |fit| bottle.quantity.min(fit as u32));
+            for quantity in 1..=max_quantity {
+                if let Some(count) = previous[n - quantity as usize * fill] {
+                    let count = count + quantity;
+                    let fewer = match least_bottles[n] {
+                        Some(least) => count < least,
+                        None => true,
+                    };
+                    if fewer {
+                        least_bottles[n] = Some(count);
+                        used_here[n] = quantity;
+                    }
+                }
+            }
+        }
+        used.push(used_here);
+    }
 
-    for bottle in bottle_types {
-        let num_bottles: i32 = ((volume) / bottle.1).ceil() as i32;
-        bottle_counter.push((bottle.0, num_bottles));
+    let mut n = (0..=capacity)
+        .rev()
+        .find(|&n| least_bottles[n].is_some())
+        .unwrap_or(0);
+    let packaged = n as f64;
+
+    let mut bottles = vec![0; stock.len()];
+    for i in (0..stock.len()).rev() {
+        bottles[i] = used[i][n];
+        n -= used[i][n] as usize * fills[i];
     }
-    bottle_counter
+
+    Ok(PackingPlan {
+        bottles: stock
+            .iter()
+            .zip(bottles)
+            .filter(|(_, quantity)| *quantity > 0)
+            .map(|(b, quantity)| BottleStock::new(b.container.clone(), quantity))
+            .collect(),
+        packaged,
+        leftover: available - packaged,
+    })
 }
 
 #[cfg(test)]
@@ -53,7 +151,7 @@ mod tests {
             ("5 liter mini keg".to_string(), 1),
             ("50 liter keg".to_string(), 1),
         ];
-        assert_eq!(expected, calculate_num_bottles(330.0));
+        assert_eq!(expected, calculate_num_bottles(330.0).unwrap());
     }
 
     #[test]
@@ -72,6 +170,78 @@ mod tests {
             ("5 liter mini keg".to_string(), 66),
             ("50 liter keg".to_string(), 7),
         ];
-        assert_eq!(expected, calculate_num_bottles(330000.0));
+        assert_eq!(expected, calculate_num_bottles(330000.0).unwrap());
+    }
+
+    fn stock() -> Vec<BottleStock> {
+        vec![
+            BottleStock::new(Container::new("330ml bottle".to_string(), 330.0), 24),
+            BottleStock::new(Container::new("Half liter bottle".to_string(), 500.0), 12),
+            BottleStock::new(Container::new("5 liter mini keg".to_string(), 5000.0), 2),
+        ]
+    }
+
+    #[test]
+    fn test_packing_exact() {
+        let plan = calculate_packing(10_000.0, 0.0, 0.0, &stock()).unwrap();
+        assert_eq!(
+            vec![BottleStock::new(
+                Container::new("5 liter mini keg".to_string(), 5000.0),
+                2
+            )],
+            plan.bottles
+        );
+        assert_eq!(10_000.0, plan.packaged);
+        assert_eq!(0.0, plan.leftover);
+    }
+
+    #[test]
+    fn test_packing_mixed() {
+        let plan = calculate_packing(11_320.0, 0.0, 0.0, &stock()).unwrap();
+        assert_eq!(
+            vec![
+                BottleStock::new(Container::new("330ml bottle".to_string(), 330.0), 4),
+                BottleStock::new(Container::new("5 liter mini keg".to_string(), 5000.0), 2),
+            ],
+            plan.bottles
+        );
+        assert_eq!(11_320.0, plan.packaged);
+        assert_eq!(0.0, plan.leftover);
+    }
+
+    #[test]
+    fn test_packing_trub_and_headspace() {
+        // 1000 ml left after trub, bottles hold 297 ml and 450 ml with headspace
+        let plan = calculate_packing(1_200.0, 200.0, 0.1, &stock()[..2]).unwrap();
+        assert_eq!(
+            vec![BottleStock::new(
+                Container::new("Half liter bottle".to_string(), 500.0),
+                2
+            )],
+            plan.bottles
+        );
+        assert_eq!(900.0, plan.packaged);
+        assert_eq!(100.0, plan.leftover);
+    }
+
+    #[test]
+    fn test_packing_not_enough_bottles() {
+        let plan = calculate_packing(50_000.0, 0.0, 0.0, &stock()).unwrap();
+        assert_eq!(stock(), plan.bottles);
+        assert_eq!(23_920.0, plan.packaged);
+        assert_eq!(26_080.0, plan.leftover);
+    }
+
+    #[test]
+    fn test_packing_invalid_headspace() {
+        assert_eq!(
+            Err(crate::Error::OutOfRange {
+                name: "headspace",
+                value: 1.5,
+                min: 0.0,
+                max: 1.0
+            }),
+            calculate_packing(1_000.0, 0.0, 1.5, &stock())
+        );
     }
 }
@@ -6,7 +6,7 @@
 //! use rustybeer::calculators::priming::{calculate_sugars, Sugar};
 //!
 //! // Arguments are in Farenheit, liters, and c02 volumes
-//! let sugars = calculate_sugars(77., 5., 2.);
+//! let sugars = calculate_sugars(77., 5., 2.).unwrap();
 //!
 //! println!("You can add: ");
 //!
@@ -15,6 +15,14 @@
 //! }
 //! ```
 
+use crate::error::{self, Error, Result};
+use crate::units::{Gravity, Mass, Temperature, Volume};
+use rustybeer_util::sugars::{PrimingSugar, SugarForm, SUGARS};
+
+/// CO2 produced by fermenting wort extract relative
+/// to the same weight of table sugar (sucrose)
+static EXTRACT_FERMENTABILITY: f64 = 0.95;
+
 /// A calculator to determine how much
 /// priming sugar should be added at
 /// bottling time for brewed beer,
@@ -23,9 +31,49 @@
 /// present in the beer due to fermentation.
 /// The temperature should be given in fahrenheit
 pub fn calculate_co2(fahrenheit: f64) -> f64 {
+    residual_co2(Temperature::from_fahrenheit(fahrenheit))
+}
+
+/// Calculates the residual volumes of c02 present in
+/// the beer due to fermentation at the given temperature
+pub fn residual_co2(temperature: Temperature) -> f64 {
+    let fahrenheit = temperature.as_fahrenheit();
     3.0378 - 0.050062 * fahrenheit + 0.00026555 * fahrenheit.powf(2.0)
 }
 
+/// Calculates the amount of table sugar (sucrose)
+/// that should be added at bottling time, based off
+/// the beer temperature, amount of beer and the
+/// volumes of c02.
+///
+/// Fails when the beer already holds more c02 than wanted.
+pub fn sucrose(temperature: Temperature, amount: Volume, co2_volumes: f64) -> Result<Mass> {
+    let amount = error::non_negative("amount", amount.as_litres())?;
+    let beer_co2 = residual_co2(temperature);
+    if co2_volumes < beer_co2 {
+        return Err(Error::Unreachable {
+            target: "CO2 volumes",
+            value: co2_volumes,
+        });
+    }
+    Ok(Mass::from_grams(
+        ((co2_volumes * 2.0) - (beer_co2 * 2.0)) * 2.0 * amount,
+    ))
+}
+
+/// Calculates the amount of table sugar (sucrose) in grams
+/// that should be added at bottling time, based off a given
+/// temperature (In farenheit), amount of beer (In liters),
+/// and the volume of c02.
+pub fn calculate_sucrose(fahrenheit: f64, amount: f64, co2_volumes: f64) -> Result<f64> {
+    Ok(sucrose(
+        Temperature::from_fahrenheit(fahrenheit),
+        Volume::from_litres(amount),
+        co2_volumes,
+    )?
+    .as_grams())
+}
+
 /// Calculates the amount of each sugar that
 /// should be added at bottling time, based
 /// off a given temperature (In farenheit),
@@ -33,41 +81,194 @@ pub fn calculate_co2(fahrenheit: f64) -> f64 {
 /// volume of c02.
 /// The returned Vec of [Sugars](struct.Sugar.html)
 /// can be printed or indexed as desired
-pub fn calculate_sugars(fahrenheit: f64, amount: f64, co2_volumes: f64) -> Vec<Sugar> {
-    let mut sugars = vec![
-        Sugar::new(String::from("Table Sugar (sucrose)"), 1.0),
-        Sugar::new(String::from("Corn Sugar (dextrose)"), 1.0 / 0.91),
-        Sugar::new(String::from("DME - All Varieties"), 1.0 / 0.68),
-        Sugar::new(String::from("DME - Laaglander"), 1.0 / 0.5),
-        Sugar::new(String::from("Turbinado"), 1.0),
-        Sugar::new(String::from("Demarara"), 1.0),
-        Sugar::new(String::from("Corn Syrup"), 1.0 / 0.69),
-        Sugar::new(String::from("Brown Sugar"), 1.0 / 0.89),
-        Sugar::new(String::from("Molasses"), 1.0 / 0.71),
-        Sugar::new(String::from("Maple Syrup"), 1.0 / 0.77),
-        Sugar::new(String::from("Sorghum Syrup"), 1.0 / 0.69),
-        Sugar::new(String::from("Honey"), 1.0 / 0.74),
-        Sugar::new(String::from("Belgian Candy Syrup"), 1.0 / 0.63),
-        Sugar::new(String::from("Belgian Candy Sugar"), 1.0 / 0.75),
-        Sugar::new(String::from("Invert Sugar Syrup"), 1.0 / 0.91),
-        Sugar::new(String::from("Black Treacle"), 1.0 / 0.87),
-        Sugar::new(String::from("Rice Solids"), 1.0 / 0.79),
-    ];
-
-    let beer_co2 = calculate_co2(fahrenheit);
-    let sucrose = ((co2_volumes * 2.0) - (beer_co2 * 2.0)) * 2.0 * amount;
+pub fn calculate_sugars(fahrenheit: f64, amount: f64, co2_volumes: f64) -> Result<Vec<Sugar>> {
+    let mut sugars: Vec<Sugar> = SUGARS
+        .iter()
+        .map(|sugar| Sugar::new(sugar.name.clone(), 1.0 / sugar.fermentability))
+        .collect();
+
+    let sucrose = calculate_sucrose(fahrenheit, amount, co2_volumes)?;
 
     for sugar in sugars.iter_mut() {
         sugar.ratio *= sucrose
     }
 
-    sugars
+    Ok(sugars)
+}
+
+/// Calculates the amount of a single sugar that
+/// should be added at bottling time, based
+/// off a given temperature (In farenheit),
+/// amount of beer (In liters), and the
+/// volume of c02.
+///
+/// ```
+/// use rustybeer::calculators::priming::calculate_sugar;
+/// use rustybeer_util::{assert_approx, sugars::SUGARS};
+///
+/// let honey = SUGARS.iter().find(|s| s.name == "Honey").unwrap();
+/// let amount = calculate_sugar(77., 5., 2., honey).unwrap();
+/// assert_approx!(33.5818, amount.grams);
+/// assert_approx!(23.6492, amount.milliliters);
+/// ```
+pub fn calculate_sugar(
+    fahrenheit: f64,
+    amount: f64,
+    co2_volumes: f64,
+    sugar: &PrimingSugar,
+) -> Result<SugarAmount> {
+    let grams = calculate_sucrose(fahrenheit, amount, co2_volumes)? / sugar.fermentability;
+    Ok(SugarAmount {
+        name: sugar.name.clone(),
+        form: sugar.form,
+        grams,
+        milliliters: grams / sugar.density,
+    })
+}
+
+/// Calculates the volume (ml) of a priming syrup with a
+/// known concentration of a dissolved sugar (g/l) that
+/// should be added at bottling time, based off a given
+/// temperature (In farenheit), amount of beer (In liters),
+/// and the volume of c02.
+///
+/// ```
+/// use rustybeer::calculators::priming::calculate_syrup;
+/// use rustybeer_util::{assert_approx, sugars::SUGARS};
+///
+/// // Table sugar dissolved 500 g per liter of syrup
+/// assert_approx!(49.7011, calculate_syrup(77., 5., 2., &SUGARS[0], 500.).unwrap());
+/// ```
+pub fn calculate_syrup(
+    fahrenheit: f64,
+    amount: f64,
+    co2_volumes: f64,
+    sugar: &PrimingSugar,
+    concentration: f64,
+) -> Result<f64> {
+    let concentration = error::positive("syrup concentration", concentration)?;
+    Ok(calculate_sugar(fahrenheit, amount, co2_volumes, sugar)?.grams / concentration * 1000.0)
+}
+
+/// Converts a gravity to grams of
+/// extract per liter of wort
+fn calculate_extract(gravity: Gravity) -> f64 {
+    gravity.as_plato() * gravity.as_specific_gravity() * 10.0
+}
+
+/// Calculates the volume (In liters) of speise, unfermented
+/// wort saved from the brew day, that should be added at
+/// bottling time, based off a given temperature (In farenheit),
+/// amount of beer (In liters), the volume of c02, the gravity
+/// of the speise and the gravity of the beer.
+///
+/// The speise is expected to ferment down to the gravity
+/// of the beer. As the speise adds to the amount of beer,
+/// it needs to carbonate itself too.
+///
+/// ```
+/// use rustybeer::calculators::priming::calculate_speise;
+/// use rustybeer_util::assert_approx;
+///
+/// assert_approx!(1.1722, calculate_speise(50., 20., 2.5, 1.048, 1.010).unwrap());
+/// ```
+pub fn calculate_speise(
+    fahrenheit: f64,
+    amount: f64,
+    co2_volumes: f64,
+    speise_gravity: f64,
+    beer_gravity: f64,
+) -> Result<f64> {
+    Ok(speise(
+        Temperature::from_fahrenheit(fahrenheit),
+        Volume::from_litres(amount),
+        co2_volumes,
+        Gravity::from_specific_gravity(speise_gravity),
+        Gravity::from_specific_gravity(beer_gravity),
+    )?
+    .as_litres())
+}
+
+/// Calculates the volume of speise, unfermented wort saved
+/// from the brew day, that should be added at bottling time,
+/// based off the beer temperature, amount of beer, the volumes
+/// of c02, the gravity of the speise and the gravity of the beer.
+pub fn speise(
+    temperature: Temperature,
+    amount: Volume,
+    co2_volumes: f64,
+    speise_gravity: Gravity,
+    beer_gravity: Gravity,
+) -> Result<Volume> {
+    let amount = error::non_negative("amount", amount.as_litres())?;
+    let sucrose_per_liter = sucrose(temperature, Volume::from_litres(1.0), co2_volumes)?.as_grams();
+    let sucrose_per_speise_liter = (calculate_extract(speise_gravity)
+        - calculate_extract(beer_gravity))
+        * EXTRACT_FERMENTABILITY;
+
+    // The addition must carry more sugar than it needs to carbonate itself
+    if sucrose_per_speise_liter <= sucrose_per_liter {
+        return Err(Error::Unreachable {
+            target: "CO2 volumes",
+            value: co2_volumes,
+        });
+    }
+
+    Ok(Volume::from_litres(
+        sucrose_per_liter * amount / (sucrose_per_speise_liter - sucrose_per_liter),
+    ))
+}
+
+/// Calculates the volume (In liters) of krausen, actively
+/// fermenting beer, that should be added at bottling time,
+/// based off a given temperature (In farenheit), amount of
+/// beer (In liters), the volume of c02, the current gravity
+/// of the krausen and the gravity of the beer.
+///
+/// The krausen is expected to ferment down to the gravity
+/// of the beer, the same way as [speise](fn.calculate_speise.html).
+pub fn calculate_krausen(
+    fahrenheit: f64,
+    amount: f64,
+    co2_volumes: f64,
+    krausen_gravity: f64,
+    beer_gravity: f64,
+) -> Result<f64> {
+    Ok(krausen(
+        Temperature::from_fahrenheit(fahrenheit),
+        Volume::from_litres(amount),
+        co2_volumes,
+        Gravity::from_specific_gravity(krausen_gravity),
+        Gravity::from_specific_gravity(beer_gravity),
+    )?
+    .as_litres())
+}
+
+/// Calculates the volume of krausen, actively fermenting beer,
+/// that should be added at bottling time, based off the beer
+/// temperature, amount of beer, the volumes of c02, the current
+/// gravity of the krausen and the gravity of the beer.
+pub fn krausen(
+    temperature: Temperature,
+    amount: Volume,
+    co2_volumes: f64,
+    krausen_gravity: Gravity,
+    beer_gravity: Gravity,
+) -> Result<Volume> {
+    speise(
+        temperature,
+        amount,
+        co2_volumes,
+        krausen_gravity,
+        beer_gravity,
+    )
 }
 
 /// A sugar added at bottling time for
 /// brewed beer, with a name and ratio
 /// of beer to be used, in grams
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Sugar {
     pub name: String,
     pub ratio: f64,
@@ -78,3 +279,15 @@ impl Sugar {
         Self { name, ratio }
     }
 }
+
+/// The amount of a single sugar added
+/// at bottling time, by weight and by
+/// volume as the sugar is usually measured
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SugarAmount {
+    pub name: String,
+    pub form: SugarForm,
+    pub grams: f64,
+    pub milliliters: f64,
+}
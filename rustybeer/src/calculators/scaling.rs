@@ -0,0 +1,176 @@
+//! Scales a recipe to a new batch volume and brewhouse efficiency
+//!
+//! Grain weights are corrected for the efficiency so that the
+//! original gravity stays the same, and hop weights are recomputed
+//! so that each boil addition gives the same IBU in the new batch.
+//! Aroma and dry hops, water salts and yeast scale with the volume.
+//! ```
+//! use rustybeer::calculators::scaling::calculate_scaled_recipe;
+//! use rustybeer::recipe::{Fermentable, Recipe};
+//! use rustybeer_util::assert_approx;
+//!
+//! let pilot = Recipe {
+//!     name: "Pilot".to_owned(),
+//!     batch_volume_liters: 20.,
+//!     efficiency: 0.75,
+//!     fermentables: vec![Fermentable::grain("Pale Malt", 4500., 1.037, 3.)],
+//!     ..Default::default()
+//! };
+//! let scaled = calculate_scaled_recipe(&pilot, 200., 0.68).unwrap();
+//! assert_approx!(49632.3529, scaled.fermentables[0].weight_grams);
+//! ```
+
+use crate::calculators::ibu::ibu;
+use crate::error::{self, Result};
+use crate::recipe::{FermentableType, Recipe};
+use crate::units::Volume;
+
+/// Scales a recipe to a new batch volume (liters) and
+/// brewhouse efficiency (fraction, e.g. 0.72)
+pub fn calculate_scaled_recipe(
+    recipe: &Recipe,
+    batch_volume_liters: f64,
+    efficiency: f64,
+) -> Result<Recipe> {
+    scale(recipe, Volume::from_litres(batch_volume_liters), efficiency)
+}
+
+/// Scales a recipe to a new batch volume and brewhouse efficiency
+/// keeping the original gravity and IBU of the recipe
+pub fn scale(recipe: &Recipe, batch_volume: Volume, efficiency: f64) -> Result<Recipe> {
+    let new_volume = error::positive("batch volume", batch_volume.as_litres())?;
+    error::positive("efficiency", efficiency)?;
+    error::in_range("efficiency", efficiency, 0., 1.)?;
+    let original_gravity = recipe.original_gravity()?;
+    let volume_ratio = new_volume / recipe.batch_volume_liters;
+
+    let mut scaled = recipe.clone();
+    scaled.batch_volume_liters = new_volume;
+    scaled.efficiency = efficiency;
+
+    for fermentable in scaled.fermentables.iter_mut() {
+        fermentable.weight_grams *= match fermentable.fermentable_type {
+            FermentableType::Grain => {
+                volume_ratio * error::positive("efficiency", recipe.efficiency)? / efficiency
+            }
+            FermentableType::Extract | FermentableType::Sugar => volume_ratio,
+        };
+    }
+
+    // Utilization depends on the gravity, so every boil addition
+    // is weighed again for the IBU it gave in the original recipe
+    let scaled_gravity = scaled.original_gravity()?;
+    for hop in scaled.hops.iter_mut() {
+        let addition = [hop.addition];
+        let original_ibu = ibu(&addition, recipe.batch_volume(), original_gravity)?.as_ibu();
+        let scaled_ibu = ibu(&addition, batch_volume, scaled_gravity)?.as_ibu();
+        hop.addition.weight_grams *= if original_ibu > 0. && scaled_ibu > 0. {
+            original_ibu / scaled_ibu
+        } else {
+            volume_ratio
+        };
+    }
+
+    for salt in scaled.salts.iter_mut() {
+        salt.weight_grams *= volume_ratio;
+    }
+    for yeast in scaled.yeasts.iter_mut() {
+        yeast.cells_billions *= volume_ratio;
+    }
+
+    Ok(scaled)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::calculate_scaled_recipe;
+    use crate::calculators::ibu::{HopAddition, HopAdditionType};
+    use crate::recipe::{Fermentable, FermentableType, Recipe, RecipeHop, RecipeYeast, Salt};
+    use crate::Error;
+    use rustybeer_util::assert_approx;
+
+    fn pilot() -> Recipe {
+        Recipe {
+            name: "Pilot IPA".to_owned(),
+            batch_volume_liters: 20.,
+            efficiency: 0.75,
+            fermentables: vec![
+                Fermentable::grain("Pale Malt", 5000., 1.037, 3.),
+                Fermentable::grain("Crystal 40", 300., 1.034, 40.),
+                Fermentable {
+                    name: "Dextrose".to_owned(),
+                    weight_grams: 250.,
+                    potential: 1.046,
+                    color_lovibond: 0.,
+                    fermentable_type: FermentableType::Sugar,
+                },
+            ],
+            hops: vec![
+                RecipeHop::new(
+                    "Magnum",
+                    HopAddition::new(20., 0.12, 60, HopAdditionType::Pellet),
+                ),
+                RecipeHop::new(
+                    "Citra",
+                    HopAddition::new(30., 0.12, 10, HopAdditionType::Pellet),
+                ),
+                RecipeHop::new(
+                    "Citra",
+                    HopAddition::new(50., 0.12, 0, HopAdditionType::Pellet),
+                ),
+            ],
+            salts: vec![Salt {
+                name: "Gypsum".to_owned(),
+                weight_grams: 5.,
+            }],
+            yeasts: vec![RecipeYeast {
+                name: "US-05".to_owned(),
+                cells_billions: 200.,
+            }],
+            mash_steps: vec![],
+        }
+    }
+
+    #[test]
+    fn scale_keeps_gravity_and_ibu() {
+        let pilot = pilot();
+        let scaled = calculate_scaled_recipe(&pilot, 200., 0.68).unwrap();
+
+        assert_approx!(
+            pilot.original_gravity().unwrap().as_specific_gravity(),
+            scaled.original_gravity().unwrap().as_specific_gravity()
+        );
+        assert_approx!(
+            pilot.ibu().unwrap().as_ibu(),
+            scaled.ibu().unwrap().as_ibu()
+        );
+    }
+
+    #[test]
+    fn scale_weights() {
+        let scaled = calculate_scaled_recipe(&pilot(), 200., 0.68).unwrap();
+
+        // Grains corrected for efficiency, sugar only for volume
+        assert_approx!(55147.0588, scaled.fermentables[0].weight_grams);
+        assert_approx!(2500., scaled.fermentables[2].weight_grams);
+        // Same gravity, so boil hops scale with the volume
+        assert_approx!(200., scaled.hops[0].addition.weight_grams);
+        assert_approx!(500., scaled.hops[2].addition.weight_grams);
+        assert_approx!(50., scaled.salts[0].weight_grams);
+        assert_approx!(2000., scaled.yeasts[0].cells_billions);
+        assert_eq!(200., scaled.batch_volume_liters);
+        assert_eq!(0.68, scaled.efficiency);
+    }
+
+    #[test]
+    fn invalid_scale() {
+        assert!(matches!(
+            calculate_scaled_recipe(&pilot(), 0., 0.68),
+            Err(Error::NotPositive { .. })
+        ));
+        assert!(matches!(
+            calculate_scaled_recipe(&pilot(), 200., 68.),
+            Err(Error::OutOfRange { .. })
+        ));
+    }
+}
@@ -3,17 +3,54 @@
 //! between the measurement temperature
 //! and the calibration temperature
 
+use crate::error::{self, Result};
+use crate::units::{Gravity, Temperature};
+
+/// Corrects a specific gravity reading with respect to
+/// the calibration temperature of the hydrometer and the
+/// temperature of the sample at the time of measurement
+/// ```
+/// use rustybeer::calculators::sg_correction::correct_gravity;
+/// use rustybeer::units::{Gravity, Temperature};
+/// use rustybeer_util::assert_approx;
+///
+/// let corrected = correct_gravity(
+///     Gravity::from_specific_gravity(1.050),
+///     Temperature::from_celsius(20.),
+///     Temperature::from_celsius(30.),
+/// )
+/// .unwrap();
+/// assert_approx!(1.0527, corrected.as_specific_gravity());
+/// ```
+pub fn correct_gravity(
+    sg: Gravity,
+    calibration_temperature: Temperature,
+    measurement_temperature: Temperature,
+) -> Result<Gravity> {
+    let sg = error::positive("specific gravity", sg.as_specific_gravity())?;
+    let ctf = calibration_temperature.as_fahrenheit();
+    let mtf = measurement_temperature.as_fahrenheit();
+    Ok(Gravity::from_specific_gravity(
+        sg * ((1.00130346 - 0.000134722124 * mtf + 0.00000204052596 * (mtf * mtf)
+            - 0.00000000232820948 * (mtf * mtf * mtf))
+            / (1.00130346 - 0.000134722124 * ctf + 0.00000204052596 * (ctf * ctf)
+                - 0.00000000232820948 * (ctf * ctf * ctf))),
+    ))
+}
+
 /// Corrects a specific gravity value with respect to
 /// a given calibration temperature and
 /// measurement temperature
 /// ```
 /// use rustybeer::calculators::sg_correction::correct_sg;
 ///
-/// assert_eq!(5.0002323479056585, correct_sg(5., 23., 22.));
+/// assert_eq!(Ok(5.0002323479056585), correct_sg(5., 23., 22.));
 /// ```
-pub fn correct_sg(sg: f64, ctf: f64, mtf: f64) -> f64 {
-    sg * ((1.00130346 - 0.000134722124 * mtf + 0.00000204052596 * (mtf * mtf)
-        - 0.00000000232820948 * (mtf * mtf * mtf))
-        / (1.00130346 - 0.000134722124 * ctf + 0.00000204052596 * (ctf * ctf)
-            - 0.00000000232820948 * (ctf * ctf * ctf)))
+pub fn correct_sg(sg: f64, ctf: f64, mtf: f64) -> Result<f64> {
+    Ok(correct_gravity(
+        Gravity::from_specific_gravity(sg),
+        Temperature::from_fahrenheit(ctf),
+        Temperature::from_fahrenheit(mtf),
+    )?
+    .as_specific_gravity())
 }
@@ -0,0 +1,324 @@
+//! Brew-day timeline of a recipe
+//!
+//! Orders the steps of a brew day from heating the strike water
+//! through the mash rests, sparge and boil to chilling the wort.
+//! Hop additions are placed by their boil time, counting down to
+//! flameout. Step times are minutes from the start of the brew day.
+//! ```
+//! use rustybeer::calculators::ibu::{HopAddition, HopAdditionType};
+//! use rustybeer::calculators::timeline::{timeline, BrewDay, BrewStepKind};
+//! use rustybeer::recipe::{MashStep, Recipe, RecipeHop};
+//!
+//! let recipe = Recipe {
+//!     name: "Pale Ale".to_owned(),
+//!     hops: vec![RecipeHop::new(
+//!         "Cascade",
+//!         HopAddition::new(28., 0.064, 15, HopAdditionType::Pellet),
+//!     )],
+//!     mash_steps: vec![MashStep {
+//!         name: "Saccharification".to_owned(),
+//!         temperature_celsius: 66.,
+//!         time_mins: 60,
+//!     }],
+//!     ..Default::default()
+//! };
+//! let steps = timeline(&recipe, &BrewDay::default()).unwrap();
+//! let cascade = steps
+//!     .iter()
+//!     .find(|step| step.kind == BrewStepKind::HopAddition)
+//!     .unwrap();
+//! // 30 min heating, 60 min mash, 30 min sparge, 45 min boil
+//! assert_eq!(165, cascade.start_mins);
+//! assert_eq!(Some(15), cascade.boil_mins_left);
+//! ```
+
+use crate::error::{self, Result};
+use crate::recipe::{Recipe, RecipeHop};
+use std::cmp::Reverse;
+
+/// Boil time used when the recipe has no longer hop additions
+const DEFAULT_BOIL_MINS: u32 = 60;
+
+/// Lengths of the brew day steps that are not given by the recipe
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct BrewDay {
+    /// time to heat the strike water (min)
+    pub strike_heating_mins: u32,
+    /// time to sparge and bring the wort to a boil (min)
+    pub sparge_mins: u32,
+    /// boil time (min), defaults to the longest hop addition but at least 60 minutes
+    pub boil_mins: Option<u32>,
+    /// whirlpool or hop stand after flameout (min), 0 for none
+    pub whirlpool_mins: u32,
+    /// time to chill the wort to pitching temperature (min)
+    pub chill_mins: u32,
+}
+
+impl Default for BrewDay {
+    fn default() -> Self {
+        Self {
+            strike_heating_mins: 30,
+            sparge_mins: 30,
+            boil_mins: None,
+            whirlpool_mins: 15,
+            chill_mins: 20,
+        }
+    }
+}
+
+/// An enum of brew day steps
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum BrewStepKind {
+    HeatStrikeWater,
+    MashRest,
+    Sparge,
+    /// The boil, lasting until flameout
+    BoilStart,
+    HopAddition,
+    Flameout,
+    Whirlpool,
+    Chill,
+}
+
+/// A step of the brew day
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BrewStep {
+    pub kind: BrewStepKind,
+    /// name of the mash rest or hop, otherwise the name of the step
+    pub name: String,
+    /// start of the step from the start of the brew day (min)
+    pub start_mins: u32,
+    /// length of the step (min)
+    pub duration_mins: u32,
+    /// temperature of a mash rest, or the mash-in temperature
+    /// the strike water is heated for (°C)
+    pub temperature_celsius: Option<f64>,
+    /// weight of a hop addition (gm)
+    pub weight_grams: Option<f64>,
+    /// boil time left when a hop is added (min)
+    pub boil_mins_left: Option<u32>,
+}
+
+impl BrewStep {
+    fn new(kind: BrewStepKind, name: &str, start_mins: u32, duration_mins: u32) -> Self {
+        Self {
+            kind,
+            name: name.to_owned(),
+            start_mins,
+            duration_mins,
+            temperature_celsius: None,
+            weight_grams: None,
+            boil_mins_left: None,
+        }
+    }
+
+    /// End of the step from the start of the brew day (min)
+    pub fn end_mins(&self) -> u32 {
+        self.start_mins + self.duration_mins
+    }
+}
+
+/// Orders the steps of brewing a recipe. Recipes without
+/// mash steps are brewed from extract and start with the boil.
+pub fn timeline(recipe: &Recipe, brew_day: &BrewDay) -> Result<Vec<BrewStep>> {
+    let longest_addition = recipe
+        .hops
+        .iter()
+        .map(|hop| hop.addition.time_mins)
+        .max()
+        .unwrap_or_default();
+    let boil_mins = match brew_day.boil_mins {
+        Some(boil_mins) => {
+            error::in_range(
+                "hop addition time",
+                longest_addition.into(),
+                0.,
+                boil_mins.into(),
+            )?;
+            boil_mins
+        }
+        None => longest_addition.max(DEFAULT_BOIL_MINS),
+    };
+
+    let mut steps = Vec::new();
+    let mut time = 0;
+    if let Some(mash_in) = recipe.mash_steps.first() {
+        let mut strike = BrewStep::new(
+            BrewStepKind::HeatStrikeWater,
+            "Heat strike water",
+            time,
+            brew_day.strike_heating_mins,
+        );
+        strike.temperature_celsius = Some(mash_in.temperature_celsius);
+        time = strike.end_mins();
+        steps.push(strike);
+
+        for mash_step in &recipe.mash_steps {
+            let mut rest = BrewStep::new(
+                BrewStepKind::MashRest,
+                &mash_step.name,
+                time,
+                mash_step.time_mins,
+            );
+            rest.temperature_celsius = Some(mash_step.temperature_celsius);
+            time = rest.end_mins();
+            steps.push(rest);
+        }
+
+        let sparge = BrewStep::new(BrewStepKind::Sparge, "Sparge", time, brew_day.sparge_mins);
+        time = sparge.end_mins();
+        steps.push(sparge);
+    }
+
+    let boil = BrewStep::new(BrewStepKind::BoilStart, "Boil", time, boil_mins);
+    let flameout = boil.end_mins();
+    steps.push(boil);
+
+    // Longest additions first, additions at zero go in at flameout
+    let mut hops: Vec<_> = recipe.hops.iter().collect();
+    hops.sort_by_key(|hop| Reverse(hop.addition.time_mins));
+    let (boil_hops, flameout_hops): (Vec<_>, Vec<_>) =
+        hops.into_iter().partition(|hop| hop.addition.time_mins > 0);
+    let hop_step = |hop: &RecipeHop| {
+        let mut step = BrewStep::new(
+            BrewStepKind::HopAddition,
+            &hop.name,
+            flameout - hop.addition.time_mins,
+            0,
+        );
+        step.weight_grams = Some(hop.addition.weight_grams);
+        step.boil_mins_left = Some(hop.addition.time_mins);
+        step
+    };
+    steps.extend(boil_hops.into_iter().map(hop_step));
+    steps.push(BrewStep::new(
+        BrewStepKind::Flameout,
+        "Flameout",
+        flameout,
+        0,
+    ));
+    steps.extend(flameout_hops.into_iter().map(hop_step));
+
+    time = flameout;
+    if brew_day.whirlpool_mins > 0 {
+        let whirlpool = BrewStep::new(
+            BrewStepKind::Whirlpool,
+            "Whirlpool",
+            time,
+            brew_day.whirlpool_mins,
+        );
+        time = whirlpool.end_mins();
+        steps.push(whirlpool);
+    }
+    steps.push(BrewStep::new(
+        BrewStepKind::Chill,
+        "Chill",
+        time,
+        brew_day.chill_mins,
+    ));
+    Ok(steps)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::{timeline, BrewDay, BrewStepKind};
+    use crate::calculators::ibu::{HopAddition, HopAdditionType};
+    use crate::recipe::{MashStep, Recipe, RecipeHop};
+    use crate::Error;
+
+    fn step_mash() -> Recipe {
+        let hop = |name, time_mins| {
+            RecipeHop::new(
+                name,
+                HopAddition::new(20., 0.1, time_mins, HopAdditionType::Pellet),
+            )
+        };
+        let rest = |name: &str, temperature_celsius, time_mins| MashStep {
+            name: name.to_owned(),
+            temperature_celsius,
+            time_mins,
+        };
+        Recipe {
+            name: "Pilsner".to_owned(),
+            hops: vec![hop("Saaz", 0), hop("Hallertau", 90), hop("Saaz", 30)],
+            mash_steps: vec![
+                rest("Protein rest", 52., 15),
+                rest("Saccharification", 65., 45),
+                rest("Mash out", 76., 10),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn step_mash_timeline() {
+        let steps = timeline(&step_mash(), &BrewDay::default()).unwrap();
+        let order: Vec<_> = steps
+            .iter()
+            .map(|s| (s.kind, s.name.as_str(), s.start_mins, s.duration_mins))
+            .collect();
+        assert_eq!(
+            vec![
+                (BrewStepKind::HeatStrikeWater, "Heat strike water", 0, 30),
+                (BrewStepKind::MashRest, "Protein rest", 30, 15),
+                (BrewStepKind::MashRest, "Saccharification", 45, 45),
+                (BrewStepKind::MashRest, "Mash out", 90, 10),
+                (BrewStepKind::Sparge, "Sparge", 100, 30),
+                // Boil is as long as the longest hop addition
+                (BrewStepKind::BoilStart, "Boil", 130, 90),
+                (BrewStepKind::HopAddition, "Hallertau", 130, 0),
+                (BrewStepKind::HopAddition, "Saaz", 190, 0),
+                (BrewStepKind::Flameout, "Flameout", 220, 0),
+                (BrewStepKind::HopAddition, "Saaz", 220, 0),
+                (BrewStepKind::Whirlpool, "Whirlpool", 220, 15),
+                (BrewStepKind::Chill, "Chill", 235, 20),
+            ],
+            order
+        );
+        assert_eq!(Some(52.), steps[0].temperature_celsius);
+        assert_eq!(Some(30), steps[7].boil_mins_left);
+    }
+
+    #[test]
+    fn extract_timeline() {
+        let mut recipe = step_mash();
+        recipe.mash_steps.clear();
+        recipe.hops.truncate(1);
+        let brew_day = BrewDay {
+            boil_mins: Some(45),
+            whirlpool_mins: 0,
+            ..Default::default()
+        };
+        let kinds: Vec<_> = timeline(&recipe, &brew_day)
+            .unwrap()
+            .iter()
+            .map(|s| (s.kind, s.start_mins))
+            .collect();
+        assert_eq!(
+            vec![
+                (BrewStepKind::BoilStart, 0),
+                (BrewStepKind::Flameout, 45),
+                (BrewStepKind::HopAddition, 45),
+                (BrewStepKind::Chill, 45),
+            ],
+            kinds
+        );
+    }
+
+    #[test]
+    fn hops_longer_than_boil() {
+        let brew_day = BrewDay {
+            boil_mins: Some(60),
+            ..Default::default()
+        };
+        assert!(matches!(
+            timeline(&step_mash(), &brew_day),
+            Err(Error::OutOfRange { .. })
+        ));
+    }
+}
@@ -1,11 +1,18 @@
 //! A calculator used to estimate
 //! yeast viability
 
-pub fn calculate_yv(days: f32) -> f32 {
-    let n_of_days = if days > 0.0 { days } else { 0.0 };
-    97.0 * ((2.72_f32).powf(-0.008 * n_of_days))
+use crate::error::{self, Result};
+
+/// Estimates the viability (%) of yeast
+/// after the given number of days since production
+pub fn calculate_yv(days: f32) -> Result<f32> {
+    let days = error::non_negative("days", days.into())? as f32;
+    Ok(97.0 * ((2.72_f32).powf(-0.008 * days)))
 }
 
-pub fn calculate_cc(cc: f32, days: f32) -> f32 {
-    cc * (calculate_yv(days) / 100.0)
+/// Estimates the viable cell count after the given
+/// number of days from the cell count at production
+pub fn calculate_cc(cc: f32, days: f32) -> Result<f32> {
+    let cc = error::non_negative("cell count", cc.into())? as f32;
+    Ok(cc * (calculate_yv(days)? / 100.0))
 }
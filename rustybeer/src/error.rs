@@ -0,0 +1,134 @@
+//! Errors returned by the calculators
+//! ```
+//! use rustybeer::calculators::abv::calculate_abv;
+//! use rustybeer::Error;
+//!
+//! match calculate_abv(1.010, 1.050) {
+//!     Err(Error::FinalAboveOriginalGravity { .. }) => {}
+//!     other => panic!("unexpected {:?}", other),
+//! }
+//! ```
+
+use std::{fmt, io};
+
+/// An error for input the calculators can not handle
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Error {
+    /// A value must be above zero
+    NotPositive { name: &'static str, value: f64 },
+    /// A value must not be below zero
+    Negative { name: &'static str, value: f64 },
+    /// A value is outside of the range the calculation is valid for
+    OutOfRange {
+        name: &'static str,
+        value: f64,
+        min: f64,
+        max: f64,
+    },
+    /// Final gravity is above original gravity
+    FinalAboveOriginalGravity { og: f64, fg: f64 },
+    /// A target can not be reached with the given input,
+    /// e.g. other hop additions already exceed the target IBU
+    Unreachable { target: &'static str, value: f64 },
+    /// A target needs an input that was not given,
+    /// e.g. a color target needs a grain given by weight
+    MissingInput {
+        target: &'static str,
+        value: f64,
+        input: &'static str,
+    },
+    /// Data, such as a user defined containers file, could not be loaded
+    Data(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::NotPositive { name, value } => {
+                write!(f, "{} must be above zero, got {}", name, value)
+            }
+            Error::Negative { name, value } => {
+                write!(f, "{} must not be negative, got {}", name, value)
+            }
+            Error::OutOfRange {
+                name,
+                value,
+                min,
+                max,
+            } => write!(
+                f,
+                "{} must be between {} and {}, got {}",
+                name, min, max, value
+            ),
+            Error::FinalAboveOriginalGravity { og, fg } => write!(
+                f,
+                "final gravity {} must not be above original gravity {}",
+                fg, og
+            ),
+            Error::Unreachable { target, value } => write!(
+                f,
+                "{} of {} can not be reached with the given input",
+                target, value
+            ),
+            Error::MissingInput {
+                target,
+                value,
+                input,
+            } => write!(f, "{} of {} needs {}", target, value, input),
+            Error::Data(e) => write!(f, "data could not be loaded: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Data(e.to_string())
+    }
+}
+
+/// Result type returned by the calculators
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Checks that a value is above zero
+pub(crate) fn positive(name: &'static str, value: f64) -> Result<f64> {
+    if value > 0.0 {
+        Ok(value)
+    } else {
+        Err(Error::NotPositive { name, value })
+    }
+}
+
+/// Checks that a value is not below zero
+pub(crate) fn non_negative(name: &'static str, value: f64) -> Result<f64> {
+    if value >= 0.0 {
+        Ok(value)
+    } else {
+        Err(Error::Negative { name, value })
+    }
+}
+
+/// Checks that a value is within an inclusive range
+pub(crate) fn in_range(name: &'static str, value: f64, min: f64, max: f64) -> Result<f64> {
+    if value >= min && value <= max {
+        Ok(value)
+    } else {
+        Err(Error::OutOfRange {
+            name,
+            value,
+            min,
+            max,
+        })
+    }
+}
+
+/// Checks that final gravity is not above original gravity
+pub(crate) fn gravities(og: f64, fg: f64) -> Result<()> {
+    if fg <= og {
+        Ok(())
+    } else {
+        Err(Error::FinalAboveOriginalGravity { og, fg })
+    }
+}
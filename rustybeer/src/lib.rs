@@ -5,10 +5,34 @@
 //! - Calculating ABV (Alcohol By Volume)
 //! - The specific gravity after dilution
 //! - Beer priming calculator
+//! - Krausening and speise priming calculator
+//! - Forced carbonation pressure for kegs
+//! - Draught line balancing
 //! - Specific gravity correction
 //! - IBU calculator
+//! - Conversion, lauter, mash and brewhouse efficiency
+//! - Recipe scaling to a new batch size and efficiency
+//! - Recipe design solving grain and hop weights for target OG, color and IBU
+//! - Starting recipe generator for a beer style
+//! - Recipe comparison of ingredients and predicted values
+//! - Brew-day timeline of mash rests, boil and hop additions
+//! - Brew sessions with measured efficiency, attenuation and ABV
 //!
 //! There's also a list of beer styles that can
 //! be indexed and requested
+//!
+//! Calculators take plain floats, or typed quantities from
+//! [units](units/index.html) that keep units and argument
+//! order checked by the compiler.
+//!
+//! Calculators that can be given input they can not handle
+//! return a [Result](type.Result.html) with an [Error](enum.Error.html)
+//! describing the invalid input.
 
 pub mod calculators;
+pub mod error;
+pub mod recipe;
+pub mod session;
+pub mod units;
+
+pub use error::{Error, Result};
@@ -0,0 +1,209 @@
+//! A beer recipe with its grain, hop, water salt and yeast bill
+//!
+//! Quantities are kept as plain numbers with the unit in the
+//! field name, like the inputs of the calculators.
+//! ```
+//! use rustybeer::calculators::ibu::{HopAddition, HopAdditionType};
+//! use rustybeer::recipe::{Fermentable, Recipe, RecipeHop};
+//! use rustybeer_util::assert_approx;
+//!
+//! let recipe = Recipe {
+//!     name: "Pale Ale".to_owned(),
+//!     batch_volume_liters: 20.,
+//!     efficiency: 0.75,
+//!     fermentables: vec![Fermentable::grain("Pale Malt", 4500., 1.037, 3.)],
+//!     hops: vec![RecipeHop::new(
+//!         "Cascade",
+//!         HopAddition::new(28., 0.064, 45, HopAdditionType::Pellet),
+//!     )],
+//!     ..Default::default()
+//! };
+//! assert_approx!(1.0521, recipe.original_gravity().unwrap().as_specific_gravity());
+//! assert_approx!(4.8839, recipe.color().unwrap().as_srm());
+//! ```
+
+use crate::calculators::ibu::{ibu, HopAddition};
+use crate::error::{self, Result};
+use crate::units::{Color, Gravity, Ibu, Volume};
+
+/// One gram per litre in pounds per US gallon, the unit extract
+/// potentials and malt color units are given in
+pub(crate) const LB_PER_GAL: f64 = 0.0083454;
+
+/// An enum of fermentable types
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum FermentableType {
+    /// Mashed grain, default. Yield depends on brewhouse efficiency
+    #[default]
+    Grain,
+    /// Malt extract, fully dissolved
+    Extract,
+    /// Sugar, honey or other adjunct added to the boil
+    Sugar,
+}
+
+/// A fermentable in the grain bill
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fermentable {
+    pub name: String,
+    /// weight of the fermentable (gm)
+    pub weight_grams: f64,
+    /// extract potential as specific gravity of one pound in one US gallon, e.g. 1.037
+    pub potential: f64,
+    /// color of the fermentable (°L)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub color_lovibond: f64,
+    /// type of the fermentable. [default() = FermentableType::Grain]
+    #[cfg_attr(feature = "serde", serde(default, rename = "type"))]
+    pub fermentable_type: FermentableType,
+}
+
+impl Fermentable {
+    /// Creates a mashed grain
+    pub fn grain(name: &str, weight_grams: f64, potential: f64, color_lovibond: f64) -> Self {
+        Self {
+            name: name.to_owned(),
+            weight_grams,
+            potential,
+            color_lovibond,
+            fermentable_type: FermentableType::Grain,
+        }
+    }
+
+    /// Malt color units the fermentable gives in one litre of wort
+    pub fn color_units(&self) -> f64 {
+        self.color_lovibond * LB_PER_GAL * self.weight_grams
+    }
+
+    /// Gravity points the fermentable gives in one litre of wort
+    /// with the given brewhouse efficiency
+    pub fn gravity_points(&self, efficiency: f64) -> f64 {
+        let yield_factor = match self.fermentable_type {
+            FermentableType::Grain => efficiency,
+            FermentableType::Extract | FermentableType::Sugar => 1.,
+        };
+        (self.potential - 1.) * 1000. * LB_PER_GAL * self.weight_grams * yield_factor
+    }
+}
+
+/// A hop addition in the hop bill
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecipeHop {
+    pub name: String,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub addition: HopAddition,
+}
+
+impl RecipeHop {
+    pub fn new(name: &str, addition: HopAddition) -> Self {
+        Self {
+            name: name.to_owned(),
+            addition,
+        }
+    }
+}
+
+/// A water salt, e.g. gypsum or calcium chloride
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Salt {
+    pub name: String,
+    /// weight of the salt (gm)
+    pub weight_grams: f64,
+}
+
+/// A yeast pitch
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecipeYeast {
+    pub name: String,
+    /// number of cells pitched (billions)
+    pub cells_billions: f64,
+}
+
+/// A rest of the mash
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MashStep {
+    pub name: String,
+    /// temperature of the rest (°C)
+    pub temperature_celsius: f64,
+    /// length of the rest (min)
+    pub time_mins: u32,
+}
+
+/// A beer recipe
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Recipe {
+    pub name: String,
+    /// volume of wort at the end of the boil (liters)
+    pub batch_volume_liters: f64,
+    /// brewhouse efficiency as a fraction, e.g. 0.72
+    pub efficiency: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fermentables: Vec<Fermentable>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub hops: Vec<RecipeHop>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub salts: Vec<Salt>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub yeasts: Vec<RecipeYeast>,
+    /// mash rests in order, empty for extract brewing
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub mash_steps: Vec<MashStep>,
+}
+
+impl Recipe {
+    /// Volume of wort at the end of the boil
+    pub fn batch_volume(&self) -> Volume {
+        Volume::from_litres(self.batch_volume_liters)
+    }
+
+    /// Original gravity of the wort given by the fermentables
+    pub fn original_gravity(&self) -> Result<Gravity> {
+        let volume = error::positive("batch volume", self.batch_volume_liters)?;
+        error::in_range("efficiency", self.efficiency, 0., 1.)?;
+        let points: f64 = self
+            .fermentables
+            .iter()
+            .map(|fermentable| fermentable.gravity_points(self.efficiency))
+            .sum();
+        Ok(Gravity::from_points(points / volume))
+    }
+
+    /// Color of the wort given by the fermentables, using the Morey equation
+    pub fn color(&self) -> Result<Color> {
+        let volume = error::positive("batch volume", self.batch_volume_liters)?;
+        let color_units: f64 = self.fermentables.iter().map(Fermentable::color_units).sum();
+        Ok(morey(color_units / volume))
+    }
+
+    /// Bitterness given by the hop additions at the original gravity
+    pub fn ibu(&self) -> Result<Ibu> {
+        ibu(
+            &self.hop_additions(),
+            self.batch_volume(),
+            self.original_gravity()?,
+        )
+    }
+
+    /// Hop additions of the recipe without the hop names
+    pub fn hop_additions(&self) -> Vec<HopAddition> {
+        self.hops.iter().map(|hop| hop.addition).collect()
+    }
+}
+
+/// Wort color from malt color units (°L lb/gal)
+pub(crate) fn morey(malt_color_units: f64) -> Color {
+    Color::from_srm(1.4922 * malt_color_units.powf(0.6859))
+}
+
+/// Malt color units (°L lb/gal) giving a wort color, the inverse of [morey]
+pub(crate) fn malt_color_units(color: Color) -> f64 {
+    (color.as_srm() / 1.4922).powf(1. / 0.6859)
+}
@@ -0,0 +1,289 @@
+//! A brew session: what was measured when a recipe was brewed
+//!
+//! Gives the efficiency, attenuation and ABV actually reached,
+//! next to the values predicted from the recipe.
+//! ```
+//! use rustybeer::recipe::{Fermentable, Recipe};
+//! use rustybeer::session::BrewSession;
+//! use rustybeer::units::Gravity;
+//! use rustybeer_util::assert_approx;
+//!
+//! let recipe = Recipe {
+//!     name: "Pale Ale".to_owned(),
+//!     batch_volume_liters: 20.,
+//!     efficiency: 0.75,
+//!     fermentables: vec![Fermentable::grain("Pale Malt", 4500., 1.037, 3.)],
+//!     ..Default::default()
+//! };
+//! let session = BrewSession {
+//!     recipe: "Pale Ale".to_owned(),
+//!     brew_date: "2026-10-03".to_owned(),
+//!     original_gravity: 1.048,
+//!     post_boil_volume_liters: 20.,
+//!     final_gravity: Some(1.010),
+//!     ..Default::default()
+//! };
+//! let results = session
+//!     .results(&recipe, Gravity::from_specific_gravity(1.012))
+//!     .unwrap();
+//! assert_approx!(0.6909, results.efficiency.measured.unwrap());
+//! assert_approx!(4.9875, results.abv.measured.unwrap());
+//! ```
+
+use crate::calculators::abv::abv;
+use crate::calculators::efficiency::{
+    brewhouse_efficiency, conversion_efficiency, lauter_efficiency, mash_efficiency,
+};
+use crate::error::{self, Result};
+use crate::recipe::Recipe;
+use crate::units::{Gravity, Volume};
+
+/// Measurements of brewing a recipe once
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BrewSession {
+    /// name or file of the recipe brewed
+    pub recipe: String,
+    /// date of the brew day, e.g. 2026-10-03
+    pub brew_date: String,
+    /// gravity of the first wort run off the mash
+    pub first_wort_gravity: Option<f64>,
+    /// volume of water the grain was mashed in (liters)
+    pub mash_water_liters: Option<f64>,
+    /// gravity of the wort collected in the kettle, before sugars and extracts are added
+    pub pre_boil_gravity: Option<f64>,
+    /// volume of the wort collected in the kettle (liters)
+    pub pre_boil_volume_liters: Option<f64>,
+    pub original_gravity: f64,
+    /// volume of wort at the end of the boil (liters)
+    pub post_boil_volume_liters: f64,
+    /// volume of wort in the fermenter (liters)
+    pub fermenter_volume_liters: Option<f64>,
+    /// final gravity, none while the beer is fermenting
+    pub final_gravity: Option<f64>,
+    /// date the beer was bottled or kegged
+    pub packaging_date: Option<String>,
+    /// volume of beer packaged (liters)
+    pub packaged_volume_liters: Option<f64>,
+    pub notes: Option<String>,
+}
+
+/// A value predicted from the recipe and the value measured in a session
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Measured {
+    pub predicted: f64,
+    /// measured value, none when it was not measured (yet)
+    pub measured: Option<f64>,
+}
+
+impl Measured {
+    /// Measured minus predicted value
+    pub fn difference(&self) -> Option<f64> {
+        self.measured.map(|measured| measured - self.predicted)
+    }
+}
+
+/// Values reached in a brew session next to the predicted ones
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SessionResults {
+    /// original gravity (sg)
+    pub original_gravity: Measured,
+    /// final gravity (sg)
+    pub final_gravity: Measured,
+    /// alcohol by volume (%)
+    pub abv: Measured,
+    /// apparent attenuation (%)
+    pub apparent_attenuation: Measured,
+    /// volume at the end of the boil (liters)
+    pub post_boil_volume_liters: Measured,
+    /// brewhouse efficiency as a fraction, comparable to the recipe efficiency
+    pub efficiency: Measured,
+    /// share of the grain extract dissolved in the mash
+    pub conversion_efficiency: Option<f64>,
+    /// share of the dissolved extract collected in the kettle
+    pub lauter_efficiency: Option<f64>,
+    /// share of the grain extract collected in the kettle
+    pub mash_efficiency: Option<f64>,
+}
+
+impl BrewSession {
+    /// Values reached in the session, next to the ones predicted from
+    /// the recipe brewed and its expected final gravity
+    pub fn results(
+        &self,
+        recipe: &Recipe,
+        predicted_final_gravity: Gravity,
+    ) -> Result<SessionResults> {
+        let volume = error::positive("post-boil volume", self.post_boil_volume_liters)?;
+        let original_gravity = Gravity::from_specific_gravity(self.original_gravity);
+        let final_gravity = self.final_gravity.map(Gravity::from_specific_gravity);
+        let predicted_gravity = recipe.original_gravity()?;
+
+        let fermentables = &recipe.fermentables;
+        let efficiency =
+            brewhouse_efficiency(fermentables, original_gravity, Volume::from_litres(volume))?;
+        let conversion_efficiency = match (self.first_wort_gravity, self.mash_water_liters) {
+            (Some(gravity), Some(water)) => Some(conversion_efficiency(
+                fermentables,
+                Gravity::from_specific_gravity(gravity),
+                Volume::from_litres(water),
+            )?),
+            _ => None,
+        };
+        let mash_efficiency = match (self.pre_boil_gravity, self.pre_boil_volume_liters) {
+            (Some(gravity), Some(volume)) => Some(mash_efficiency(
+                fermentables,
+                Gravity::from_specific_gravity(gravity),
+                Volume::from_litres(volume),
+            )?),
+            _ => None,
+        };
+        let lauter_efficiency = match (mash_efficiency, conversion_efficiency) {
+            (Some(mash), Some(conversion)) => Some(lauter_efficiency(mash, conversion)?),
+            _ => None,
+        };
+
+        Ok(SessionResults {
+            original_gravity: Measured {
+                predicted: predicted_gravity.as_specific_gravity(),
+                measured: Some(self.original_gravity),
+            },
+            final_gravity: Measured {
+                predicted: predicted_final_gravity.as_specific_gravity(),
+                measured: self.final_gravity,
+            },
+            abv: Measured {
+                predicted: abv(predicted_gravity, predicted_final_gravity)?.as_percent(),
+                measured: final_gravity
+                    .map(|fg| abv(original_gravity, fg).map(|abv| abv.as_percent()))
+                    .transpose()?,
+            },
+            apparent_attenuation: Measured {
+                predicted: apparent_attenuation(predicted_gravity, predicted_final_gravity)?,
+                measured: final_gravity
+                    .map(|fg| apparent_attenuation(original_gravity, fg))
+                    .transpose()?,
+            },
+            post_boil_volume_liters: Measured {
+                predicted: recipe.batch_volume_liters,
+                measured: Some(volume),
+            },
+            efficiency: Measured {
+                predicted: recipe.efficiency,
+                measured: Some(efficiency),
+            },
+            conversion_efficiency,
+            lauter_efficiency,
+            mash_efficiency,
+        })
+    }
+}
+
+/// Share of the gravity points fermented (%)
+fn apparent_attenuation(original_gravity: Gravity, final_gravity: Gravity) -> Result<f64> {
+    error::gravities(
+        original_gravity.as_specific_gravity(),
+        final_gravity.as_specific_gravity(),
+    )?;
+    let points = error::positive("original gravity points", original_gravity.as_points())?;
+    Ok((points - final_gravity.as_points()) / points * 100.)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::BrewSession;
+    use crate::recipe::{Fermentable, FermentableType, Recipe};
+    use crate::units::Gravity;
+    use crate::Error;
+    use rustybeer_util::assert_approx;
+
+    fn recipe() -> Recipe {
+        Recipe {
+            name: "Blonde".to_owned(),
+            batch_volume_liters: 20.,
+            efficiency: 0.72,
+            fermentables: vec![
+                Fermentable::grain("Pilsner Malt", 4000., 1.037, 2.),
+                Fermentable {
+                    name: "Dextrose".to_owned(),
+                    weight_grams: 300.,
+                    potential: 1.046,
+                    color_lovibond: 0.,
+                    fermentable_type: FermentableType::Sugar,
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn session() -> BrewSession {
+        BrewSession {
+            recipe: "Blonde".to_owned(),
+            brew_date: "2026-09-12".to_owned(),
+            first_wort_gravity: Some(1.075),
+            mash_water_liters: Some(12.),
+            pre_boil_gravity: Some(1.040),
+            pre_boil_volume_liters: Some(24.),
+            original_gravity: 1.050,
+            post_boil_volume_liters: 20.5,
+            fermenter_volume_liters: Some(19.),
+            final_gravity: Some(1.008),
+            packaging_date: Some("2026-09-30".to_owned()),
+            packaged_volume_liters: Some(18.),
+            notes: None,
+        }
+    }
+
+    #[test]
+    fn session_results() {
+        let results = session()
+            .results(&recipe(), Gravity::from_specific_gravity(1.010))
+            .unwrap();
+        assert_approx!(1.0502, results.original_gravity.predicted);
+        assert_approx!(-0.0002, results.original_gravity.difference().unwrap());
+        assert_approx!(0.7366, results.efficiency.measured.unwrap());
+        assert_approx!(0.7773, results.mash_efficiency.unwrap());
+        assert_approx!(0.9324, results.lauter_efficiency.unwrap());
+        assert_approx!(84., results.apparent_attenuation.measured.unwrap());
+        assert_approx!(5.5125, results.abv.measured.unwrap());
+        assert_approx!(0.5, results.post_boil_volume_liters.difference().unwrap());
+    }
+
+    #[test]
+    fn fermenting_session() {
+        let session = BrewSession {
+            final_gravity: None,
+            pre_boil_gravity: None,
+            ..session()
+        };
+        let results = session
+            .results(&recipe(), Gravity::from_specific_gravity(1.010))
+            .unwrap();
+        assert_eq!(None, results.final_gravity.measured);
+        assert_eq!(None, results.abv.difference());
+        assert_eq!(None, results.mash_efficiency);
+        assert_eq!(None, results.lauter_efficiency);
+        assert!(results.conversion_efficiency.is_some());
+        assert_approx!(80.0887, results.apparent_attenuation.predicted);
+    }
+
+    #[test]
+    fn invalid_session() {
+        let mut extract = recipe();
+        extract.fermentables.remove(0);
+        assert!(matches!(
+            session().results(&extract, Gravity::from_specific_gravity(1.010)),
+            Err(Error::NotPositive { .. })
+        ));
+        let session = BrewSession {
+            final_gravity: Some(1.060),
+            ..session()
+        };
+        assert!(matches!(
+            session.results(&recipe(), Gravity::from_specific_gravity(1.010)),
+            Err(Error::FinalAboveOriginalGravity { .. })
+        ));
+    }
+}
@@ -0,0 +1,175 @@
+//! Typed quantities used by the calculators
+//!
+//! Temperatures, volumes, masses, pressures, lengths and energies
+//! come from the [measurements](https://docs.rs/measurements) crate.
+//! Brewing specific quantities are newtypes so that the compiler
+//! catches mixed up units and argument order.
+//!
+//! With the `serde` feature all quantities serialize as plain numbers
+//! in the units their `as_*` accessor without a suffix returns:
+//! specific gravity, percent, IBU and SRM.
+//! ```
+//! use rustybeer::units::{Abv, Gravity};
+//! use rustybeer_util::assert_approx;
+//!
+//! let og = Gravity::from_specific_gravity(1.050);
+//! assert_approx!(50., og.as_points());
+//! assert_eq!(5.2, Abv::from_percent(5.2).as_percent());
+//! ```
+
+use std::fmt;
+
+pub use rustybeer_util::measurements::{Energy, Length, Mass, Pressure, Temperature, Volume};
+
+/// Specific gravity of wort or beer
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Gravity(f64);
+
+impl Gravity {
+    /// Creates gravity from specific gravity, e.g. 1.050
+    pub fn from_specific_gravity(specific_gravity: f64) -> Self {
+        Self(specific_gravity)
+    }
+
+    /// Creates gravity from gravity points, e.g. 50 for 1.050
+    pub fn from_points(points: f64) -> Self {
+        Self(1.0 + points / 1000.0)
+    }
+
+    /// Creates gravity from degrees Plato
+    pub fn from_plato(plato: f64) -> Self {
+        Self(1.0 + plato / (258.6 - (plato / 258.2) * 227.1))
+    }
+
+    pub fn as_specific_gravity(&self) -> f64 {
+        self.0
+    }
+
+    pub fn as_points(&self) -> f64 {
+        (self.0 - 1.0) * 1000.0
+    }
+
+    pub fn as_plato(&self) -> f64 {
+        -616.868 + 1111.14 * self.0 - 630.272 * self.0.powf(2.0) + 135.997 * self.0.powf(3.0)
+    }
+}
+
+impl fmt::Display for Gravity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.3}", self.0)
+    }
+}
+
+/// Alcohol by volume
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Abv(f64);
+
+impl Abv {
+    /// Creates alcohol by volume from percentage, e.g. 5.2 for 5.2%
+    pub fn from_percent(percent: f64) -> Self {
+        Self(percent)
+    }
+
+    pub fn as_percent(&self) -> f64 {
+        self.0
+    }
+}
+
+impl fmt::Display for Abv {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.1}%", self.0)
+    }
+}
+
+/// International Bittering Units
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Ibu(f64);
+
+impl Ibu {
+    pub fn new(ibu: f64) -> Self {
+        Self(ibu)
+    }
+
+    pub fn as_ibu(&self) -> f64 {
+        self.0
+    }
+}
+
+impl fmt::Display for Ibu {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.0} IBU", self.0)
+    }
+}
+
+/// Color of wort, beer or malt
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Color(f64);
+
+impl Color {
+    /// Creates color from Standard Reference Method (SRM)
+    pub fn from_srm(srm: f64) -> Self {
+        Self(srm)
+    }
+
+    /// Creates color from European Brewery Convention (EBC)
+    pub fn from_ebc(ebc: f64) -> Self {
+        Self(ebc / 1.97)
+    }
+
+    /// Creates color from degrees Lovibond
+    pub fn from_lovibond(lovibond: f64) -> Self {
+        Self(1.3546 * lovibond - 0.76)
+    }
+
+    pub fn as_srm(&self) -> f64 {
+        self.0
+    }
+
+    pub fn as_ebc(&self) -> f64 {
+        self.0 * 1.97
+    }
+
+    pub fn as_lovibond(&self) -> f64 {
+        (self.0 + 0.76) / 1.3546
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.1} SRM", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Color, Gravity};
+    use rustybeer_util::assert_approx;
+
+    #[test]
+    fn gravity_conversions() {
+        let gravity = Gravity::from_specific_gravity(1.048);
+        assert_approx!(48., gravity.as_points());
+        assert_approx!(11.9121, gravity.as_plato());
+        assert_approx!(
+            1.048,
+            Gravity::from_plato(gravity.as_plato()).as_specific_gravity()
+        );
+        assert_approx!(1.048, Gravity::from_points(48.).as_specific_gravity());
+    }
+
+    #[test]
+    fn color_conversions() {
+        let color = Color::from_srm(10.);
+        assert_approx!(19.7, color.as_ebc());
+        assert_approx!(10., Color::from_ebc(color.as_ebc()).as_srm());
+        assert_approx!(10., Color::from_lovibond(color.as_lovibond()).as_srm());
+    }
+}
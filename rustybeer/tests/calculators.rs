@@ -1,12 +1,18 @@
+use rustybeer::Error;
 use rustybeer_util::assert_approx;
 
 #[test]
 fn abv() {
     use rustybeer::calculators::abv::calculate_abv;
 
-    assert_approx!(1050., calculate_abv(10., 2.));
+    assert_approx!(1050., calculate_abv(10., 2.).unwrap());
 
-    assert_approx!(39.5548, calculate_abv(0.3026, 0.00123));
+    assert_approx!(39.5548, calculate_abv(0.3026, 0.00123).unwrap());
+
+    assert!(matches!(
+        calculate_abv(1.010, 1.050),
+        Err(Error::FinalAboveOriginalGravity { .. })
+    ));
 }
 
 #[test]
@@ -17,10 +23,10 @@ fn alcohol_volume_weight() {
     assert_approx!(0., calculate_abv_abw(0.));
     assert_approx!(400., calculate_abv_abw(500.));
 
-    assert_approx!(3.945, calculate_abv_abw_density(5., 1.));
-    assert_approx!(5., calculate_abv_abw_density(5., 0.789));
-    assert_approx!(0.789, calculate_abv_abw_density(5., 5.));
-    assert_approx!(100., calculate_abv_abw_density(5., 0.03945));
+    assert_approx!(3.945, calculate_abv_abw_density(5., 1.).unwrap());
+    assert_approx!(5., calculate_abv_abw_density(5., 0.789).unwrap());
+    assert_approx!(0.789, calculate_abv_abw_density(5., 5.).unwrap());
+    assert_approx!(100., calculate_abv_abw_density(5., 0.03945).unwrap());
 
     assert_approx!(6.25, calculate_abw_abv(5.));
     assert_approx!(0., calculate_abw_abv(0.));
@@ -56,26 +62,39 @@ fn alcohol_volume_weight() {
 fn boil_off() {
     use rustybeer::calculators::diluting::*;
 
-    assert_approx!(2., calculate_new_volume(2., 2., 2.));
+    assert_approx!(2., calculate_new_volume(2., 2., 2.).unwrap());
 
-    assert_approx!(15., calculate_new_volume(7., 5., 3.));
+    assert_approx!(15., calculate_new_volume(7., 5., 3.).unwrap());
 
-    assert_approx!(11., calculate_new_gravity(7., 5., 3.));
+    assert_approx!(11., calculate_new_gravity(7., 5., 3.).unwrap());
 
-    assert_approx!(69.5714, calculate_new_gravity(4., 3.2, 0.14));
+    assert_approx!(69.5714, calculate_new_gravity(4., 3.2, 0.14).unwrap());
 }
 
 #[test]
 fn diluting() {
     use rustybeer::calculators::diluting::*;
 
-    assert_approx!(14.1625, calculate_new_gravity(9.1, 5.2, 3.2));
+    assert_approx!(14.1625, calculate_new_gravity(9.1, 5.2, 3.2).unwrap());
+
+    assert_approx!(4.5305, calculate_new_gravity(9.1, 3.16, 7.25).unwrap());
 
-    assert_approx!(4.5305, calculate_new_gravity(9.1, 3.16, 7.25));
+    assert_approx!(2.0, calculate_new_volume(2., 2., 2.).unwrap());
 
-    assert_approx!(2.0, calculate_new_volume(2., 2., 2.));
+    assert_approx!(15., calculate_new_volume(7., 5., 3.).unwrap());
 
-    assert_approx!(15., calculate_new_volume(7., 5., 3.));
+    assert_eq!(
+        Err(Error::NotPositive {
+            name: "target volume",
+            value: 0.
+        }),
+        calculate_new_gravity(1.050, 20., 0.)
+    );
+
+    assert!(matches!(
+        calculate_new_volume(1.050, 20., 1.),
+        Err(Error::NotPositive { .. })
+    ));
 }
 
 #[test]
@@ -86,7 +105,7 @@ fn priming() {
 
     assert_approx!(2.4556, calculate_co2(12.45));
 
-    let stream = calculate_sugars(77., 5., 2.);
+    let stream = calculate_sugars(77., 5., 2.).unwrap();
 
     let expected = vec![
         Sugar::new(String::from("Table Sugar (sucrose)"), 24.850561000000013),
@@ -111,13 +130,140 @@ fn priming() {
     assert_eq!(expected, stream);
 }
 
+#[test]
+fn priming_single_sugar() {
+    use rustybeer::calculators::priming::{calculate_sucrose, calculate_sugar, calculate_syrup};
+    use rustybeer_util::sugars::{SugarForm, SUGARS};
+
+    assert_approx!(24.8506, calculate_sucrose(77., 5., 2.).unwrap());
+
+    let dextrose = SUGARS
+        .iter()
+        .find(|s| s.name == "Corn Sugar (dextrose)")
+        .unwrap();
+    let amount = calculate_sugar(77., 5., 2., dextrose).unwrap();
+    assert_eq!(SugarForm::Solid, amount.form);
+    assert_approx!(27.3083, amount.grams);
+    assert_approx!(42.0128, amount.milliliters);
+
+    assert_approx!(
+        273.0831,
+        calculate_syrup(77., 5., 2., dextrose, 100.).unwrap()
+    );
+}
+
+#[test]
+fn krausening() {
+    use rustybeer::calculators::priming::{calculate_krausen, calculate_speise};
+
+    assert_approx!(
+        1.1722,
+        calculate_speise(50., 20., 2.5, 1.048, 1.010).unwrap()
+    );
+
+    assert_approx!(
+        0.5251,
+        calculate_speise(50., 20., 1.8, 1.048, 1.010).unwrap()
+    );
+
+    assert_approx!(
+        0.,
+        calculate_speise(77., 20., 0.7575, 1.048, 1.010).unwrap()
+    );
+
+    assert_approx!(
+        3.2728,
+        calculate_krausen(50., 20., 2.5, 1.025, 1.010).unwrap()
+    );
+
+    assert_eq!(
+        Err(Error::Unreachable {
+            target: "CO2 volumes",
+            value: 2.5
+        }),
+        calculate_speise(50., 20., 2.5, 1.012, 1.010)
+    );
+}
+
 #[test]
 fn sg_correction() {
     use rustybeer::calculators::sg_correction::correct_sg;
 
-    assert_approx!(5.001, correct_sg(5.0, 2.9, 1.37));
+    assert_approx!(5.001, correct_sg(5.0, 2.9, 1.37).unwrap());
+
+    assert_approx!(7.3023, correct_sg(7.3, 8.1, 5.12).unwrap());
+
+    assert_approx!(7.4175, correct_sg(7.413, 28.1, 55.1212).unwrap());
+}
+
+#[test]
+fn forced_carbonation() {
+    use rustybeer::calculators::forced_carbonation::*;
+
+    assert_approx!(11.2462, calculate_pressure(38., 2.5).unwrap());
+
+    assert_approx!(3.4760, calculate_pressure(32., 2.0).unwrap());
+
+    assert_approx!(
+        2.5,
+        calculate_co2_volumes(38., calculate_pressure(38., 2.5).unwrap()).unwrap()
+    );
+
+    assert_approx!(
+        3.0,
+        calculate_co2_volumes(50., calculate_pressure(50., 3.0).unwrap()).unwrap()
+    );
+
+    assert_approx!(0., calculate_altitude_correction(0.).unwrap());
+
+    assert_approx!(2.4321, calculate_altitude_correction(1500.).unwrap());
+}
+
+#[test]
+fn draft_line() {
+    use rustybeer::calculators::draft_line::*;
+
+    assert_approx!(
+        3.3333,
+        calculate_line_length(12., 3., 2., STANDARD_FLOW_RATE).unwrap()
+    );
+
+    assert_approx!(
+        55.,
+        calculate_line_length(12., 0.2, 0., STANDARD_FLOW_RATE).unwrap()
+    );
+
+    assert_approx!(
+        13.3333,
+        calculate_line_length(12., 3., 2., STANDARD_FLOW_RATE / 2.).unwrap()
+    );
+
+    assert_eq!(
+        Err(Error::Unreachable {
+            target: "vertical rise (ft)",
+            value: 10.
+        }),
+        calculate_line_length(5., 3., 10., STANDARD_FLOW_RATE)
+    );
+
+    assert_approx!(12., calculate_resistance(3., STANDARD_FLOW_RATE * 2.));
+
+    assert_approx!(8., calculate_pour_time(480., 60.).unwrap());
+}
+
+#[test]
+fn yeast_viability() {
+    use rustybeer::calculators::yeast_viability::*;
+
+    assert_approx!(97., calculate_yv(0.).unwrap());
 
-    assert_approx!(7.3023, correct_sg(7.3, 8.1, 5.12));
+    assert_approx!(48.5, calculate_cc(50., 0.).unwrap());
 
-    assert_approx!(7.4175, correct_sg(7.413, 28.1, 55.1212));
+    assert_eq!(
+        Err(Error::Negative {
+            name: "days",
+            value: -1.
+        }),
+        calculate_yv(-1.)
+    );
 }
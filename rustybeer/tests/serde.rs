@@ -0,0 +1,116 @@
+#![cfg(feature = "serde")]
+
+use rustybeer::calculators::design::RecipeDesign;
+use rustybeer::calculators::ibu::{HopAddition, HopAdditionType};
+use rustybeer::calculators::num_bottles::{calculate_packing, BottleStock, PackingPlan};
+use rustybeer::calculators::priming::{calculate_sugars, Sugar};
+use rustybeer::recipe::{FermentableType, Recipe};
+use rustybeer::units::{Gravity, Temperature};
+use rustybeer_util::containers::Container;
+
+#[test]
+fn quantities() {
+    assert_eq!(
+        "1.05",
+        serde_json::to_string(&Gravity::from_specific_gravity(1.05)).unwrap()
+    );
+    assert_eq!(
+        Gravity::from_specific_gravity(1.048),
+        serde_json::from_str("1.048").unwrap()
+    );
+
+    let temperature = Temperature::from_celsius(20.);
+    let json = serde_json::to_string(&temperature).unwrap();
+    let parsed: Temperature = serde_json::from_str(&json).unwrap();
+    assert_eq!(temperature.as_celsius(), parsed.as_celsius());
+}
+
+#[test]
+fn hop_addition() {
+    let hop: HopAddition = serde_json::from_str(
+        r#"{"weight_grams": 28.0, "alpha_acid_percentage": 0.064, "time_mins": 45}"#,
+    )
+    .unwrap();
+    assert_eq!(28.0, hop.weight_grams);
+    assert!(matches!(hop.hop_type, HopAdditionType::Whole));
+
+    let json =
+        serde_json::to_string(&HopAddition::new(7., 0.085, 15, HopAdditionType::Pellet)).unwrap();
+    let parsed: HopAddition = serde_json::from_str(&json).unwrap();
+    assert!(matches!(parsed.hop_type, HopAdditionType::Pellet));
+    assert_eq!(15, parsed.time_mins);
+}
+
+#[test]
+fn results() {
+    let sugars = calculate_sugars(77., 5., 2.).unwrap();
+    let json = serde_json::to_string(&sugars).unwrap();
+    assert_eq!(sugars, serde_json::from_str::<Vec<Sugar>>(&json).unwrap());
+
+    let stock = vec![BottleStock::new(
+        Container::new("330ml bottle".to_string(), 330.0),
+        24,
+    )];
+    let plan = calculate_packing(1_000.0, 0.0, 0.0, &stock).unwrap();
+    let json = serde_json::to_string(&plan).unwrap();
+    assert_eq!(plan, serde_json::from_str::<PackingPlan>(&json).unwrap());
+}
+
+#[test]
+fn recipe() {
+    let recipe: Recipe = serde_json::from_str(
+        r#"{
+            "name": "Pale Ale",
+            "batch_volume_liters": 20.0,
+            "efficiency": 0.75,
+            "fermentables": [
+                {"name": "Pale Malt", "weight_grams": 4500.0, "potential": 1.037},
+                {"name": "Dextrose", "weight_grams": 250.0, "potential": 1.046, "type": "sugar"}
+            ],
+            "hops": [
+                {"name": "Cascade", "weight_grams": 28.0, "alpha_acid_percentage": 0.064, "time_mins": 45}
+            ]
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        FermentableType::Grain,
+        recipe.fermentables[0].fermentable_type
+    );
+    assert_eq!(
+        FermentableType::Sugar,
+        recipe.fermentables[1].fermentable_type
+    );
+    assert_eq!("Cascade", recipe.hops[0].name);
+    assert_eq!(45, recipe.hops[0].addition.time_mins);
+    assert!(recipe.salts.is_empty());
+
+    let json = serde_json::to_string(&recipe).unwrap();
+    let parsed: Recipe = serde_json::from_str(&json).unwrap();
+    assert_eq!(recipe.fermentables, parsed.fermentables);
+    assert_eq!(28.0, parsed.hops[0].addition.weight_grams);
+}
+
+#[test]
+fn recipe_design() {
+    let design: RecipeDesign = serde_json::from_str(
+        r#"{
+            "name": "Pale Ale",
+            "batch_volume_liters": 20.0,
+            "efficiency": 0.75,
+            "original_gravity": 1.052,
+            "ibu": 35.0,
+            "grains": [
+                {"name": "Pale Malt", "percent": 90.0, "potential": 1.037, "color_lovibond": 3.0},
+                {"name": "Crystal 40", "weight_grams": 300.0, "potential": 1.034, "color_lovibond": 40.0}
+            ],
+            "hops": [
+                {"name": "Centennial", "ibu_percent": 100.0, "alpha_acid_percentage": 0.085, "time_mins": 60}
+            ]
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(None, design.color_srm);
+    assert_eq!(Some(300.0), design.grains[1].weight_grams);
+    assert!(design.solve().is_ok());
+}